@@ -0,0 +1,85 @@
+//! Benchmarks of the check of a synthetic tree of 1 000 Markdown files, sequentially and on several threads
+
+use broken_md_links::{check_broken_links_simple, CheckerOptions};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::path::PathBuf;
+
+/// Number of directories of the synthetic tree
+const DIRS: usize = 20;
+
+/// Number of files in each directory of the synthetic tree
+const FILES_PER_DIR: usize = 50;
+
+/// Create a tree of Markdown files linking to the headers of each other, a few links being broken
+fn synthetic_tree() -> PathBuf {
+    let root = std::env::temp_dir().join("broken-md-links-bench-check");
+    let _ = fs::remove_dir_all(&root);
+
+    for dir in 0..DIRS {
+        fs::create_dir_all(root.join(format!("dir-{}", dir))).unwrap();
+
+        for file in 0..FILES_PER_DIR {
+            let mut content = format!("# File {}\n\n", file);
+
+            for section in 0..10 {
+                content.push_str(&format!(
+                    "## Section {}\n\nSee [the next file](../dir-{}/file-{}.md#section-{}) and [this section](#section-{}).\n\n",
+                    section,
+                    (dir + 1) % DIRS,
+                    (file + section) % FILES_PER_DIR,
+                    section,
+                    section
+                ));
+            }
+
+            if file % 10 == 0 {
+                content.push_str("[Broken](missing.md)\n");
+            }
+
+            fs::write(root.join(format!("dir-{}/file-{}.md", dir, file)), content).unwrap();
+        }
+    }
+
+    root
+}
+
+fn check(c: &mut Criterion) {
+    let root = synthetic_tree();
+
+    let modes = [
+        ("sequential", CheckerOptions::default()),
+        (
+            "max_concurrent_dirs",
+            CheckerOptions {
+                max_concurrent_dirs: 4,
+                ..Default::default()
+            },
+        ),
+        (
+            "parallel",
+            CheckerOptions {
+                parallel: true,
+                ..Default::default()
+            },
+        ),
+    ];
+
+    let mut group = c.benchmark_group("check_1000_files");
+    group.sample_size(10);
+
+    for (name, options) in modes {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let errors = check_broken_links_simple(&root, &options).unwrap();
+
+                assert_eq!(errors.len(), DIRS * FILES_PER_DIR / 10);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, check);
+criterion_main!(benches);
@@ -0,0 +1,97 @@
+//! Peak memory usage of the check of a synthetic corpus of large Markdown files
+//!
+//! Run it with and without the `mmap` feature to compare them:
+//!
+//! ```shell
+//! cargo bench --bench memory
+//! cargo bench --bench memory --features mmap
+//! ```
+//!
+//! The peak resident set size is read from `/proc`, so this benchmark only reports it on Linux.
+
+use broken_md_links::{check_broken_links_simple, CheckerOptions};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Number of files of the synthetic corpus
+const FILES: usize = 8;
+
+/// Number of sections in each file of the synthetic corpus (about 100 bytes each)
+const SECTIONS_PER_FILE: usize = 60_000;
+
+/// Create a corpus of large Markdown files linking to the headers of each other
+fn synthetic_corpus() -> PathBuf {
+    let root = std::env::temp_dir().join("broken-md-links-bench-memory");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    for file in 0..FILES {
+        let mut content = format!("# File {}\n\n", file);
+
+        for section in 0..SECTIONS_PER_FILE {
+            content.push_str(&format!(
+                "## Section {}\n\nSee [the next file](file-{}.md#section-{}) for more details.\n\n",
+                section,
+                (file + 1) % FILES,
+                section
+            ));
+        }
+
+        fs::write(root.join(format!("file-{}.md", file)), content).unwrap();
+    }
+
+    root
+}
+
+/// Get the peak resident set size of the process, in kilobytes
+fn peak_rss_kb() -> Option<u64> {
+    fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn main() {
+    // Only run when benchmarks are requested, not as part of `cargo test --benches`
+    if !std::env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+
+    let root = synthetic_corpus();
+    let size: u64 = fs::read_dir(&root)
+        .unwrap()
+        .map(|entry| entry.unwrap().metadata().unwrap().len())
+        .sum();
+
+    let before = peak_rss_kb();
+    let started = Instant::now();
+
+    let errors = check_broken_links_simple(&root, &CheckerOptions::default()).unwrap();
+    assert!(errors.is_empty());
+
+    println!(
+        "check_{}_files ({} MB, mmap {}): {:.2?}",
+        FILES,
+        size / 1024 / 1024,
+        if cfg!(feature = "mmap") {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        started.elapsed()
+    );
+
+    match (before, peak_rss_kb()) {
+        (Some(before), Some(after)) => println!(
+            "peak RSS: {} KB before the check, {} KB after it",
+            before, after
+        ),
+        _ => println!("peak RSS: not available on this platform"),
+    }
+}
@@ -0,0 +1,33 @@
+//! Attributes to control how [broken-md-links](https://github.com/ClementNerma/broken-md-links) checks
+//! the doc comments of Rust items (with its `--rust-doc-comments` option)
+//!
+//! ```
+//! use broken_md_links_derive::broken_links_ignore;
+//!
+//! /// See the [internal notes](../notes/parser.md), which are not published.
+//! #[broken_links_ignore]
+//! pub fn parse() {}
+//! ```
+
+use proc_macro::TokenStream;
+
+/// Exclude the links of an item's doc comments from the check
+///
+/// The checker recognizes this attribute in the source files, and the item's documentation is prefixed
+///  with a `<!-- broken-md-links: ignore -->` comment (which rustdoc doesn't render) so tools processing
+///  the generated documentation can do the same.
+#[proc_macro_attribute]
+pub fn broken_links_ignore(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return "compile_error!(\"the `broken_links_ignore` attribute doesn't take any argument\");"
+            .parse()
+            .unwrap();
+    }
+
+    let mut output: TokenStream = "#[doc = \"<!-- broken-md-links: ignore -->\"]"
+        .parse()
+        .unwrap();
+
+    output.extend(item);
+    output
+}
@@ -0,0 +1,64 @@
+//! Results of checks, with the number of files and links checked (see [`check_broken_links_report`](crate::check_broken_links_report))
+
+use crate::{BrokenLinkKind, CheckerError, DetectedBrokenLink, Severity};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Number of files and links checked (see [`TargetsCache::take_check_stats`](crate::TargetsCache::take_check_stats))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CheckStats {
+    /// Number of files checked
+    pub files: usize,
+
+    /// Number of links checked in these files
+    pub links: usize,
+}
+
+/// Findings of a check, with the number of files and links it checked (see [`check_broken_links_report`](crate::check_broken_links_report))
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CheckResult {
+    /// Broken and invalid links found, including warnings
+    pub findings: Vec<DetectedBrokenLink>,
+
+    /// Number of files and links checked
+    pub stats: CheckStats,
+}
+
+impl CheckResult {
+    /// Get the number of broken links found (warnings and informational findings excluded)
+    pub fn broken_count(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| {
+                finding.severity == Severity::Error && finding.kind != BrokenLinkKind::Warning
+            })
+            .count()
+    }
+
+    /// Turn the result into an error if it contains broken links, so they can be propagated with `?`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use broken_md_links::{BrokenLinkKind, CheckResult, CheckerError, DetectedBrokenLink, Severity};
+    ///
+    /// assert!(CheckResult::default().into_result().is_ok());
+    ///
+    /// let finding = DetectedBrokenLink {
+    ///     file: "README.md".into(), line: 3, cell: None, column: None, link_text: None, kind: BrokenLinkKind::MissingTarget,
+    ///     severity: Severity::Error, error: "broken link found: path 'guide.md' does not exist".into(), suggestion: None, target: None,
+    /// };
+    /// let result = CheckResult { findings: vec![finding], ..CheckResult::default() };
+    ///
+    /// assert!(matches!(result.into_result(), Err(CheckerError::BrokenLinks(result)) if result.broken_count() == 1));
+    /// ```
+    pub fn into_result(self) -> Result<Self, CheckerError> {
+        if self.broken_count() == 0 {
+            Ok(self)
+        } else {
+            Err(CheckerError::BrokenLinks(self))
+        }
+    }
+}
@@ -0,0 +1,1532 @@
+//! Command-line interface of the checker
+//!
+//! This module allows embedding the command-line tool in other programs (e.g. documentation site generators)
+//!  without spawning a subprocess.
+
+use crate::directory_config::{
+    ConfigOverrides, DirectoryConfigs, GitignorePatterns, CONFIG_FILE_NAME,
+};
+use crate::hooks::{install_hook, uninstall_hook, ExistingHook, GitHook};
+use crate::incremental::{check_broken_links_incremental, IncrementalState};
+use crate::link_graph::analyze_link_graph;
+use crate::output::write_line;
+use crate::partials::check_checked_partial_links;
+use crate::report_diff::{diff_reports, github_annotations, load_report, sarif_report, JsonReport};
+use crate::{
+    broken_targets, check_path_with_callback, dedupe_findings, find_unreachable_files,
+    format_location, group_by_target, load_anchor_aliases, load_links_cache,
+    load_placeholder_variables, safe_canonicalize, save_links_cache, AnchorAliases, BrokenLinkKind,
+    CheckerOptions, EmojiHandling, FileLinksCache, InvalidUtf8Handling, LinksCache,
+    MathSlugHandling, PlaceholderOptions, SchemeHandlers, SchemeVerdict, Severity, SlugAlgorithm,
+    SlugOptions, TargetsCache,
+};
+use clap::Clap;
+use colored::Colorize;
+use fern::colors::{Color, ColoredLevelConfig};
+use log::{debug, error, info, warn, Level, LevelFilter};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Name of the program, used as the first argument when parsing the command-line arguments
+static PROGRAM_NAME: &str = "broken-md-links";
+
+/// Exit code used when the timeout expired before all files were checked (like the `timeout` command)
+static TIMEOUT_EXIT_CODE: u8 = 124;
+
+/// Whether messages are written to the standard error instead of the standard output (which is then used for the report)
+static LOG_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Subcommand installing a git hook which runs the checker
+#[derive(Clap)]
+#[clap(
+    about = "Install a git hook checking the links of the Markdown files of the current repository"
+)]
+struct InstallHookCommand {
+    #[clap(long = "hook", possible_values=&["pre-commit", "pre-push"], default_value="pre-commit",
+           about = "Hook to install ('pre-commit' checks the staged files, 'pre-push' checks the whole repository)")]
+    pub hook: String,
+
+    #[clap(long = "force", about = "Replace the hook if it already exists")]
+    pub force: bool,
+
+    #[clap(
+        long = "append",
+        conflicts_with = "force",
+        about = "Add the check at the end of the hook if it already exists"
+    )]
+    pub append: bool,
+}
+
+/// Subcommand removing the checker from a git hook
+#[derive(Clap)]
+#[clap(
+    about = "Remove the check added by 'install-hook' from a git hook of the current repository"
+)]
+struct UninstallHookCommand {
+    #[clap(long = "hook", possible_values=&["pre-commit", "pre-push"], default_value="pre-commit",
+           about = "Hook to remove the check from")]
+    pub hook: String,
+}
+
+/// Subcommand comparing two reports
+#[derive(Clap)]
+#[clap(
+    about = "Compare two reports written with '--format json', showing the new, fixed and persisting findings"
+)]
+struct DiffCommand {
+    #[clap(index = 1, about = "Old report")]
+    pub old: String,
+
+    #[clap(index = 2, about = "New report")]
+    pub new: String,
+
+    #[clap(long = "format", possible_values=&["text", "markdown"], default_value="text",
+           about = "Format of the comparison ('markdown' can be posted as a pull request comment)")]
+    pub format: String,
+}
+
+/// Subcommand writing the JSON Schema of the reports
+#[derive(Clap)]
+#[clap(about = "Write the JSON Schema of the reports written with '--format json'")]
+struct SchemaCommand {}
+
+/// Command
+#[derive(Clap)]
+#[clap(
+    version = "1.0",
+    author = "Clément Nerma <clement.nerma@gmail.com>",
+    about = "Detect broken links in markdown files"
+)]
+struct Command {
+    #[clap(index = 1, about = "Input file or directory")]
+    pub input: String,
+
+    #[clap(
+        short = 'r',
+        long = "recursive",
+        about = "Check all files in the input directory"
+    )]
+    pub recursive: bool,
+
+    #[clap(
+        long = "ignore-header-links",
+        about = "Do not check if headers are valids in links (e.g. 'document.md#some-header')"
+    )]
+    pub ignore_header_links: bool,
+
+    #[clap(short = 'v', long = "verbosity", possible_values=&["silent", "errors", "warn", "info", "verbose", "debug"],
+           default_value="warn", about = "Verbosity level")]
+    pub verbosity: String,
+
+    #[clap(long = "format", alias = "output-format", possible_values=&["auto", "text", "json", "sarif", "github"], default_value="auto",
+           about = "Format of the report ('json', 'sarif' and 'github' write the broken links to the standard output, and messages to the standard error), 'auto' being 'github' in GitHub Actions and 'text' otherwise")]
+    pub format: String,
+
+    #[clap(short = 'f', long = "only-files", about = "Only accept links to files")]
+    pub only_files: bool,
+
+    #[clap(
+        long = "no-error",
+        about = "Convert all broken/invalid links errors to warnings"
+    )]
+    pub no_error: bool,
+
+    #[clap(
+        long = "check-mailto",
+        about = "Validate the address(es) of 'mailto:' links"
+    )]
+    pub check_mailto: bool,
+
+    #[clap(
+        long = "scheme-regex",
+        parse(try_from_str = parse_scheme_regex),
+        about = "Pattern the links with a custom URI scheme must match, as 'scheme=regex' (e.g. 'jira=^jira://[A-Z]+-\\d+$', can be used multiple times)"
+    )]
+    pub scheme_regexes: Vec<(String, Regex)>,
+
+    #[clap(
+        long = "check-path-style",
+        about = "Report links whose target is not written in its shortest relative form"
+    )]
+    pub check_path_style: bool,
+
+    #[clap(
+        long = "check-extension-case",
+        about = "Report links whose target's extension is not written with the same casing as on disk"
+    )]
+    pub check_extension_case: bool,
+
+    #[clap(
+        long = "max-link-length",
+        about = "Warn about local link targets longer than this number of characters"
+    )]
+    pub max_link_length: Option<usize>,
+
+    #[clap(
+        long = "require-alt-text",
+        about = "Warn about images whose alternative text is empty"
+    )]
+    pub require_alt_text: bool,
+
+    #[clap(
+        long = "check-image-dimensions",
+        about = "Warn about images which can't be decoded in the format their extension stands for"
+    )]
+    pub check_image_dimensions: bool,
+
+    #[clap(
+        long = "ignore-images",
+        alias = "no-check-images",
+        about = "Do not check that the targets of images exist"
+    )]
+    pub ignore_images: bool,
+
+    #[clap(
+        long = "ignore-unresolved-references",
+        about = "Do not report links without a target (like '[link name]' without a definition), for documents using square brackets as plain text"
+    )]
+    pub ignore_unresolved_references: bool,
+
+    #[clap(
+        long = "ignore-html-blocks",
+        about = "Do not look for Markdown links inside raw HTML blocks"
+    )]
+    pub ignore_html_blocks: bool,
+
+    #[clap(
+        long = "check-toc",
+        about = "Warn about headers missing from the Table of Contents of files which have one"
+    )]
+    pub check_toc: bool,
+
+    #[clap(
+        long = "check-mdbook-includes",
+        about = "Check the files and anchors included with mdBook's '{{#include}}', '{{#rustdoc_include}}' and '{{#playground}}' directives"
+    )]
+    pub check_mdbook_includes: bool,
+
+    #[clap(
+        long = "check-nav-order",
+        about = "Report entries of this navigation file (e.g. 'SUMMARY.md') which are not ordered like the numeric prefixes of their targets' names"
+    )]
+    pub check_nav_order: Option<String>,
+
+    #[clap(long = "nav-order-severity", possible_values=&["error", "warn"], default_value="error",
+           about = "How the entries reported by '--check-nav-order' are reported")]
+    pub nav_order_severity: String,
+
+    #[clap(
+        long = "check-redirects",
+        about = "Warn about links to files which are redirects (with a 'redirect_to' key in their front matter, see '--redirect-marker')"
+    )]
+    pub check_redirects: bool,
+
+    #[clap(
+        long = "redirect-marker",
+        requires = "check-redirects",
+        about = "Front matter key indicating that a file is a redirect, for '--check-redirects' (can be used multiple times)"
+    )]
+    pub redirect_markers: Vec<String>,
+
+    #[clap(
+        long = "respect-drafts",
+        about = "Warn about links to files which won't be published (with 'draft: true', 'publish: false', 'published: false' or 'hidden: true' in their front matter, see '--draft-marker')"
+    )]
+    pub respect_drafts: bool,
+
+    #[clap(
+        long = "draft-marker",
+        requires = "respect-drafts",
+        parse(try_from_str = parse_define),
+        about = "Front matter key and value marking a file as unpublished, as 'key=value' (e.g. 'draft=true'), for '--respect-drafts' (can be used multiple times)"
+    )]
+    pub draft_markers: Vec<(String, String)>,
+
+    #[clap(
+        long = "require-frontmatter-field",
+        about = "Report Markdown files whose front matter doesn't have this field (can be used multiple times)"
+    )]
+    pub frontmatter_required_fields: Vec<String>,
+
+    #[clap(
+        long = "anchor-aliases",
+        about = "TOML file mapping renamed anchors ('path#old') to their new name ('path#new'), to accept links to the old ones"
+    )]
+    pub anchor_aliases: Option<String>,
+
+    #[clap(
+        long = "warn-anchor-aliases",
+        about = "Warn about links resolved through an anchor alias, so they can be updated"
+    )]
+    pub warn_anchor_aliases: bool,
+
+    #[clap(long = "anchor-case-severity", possible_values=&["error", "warn"], default_value="error",
+           about = "How links to a header whose slug only differs from an existing anchor by its casing are reported")]
+    pub anchor_case_severity: String,
+
+    #[clap(long = "anchor-punctuation-severity", possible_values=&["error", "warn"], default_value="error",
+           about = "How links to a header whose slug only differs from an existing anchor by repeated, leading or trailing dashes are reported")]
+    pub anchor_punctuation_severity: String,
+
+    #[clap(long = "anchor-plural-severity", possible_values=&["error", "warn"], default_value="error",
+           about = "How links to a header whose slug is the plural or singular form of an existing anchor are reported")]
+    pub anchor_plural_severity: String,
+
+    #[clap(long = "link-text-is-url", possible_values=&["info", "warn"],
+           about = "Report links whose text is their destination (e.g. '[https://example.com](https://example.com)'), with this severity")]
+    pub link_text_is_url: Option<String>,
+
+    #[clap(
+        long = "define",
+        parse(try_from_str = parse_define),
+        about = "Value of a placeholder of link destinations, as 'name=value' (can be used multiple times)"
+    )]
+    pub defines: Vec<(String, String)>,
+
+    #[clap(
+        long = "variables",
+        about = "TOML file whose '[variables]' table provides the value of placeholders ('--define' takes precedence)"
+    )]
+    pub variables_file: Option<String>,
+
+    #[clap(
+        long = "placeholder-syntax",
+        parse(try_from_str = parse_placeholder_syntax),
+        about = "Syntax of placeholders, with 'name' standing for their name (can be used multiple times, defaults to '{{name}}' and '${name}')"
+    )]
+    pub placeholder_syntaxes: Vec<(String, String)>,
+
+    #[clap(long = "unresolved-placeholders", possible_values=&["error", "warn"],
+           about = "How links containing placeholders which have no value are reported (defaults to 'error')")]
+    pub unresolved_placeholders: Option<String>,
+
+    #[clap(
+        long = "lenient-space-destinations",
+        about = "Check links whose destination contains unescaped spaces (e.g. '[Setup](my setup.md)') if their target exists, with a warning"
+    )]
+    pub lenient_space_destinations: bool,
+
+    #[clap(
+        long = "dedupe-findings",
+        about = "Report identical broken links of a file only once, with the lines they are located at"
+    )]
+    pub dedupe_findings: bool,
+
+    #[clap(
+        long = "group-by-target",
+        about = "Report links to missing targets once per target, with the locations of the links (also grouped in JSON reports)"
+    )]
+    pub group_by_target: bool,
+
+    #[clap(
+        long = "top-broken-targets",
+        about = "List the N missing targets with the most links pointing to them at the end of the output, to prioritize fixes"
+    )]
+    pub top_broken_targets: Option<usize>,
+
+    #[clap(
+        long = "rust-doc-comments",
+        about = "Also check the Markdown located in the doc comments of Rust source files ('.rs')"
+    )]
+    pub rust_doc_comments: bool,
+
+    #[clap(
+        long = "error-on-warn",
+        about = "Report all warnings as errors (e.g. headers without a title or skipped files)"
+    )]
+    pub error_on_warn: bool,
+
+    #[clap(
+        long = "no-ignore",
+        about = "Also check files ignored by '.gitignore' and '.ignore' files"
+    )]
+    pub no_ignore: bool,
+
+    #[clap(
+        long = "targets-only",
+        about = "Don't check the files matching this '.gitignore' pattern (relative to the input directory), which remain valid link targets (can be used multiple times)"
+    )]
+    pub targets_only: Vec<String>,
+
+    #[clap(
+        long = "follow-symlinks",
+        about = "Follow symbolic links when checking a directory"
+    )]
+    pub follow_symlinks: bool,
+
+    #[clap(
+        long = "halt-on-io-error",
+        about = "Fail as soon as a file or directory can't be read, instead of reporting it and checking the other files"
+    )]
+    pub halt_on_io_error: bool,
+
+    #[clap(
+        long = "max-file-size",
+        default_value = "33554432",
+        about = "Skip files larger than this number of bytes (0 for no limit)"
+    )]
+    pub max_file_size: u64,
+
+    #[clap(
+        long = "changelog-mode",
+        about = "In changelogs, check that links named after an issue number reference an existing local issue"
+    )]
+    pub changelog_mode: bool,
+
+    #[clap(
+        long = "issues-dir",
+        about = "Directory containing the local issues, relative to the changelog (defaults to 'issues')"
+    )]
+    pub issues_dir: Option<String>,
+
+    #[clap(
+        long = "max-concurrent-dirs",
+        default_value = "1",
+        about = "Maximum number of directories checked at the same time"
+    )]
+    pub max_concurrent_dirs: usize,
+
+    #[clap(
+        long = "parallel",
+        about = "Check files on all available threads, whatever their directory"
+    )]
+    pub parallel: bool,
+
+    #[clap(
+        long = "watch",
+        about = "After the check, check the Markdown files again when they are created or modified, until Ctrl-C is pressed (requires the 'watch' feature)"
+    )]
+    pub watch: bool,
+
+    #[clap(
+        long = "check-http",
+        conflicts_with = "incremental",
+        about = "Check that HTTP and HTTPS links are reachable, reporting the ones answering with a 4xx or 5xx status (requires the 'check-http' feature)"
+    )]
+    pub check_http: bool,
+
+    #[clap(
+        long = "follow-redirects",
+        requires = "check-http",
+        about = "Follow the redirects of HTTP and HTTPS links instead of reporting them with a warning"
+    )]
+    pub follow_redirects: bool,
+
+    #[clap(
+        long = "http-timeout",
+        requires = "check-http",
+        parse(try_from_str = parse_duration),
+        about = "Timeout of the requests sent to check HTTP and HTTPS links (e.g. '30s' or '500ms', 10 seconds by default)"
+    )]
+    pub http_timeout: Option<Duration>,
+
+    #[clap(
+        long = "timeout",
+        parse(try_from_str = parse_duration),
+        about = "Stop checking files after this duration (e.g. '90s', '2m' or '500ms'), reporting incomplete results with exit code 124"
+    )]
+    pub timeout: Option<Duration>,
+
+    #[clap(
+        long = "profile",
+        about = "Write the time spent checking each file to a JSON file (see '--profile-output')"
+    )]
+    pub profile: bool,
+
+    #[clap(
+        long = "profile-output",
+        default_value = "broken-md-links-profile.json",
+        about = "File the timing data of '--profile' is written to"
+    )]
+    pub profile_output: String,
+
+    #[clap(long = "invalid-utf8", possible_values=&["skip", "lossy", "error"], default_value="lossy",
+           about = "How Markdown files which are not valid UTF-8 are handled")]
+    pub invalid_utf8: String,
+
+    #[clap(long = "emojis", possible_values=&["strip", "transliterate"],
+           about = "How emojis in headers are handled when generating slugs (defaults to 'strip')")]
+    pub emojis: Option<String>,
+
+    #[clap(long = "math-in-slugs", possible_values=&["strip", "raw", "plain"],
+           about = "How math formulas in headers are handled when generating slugs ('plain' converts them to plain text, defaults to 'strip')")]
+    pub math_in_slugs: Option<String>,
+
+    #[clap(long = "slug-algorithm", possible_values=&["default", "github", "gitlab", "mdbook", "mkdocs"],
+           about = "Generate slugs like the provided rendering platform does (defaults to the checker's own algorithm)")]
+    pub slug_algorithm: Option<String>,
+
+    #[clap(
+        long = "cache-file",
+        about = "Load the headers cache from this file (if it exists) and write it back after checking"
+    )]
+    pub cache_file: Option<String>,
+
+    #[clap(
+        long = "write-cache-only",
+        requires = "cache-file",
+        about = "Only generate the headers of all Markdown files and write them to the cache file, without checking links"
+    )]
+    pub write_cache_only: bool,
+
+    #[clap(
+        long = "incremental",
+        about = "Only check files which changed (or whose targets changed) since the previous run, using this state file"
+    )]
+    pub incremental: Option<String>,
+
+    #[clap(
+        long = "report-unreachable",
+        requires = "recursive",
+        about = "List Markdown files which are not reachable from any entry point by following links"
+    )]
+    pub report_unreachable: bool,
+
+    #[clap(
+        long = "entry-point",
+        about = "Entry point for '--report-unreachable' and '--graph-analysis' (defaults to the input directory's 'README.md', and 'index.md' for the latter), can be used multiple times"
+    )]
+    pub entry_points: Vec<String>,
+
+    #[clap(
+        long = "no-config-files",
+        about = "Don't read the 'broken-md-links.toml' configuration files overriding options for the files of their directory"
+    )]
+    pub no_config_files: bool,
+
+    #[clap(
+        long = "config-root",
+        conflicts_with = "no-config-files",
+        about = "Directory to search the configuration files in (defaults to the input directory, or to the input file's directory)"
+    )]
+    pub config_root: Option<String>,
+
+    #[clap(
+        long = "print-config",
+        conflicts_with = "no-config-files",
+        about = "Print the options overridable by configuration files which apply to this file, then exit without checking anything"
+    )]
+    pub print_config: Option<String>,
+
+    #[clap(
+        long = "graph-analysis",
+        requires = "recursive",
+        conflicts_with = "incremental",
+        about = "Report clusters of Markdown files unreachable from the entry points, circular references and dead-end chains"
+    )]
+    pub graph_analysis: bool,
+
+    #[clap(
+        long = "partial-dirs",
+        requires = "recursive",
+        use_delimiter = true,
+        about = "Names of the directories containing partials (e.g. '_includes,_partials'), whose relative links must also resolve from the pages including them"
+    )]
+    pub partial_dirs: Vec<String>,
+
+    #[clap(long = "partial-context-severity", possible_values=&["error", "warn", "info"], default_value="error",
+           about = "How the links reported by '--partial-dirs' are reported")]
+    pub partial_context_severity: String,
+
+    #[clap(long = "graph-analysis-severity", possible_values=&["info", "warn", "error"], default_value="info",
+           about = "Severity of the findings of '--graph-analysis'")]
+    pub graph_analysis_severity: String,
+}
+
+/// Start the logger, hiding every message whose level is under the provided one
+/// Only messages with a level greater than or equal to the provided 'level' will be displayed
+/// If a logger was already started (e.g. by a previous run), only its level and output are updated
+fn logger(level: LevelFilter, to_stderr: bool) {
+    LOG_TO_STDERR.store(to_stderr, Ordering::Relaxed);
+
+    // Create color scheme
+    let colors_line = ColoredLevelConfig::new()
+        .error(Color::Red)
+        .warn(Color::Yellow)
+        .info(Color::Green)
+        .debug(Color::Cyan)
+        .trace(Color::Blue);
+
+    // Get instant
+    let started = Instant::now();
+
+    // Build the logger
+    fern::Dispatch::new()
+        .format(move |out, message, record| {
+            let elapsed = started.elapsed();
+            let secs = elapsed.as_secs();
+
+            out.finish(format_args!(
+                "{}[{: >2}m {: >2}.{:03}s] {}: {}",
+                format_args!(
+                    "\x1B[{}m",
+                    colors_line.get_color(&record.level()).to_fg_str()
+                ),
+                secs / 60,
+                secs % 60,
+                elapsed.subsec_millis(),
+                match record.level() {
+                    Level::Info => "INFO",
+                    Level::Warn => "WARNING",
+                    Level::Error => "ERROR",
+                    Level::Debug => "VERBOSE",
+                    Level::Trace => "DEBUG",
+                },
+                format!("{}", message).red()
+            ))
+        })
+        .level(level)
+        .chain(fern::Output::call(|record| {
+            write_line(
+                record.args().to_string(),
+                LOG_TO_STDERR.load(Ordering::Relaxed),
+            )
+        }))
+        .apply()
+        .unwrap_or_else(|_| log::set_max_level(level))
+}
+
+/// Run the command-line tool with the provided arguments (without the program's name)
+///
+/// Messages are displayed the same way as with the command-line tool. The returned exit code is a failure
+///  if the arguments are invalid, if an error occurred or if broken links were found (unless `--no-error` is provided).
+///
+/// # Examples
+///
+/// ```
+/// use std::process::ExitCode;
+/// use broken_md_links::cli::run;
+///
+/// assert_eq!(run(&["README.md", "-v", "silent"]), ExitCode::SUCCESS);
+/// assert_eq!(run(&["missing.md", "-v", "silent"]), ExitCode::FAILURE);
+/// assert_eq!(run(&["README.md", "--unknown-flag"]), ExitCode::FAILURE);
+/// ```
+pub fn run(args: &[&str]) -> ExitCode {
+    // Hooks and reports are managed with subcommands, which are parsed separately as the checker's input is a positional argument
+    match args.split_first() {
+        Some((&"install-hook", args)) => {
+            return match parse_args::<InstallHookCommand>("install-hook", args) {
+                Ok(args) => run_hook_command(&args.hook, Some(&args)),
+                Err(code) => code,
+            }
+        }
+        Some((&"uninstall-hook", args)) => {
+            return match parse_args::<UninstallHookCommand>("uninstall-hook", args) {
+                Ok(args) => run_hook_command(&args.hook, None),
+                Err(code) => code,
+            }
+        }
+        Some((&"diff", args)) => {
+            return match parse_args::<DiffCommand>("diff", args) {
+                Ok(args) => run_diff_command(&args),
+                Err(code) => code,
+            }
+        }
+        Some((&"schema", args)) => {
+            return match parse_args::<SchemaCommand>("schema", args) {
+                Ok(_) => run_schema_command(),
+                Err(code) => code,
+            }
+        }
+        _ => {}
+    }
+
+    let mut args = match parse_args::<Command>("", args) {
+        Ok(args) => args,
+        Err(code) => return code,
+    };
+
+    if args.format == "auto" {
+        args.format = match std::env::var("GITHUB_ACTIONS").as_deref() {
+            Ok("true") => "github".to_owned(),
+            _ => "text".to_owned(),
+        };
+    }
+
+    // Annotations are displayed by GitHub Actions, messages are only written to its raw logs
+    if args.format == "github" {
+        colored::control::set_override(false);
+    }
+
+    logger(
+        match args.verbosity.as_str() {
+            "silent" => LevelFilter::Off,
+            "errors" => LevelFilter::Error,
+            "warn" => LevelFilter::Warn,
+            "info" => LevelFilter::Info,
+            "verbose" => LevelFilter::Debug,
+            "debug" => LevelFilter::Trace,
+            _ => unreachable!(),
+        },
+        args.format != "text",
+    );
+
+    match run_command(&args) {
+        Ok(code) => code,
+        Err(err) => {
+            error!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parse the arguments of a command (or of a subcommand if its name is provided)
+/// If they can't be parsed or if only the help or version message was asked for, the exit code is returned instead
+fn parse_args<T: Clap>(subcommand: &str, args: &[&str]) -> Result<T, ExitCode> {
+    let program_name = match subcommand {
+        "" => PROGRAM_NAME.to_owned(),
+        subcommand => format!("{} {}", PROGRAM_NAME, subcommand),
+    };
+
+    match T::try_parse_from(std::iter::once(program_name.as_str()).chain(args.iter().copied())) {
+        Ok(args) => Ok(args),
+        // Help and version messages are reported as errors by the parser, but they are not failures
+        Err(err) if !err.use_stderr() => {
+            print!("{}", err);
+            Err(ExitCode::SUCCESS)
+        }
+        Err(err) => {
+            eprint!("{}", err);
+            Err(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Install (if its arguments are provided) or uninstall a hook in the git repository of the current directory
+fn run_hook_command(hook: &str, install: Option<&InstallHookCommand>) -> ExitCode {
+    logger(LevelFilter::Info, false);
+
+    let hook = match hook {
+        "pre-commit" => GitHook::PreCommit,
+        "pre-push" => GitHook::PrePush,
+        _ => unreachable!(),
+    };
+
+    let result = match install {
+        Some(args) => std::env::current_exe()
+            .map_err(|err| format!("Failed to get the path of the executable: {}", err))
+            .and_then(|exe| {
+                let existing = match (args.force, args.append) {
+                    (true, _) => ExistingHook::Replace,
+                    (false, true) => ExistingHook::Append,
+                    (false, false) => ExistingHook::Refuse,
+                };
+
+                install_hook(Path::new("."), hook, existing, &exe)
+            })
+            .map(|path| {
+                info!(
+                    "Installed the {} hook at '{}'",
+                    hook.name(),
+                    safe_canonicalize(&path).green()
+                )
+            }),
+
+        None => uninstall_hook(Path::new("."), hook).map(|path| {
+            info!(
+                "Removed broken-md-links from the {} hook at '{}'",
+                hook.name(),
+                safe_canonicalize(&path).green()
+            )
+        }),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            error!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Compare two reports, failing if the new one contains findings which are not in the old one
+fn run_diff_command(args: &DiffCommand) -> ExitCode {
+    logger(LevelFilter::Warn, false);
+
+    let diff = match load_report(Path::new(&args.old))
+        .and_then(|old| Ok((old, load_report(Path::new(&args.new))?)))
+    {
+        Ok((old, new)) => diff_reports(&old, &new),
+        Err(err) => {
+            error!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match args.format.as_str() {
+        "text" => println!("{}", diff.format_text()),
+        "markdown" => print!("{}", diff.format_markdown()),
+        _ => unreachable!(),
+    }
+
+    if diff.new.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Write the JSON Schema of the reports to the standard output
+fn run_schema_command() -> ExitCode {
+    #[cfg(feature = "schema")]
+    {
+        println!("{}", crate::report_diff::report_schema());
+        ExitCode::SUCCESS
+    }
+
+    #[cfg(not(feature = "schema"))]
+    {
+        logger(LevelFilter::Warn, false);
+        error!("The schema of the reports is only available with the 'schema' cargo feature");
+        ExitCode::FAILURE
+    }
+}
+
+/// Get the options provided on the command line which can be overridden by configuration files
+///
+/// Flags only enable options, so the options they disable can be enabled by configuration files.
+fn cli_overrides(args: &Command, options: &CheckerOptions) -> ConfigOverrides {
+    let flag = |set: bool| Some(true).filter(|_| set);
+
+    ConfigOverrides {
+        emojis: args.emojis.as_ref().map(|_| options.slugs.emoji_handling),
+        math_in_slugs: args
+            .math_in_slugs
+            .as_ref()
+            .map(|_| options.slugs.math_in_slug),
+        slug_algorithm: args
+            .slug_algorithm
+            .as_ref()
+            .map(|_| options.slugs.algorithm),
+        ignore_header_links: flag(args.ignore_header_links),
+        only_files: flag(args.only_files),
+        check_mailto: flag(args.check_mailto),
+        check_path_style: flag(args.check_path_style),
+        check_extension_case: flag(args.check_extension_case),
+        require_alt_text: flag(args.require_alt_text),
+        check_toc: flag(args.check_toc),
+        error_on_warnings: flag(args.error_on_warn),
+        unresolved_placeholders: args
+            .unresolved_placeholders
+            .as_ref()
+            .map(|_| options.placeholders.unresolved_severity),
+        exclude: vec![],
+        targets_only: vec![],
+    }
+}
+
+/// Write the profiles of the checked files to a JSON file
+fn write_profile(path: &Path, targets_cache: &mut TargetsCache) -> Result<(), String> {
+    let profiles = targets_cache.take_file_profiles();
+
+    let json = serde_json::to_string_pretty(&profiles)
+        .map_err(|err| format!("Failed to serialize the profile: {}", err))?;
+
+    std::fs::write(path, json).map_err(|err| {
+        format!(
+            "Failed to write the profile to '{}': {}",
+            safe_canonicalize(path).green(),
+            err
+        )
+    })?;
+
+    info!(
+        "Wrote the profile of {} files to '{}'.",
+        profiles.len(),
+        safe_canonicalize(path).green()
+    );
+
+    Ok(())
+}
+
+/// Parse the value of a placeholder, written as `name=value`
+fn parse_define(define: &str) -> Result<(String, String), String> {
+    match define.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_owned(), value.to_owned()))
+        }
+        _ => Err(format!(
+            "Invalid definition '{}', expected 'name=value'",
+            define
+        )),
+    }
+}
+
+/// Parse the pattern of the links with a custom URI scheme, written as `scheme=regex`
+fn parse_scheme_regex(scheme_regex: &str) -> Result<(String, Regex), String> {
+    match scheme_regex.split_once('=') {
+        Some((scheme, regex)) if !scheme.trim().is_empty() => Regex::new(regex)
+            .map(|regex| (scheme.trim().to_owned(), regex))
+            .map_err(|err| format!("Invalid pattern for scheme '{}': {}", scheme.trim(), err)),
+        _ => Err(format!(
+            "Invalid scheme pattern '{}', expected 'scheme=regex'",
+            scheme_regex
+        )),
+    }
+}
+
+/// Parse the syntax of placeholders (e.g. `{{name}}`) into their opening and closing delimiters
+fn parse_placeholder_syntax(syntax: &str) -> Result<(String, String), String> {
+    match syntax.split_once("name") {
+        Some((open, close)) if !open.is_empty() && !close.is_empty() => {
+            Ok((open.to_owned(), close.to_owned()))
+        }
+        _ => Err(format!(
+            "Invalid placeholder syntax '{}', expected delimiters around 'name' (e.g. '{{{{name}}}}')",
+            syntax
+        )),
+    }
+}
+
+/// Parse a duration made of a number and an optional unit (`ms`, `s`, `m` or `h`, seconds if omitted), e.g. `90s`
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let unit_start = duration
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(duration.len());
+
+    let (number, unit) = duration.split_at(unit_start);
+    let invalid = || format!("Invalid duration '{}'", duration);
+
+    let number = number.parse::<f64>().map_err(|_| invalid())?;
+
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return Err(invalid()),
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| invalid())
+}
+
+/// Run the command with parsed arguments, returning the exit code
+/// Failures are returned as an error message
+fn run_command(args: &Command) -> Result<ExitCode, String> {
+    let input = Path::new(&args.input);
+
+    if !input.exists() {
+        return Err("Input file not found".to_owned());
+    } else if !args.recursive && !input.is_file() {
+        return Err("Input is not a file - if you want to check a folder, use the '-r' / '--recursive' option".to_owned());
+    } else if args.recursive && !input.is_dir() {
+        return Err(
+            "Input is not a directory but '-r' / '--recursive' option was supplied".to_owned(),
+        );
+    }
+
+    if args.check_image_dimensions && !cfg!(feature = "image-check") {
+        return Err(
+            "The '--check-image-dimensions' option requires the 'image-check' feature".to_owned(),
+        );
+    }
+
+    if args.check_http && !cfg!(feature = "check-http") {
+        return Err("The '--check-http' option requires the 'check-http' feature".to_owned());
+    }
+
+    if args.watch && !cfg!(feature = "watch") {
+        return Err("The '--watch' option requires the 'watch' feature".to_owned());
+    }
+
+    let mut options = CheckerOptions {
+        ignore_header_links: args.ignore_header_links,
+        only_files: args.only_files,
+        no_errors: args.no_error,
+        check_mailto: args.check_mailto,
+        check_path_style: args.check_path_style,
+        check_extension_case: args.check_extension_case,
+        max_link_length: args.max_link_length,
+        require_alt_text: args.require_alt_text,
+        check_image_dimensions: args.check_image_dimensions,
+        ignore_images: args.ignore_images,
+        ignore_unresolved_references: args.ignore_unresolved_references,
+        ignore_html_blocks: args.ignore_html_blocks,
+        check_toc: args.check_toc,
+        check_mdbook_includes: args.check_mdbook_includes,
+        navigation_file: args.check_nav_order.as_ref().map(PathBuf::from),
+        navigation_order_severity: match args.nav_order_severity.as_str() {
+            "error" => Severity::Error,
+            "warn" => Severity::Warning,
+            _ => unreachable!(),
+        },
+        frontmatter_required_fields: args.frontmatter_required_fields.clone(),
+        partial_dirs: args.partial_dirs.clone(),
+        partial_context_severity: match args.partial_context_severity.as_str() {
+            "error" => Severity::Error,
+            "warn" => Severity::Warning,
+            "info" => Severity::Info,
+            _ => unreachable!(),
+        },
+        scheme_handlers: SchemeHandlers::default(),
+        redirect_markers: match (args.check_redirects, args.redirect_markers.is_empty()) {
+            (false, _) => vec![],
+            (true, true) => vec!["redirect_to".to_owned()],
+            (true, false) => args.redirect_markers.clone(),
+        },
+        draft_markers: match (args.respect_drafts, args.draft_markers.is_empty()) {
+            (false, _) => vec![],
+            (true, true) => [
+                ("draft", "true"),
+                ("publish", "false"),
+                ("published", "false"),
+                ("hidden", "true"),
+            ]
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+            (true, false) => args.draft_markers.clone(),
+        },
+        anchor_aliases: match args.anchor_aliases {
+            Some(ref path) => load_anchor_aliases(Path::new(path))?,
+            None => AnchorAliases::new(),
+        },
+        warn_anchor_aliases: args.warn_anchor_aliases,
+        anchor_case_severity: match args.anchor_case_severity.as_str() {
+            "error" => Severity::Error,
+            "warn" => Severity::Warning,
+            _ => unreachable!(),
+        },
+        anchor_punctuation_severity: match args.anchor_punctuation_severity.as_str() {
+            "error" => Severity::Error,
+            "warn" => Severity::Warning,
+            _ => unreachable!(),
+        },
+        anchor_plural_severity: match args.anchor_plural_severity.as_str() {
+            "error" => Severity::Error,
+            "warn" => Severity::Warning,
+            _ => unreachable!(),
+        },
+        link_text_is_url: args
+            .link_text_is_url
+            .as_ref()
+            .map(|severity| match severity.as_str() {
+                "info" => Severity::Info,
+                "warn" => Severity::Warning,
+                _ => unreachable!(),
+            }),
+        placeholders: PlaceholderOptions {
+            variables: {
+                let mut variables = match args.variables_file {
+                    Some(ref path) => load_placeholder_variables(Path::new(path))?,
+                    None => BTreeMap::new(),
+                };
+
+                variables.extend(args.defines.iter().cloned());
+                variables
+            },
+            delimiters: match args.placeholder_syntaxes.is_empty() {
+                true => vec![
+                    ("{{".to_owned(), "}}".to_owned()),
+                    ("${".to_owned(), "}".to_owned()),
+                ],
+                false => args.placeholder_syntaxes.clone(),
+            },
+            unresolved_severity: match args.unresolved_placeholders.as_deref().unwrap_or("error") {
+                "error" => Severity::Error,
+                "warn" => Severity::Warning,
+                _ => unreachable!(),
+            },
+        },
+        lenient_space_destinations: args.lenient_space_destinations,
+        dedupe_findings: args.dedupe_findings,
+        group_by_target: args.group_by_target,
+        rust_doc_comments: args.rust_doc_comments,
+        error_on_warnings: args.error_on_warn,
+        no_ignore: args.no_ignore,
+        targets_only: GitignorePatterns::default(),
+        follow_symlinks: args.follow_symlinks,
+        halt_on_io_error: args.halt_on_io_error,
+        max_file_size: Some(args.max_file_size).filter(|&size| size > 0),
+        timeout: args.timeout,
+        check_http_links: args.check_http,
+        follow_redirects: args.follow_redirects,
+        http_timeout: args.http_timeout,
+        profile: args.profile,
+        max_concurrent_dirs: args.max_concurrent_dirs,
+        parallel: args.parallel,
+        invalid_utf8: match args.invalid_utf8.as_str() {
+            "skip" => InvalidUtf8Handling::Skip,
+            "lossy" => InvalidUtf8Handling::Lossy,
+            "error" => InvalidUtf8Handling::Error,
+            _ => unreachable!(),
+        },
+        changelog_mode: args.changelog_mode,
+        issues_dir: args.issues_dir.as_ref().map(PathBuf::from),
+        slugs: SlugOptions {
+            emoji_handling: match args.emojis.as_deref().unwrap_or("strip") {
+                "strip" => EmojiHandling::Strip,
+                "transliterate" => EmojiHandling::Transliterate,
+                _ => unreachable!(),
+            },
+            math_in_slug: match args.math_in_slugs.as_deref().unwrap_or("strip") {
+                "strip" => MathSlugHandling::Strip,
+                "raw" => MathSlugHandling::IncludeRaw,
+                "plain" => MathSlugHandling::IncludePlain,
+                _ => unreachable!(),
+            },
+            algorithm: match args.slug_algorithm.as_deref().unwrap_or("default") {
+                "default" => SlugAlgorithm::Default,
+                "github" => SlugAlgorithm::GitHub,
+                "gitlab" => SlugAlgorithm::GitLab,
+                "mdbook" => SlugAlgorithm::MdBook,
+                "mkdocs" => SlugAlgorithm::MkDocs,
+                _ => unreachable!(),
+            },
+        },
+        directory_configs: DirectoryConfigs::default(),
+    };
+
+    for (scheme, pattern) in &args.scheme_regexes {
+        let regex = pattern.clone();
+
+        // The pattern identifies the handler's behavior across incremental runs
+        options.versioned_scheme_handler(scheme, pattern.as_str(), move |dest| {
+            match regex.is_match(dest) {
+                true => SchemeVerdict::Valid,
+                false => SchemeVerdict::Invalid(format!("doesn't match the pattern '{}'", regex)),
+            }
+        });
+    }
+
+    // Patterns and configuration files are relative to the input directory, or to the input file's directory
+    let input_dir = match args.recursive {
+        true => input,
+        false => input
+            .parent()
+            .filter(|parent| *parent != Path::new(""))
+            .unwrap_or_else(|| Path::new(".")),
+    };
+
+    options.targets_only = GitignorePatterns::new(input_dir, &args.targets_only)
+        .map_err(|err| format!("Invalid '--targets-only' patterns: {}", err))?;
+
+    if !args.no_config_files {
+        options.directory_configs = DirectoryConfigs {
+            cli_overrides: cli_overrides(args, &options),
+            ..DirectoryConfigs::load(
+                args.config_root.as_deref().map_or(input_dir, Path::new),
+                args.no_ignore,
+            )?
+        };
+    }
+
+    if let Some(ref file) = args.print_config {
+        let file = Path::new(file);
+
+        for (dir, _) in options.directory_configs.applicable(file) {
+            println!("# {}", safe_canonicalize(&dir.join(CONFIG_FILE_NAME)));
+        }
+
+        let config = ConfigOverrides::of(&options.resolve(file), &options.slugs_for(file));
+
+        print!(
+            "{}",
+            toml::to_string(&config)
+                .map_err(|err| format!("Failed to serialize the configuration: {}", err))?
+        );
+
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let cache_file = args.cache_file.as_ref().map(Path::new);
+
+    let mut links_cache = match cache_file {
+        Some(cache_file) if cache_file.is_file() => {
+            match load_links_cache(cache_file, &options.slugs) {
+                Ok(links_cache) => links_cache,
+                Err(err) => {
+                    warn!("{}", err);
+                    FileLinksCache::new()
+                }
+            }
+        }
+        _ => FileLinksCache::new(),
+    };
+
+    if args.write_cache_only {
+        let cache_file = cache_file.unwrap();
+
+        links_cache.warm(input, &options);
+        save_links_cache(&links_cache, &options.slugs, cache_file)?;
+
+        info!("Wrote headers of {} files to the cache.", links_cache.len());
+
+        #[cfg(feature = "content-hash")]
+        log_content_hash_stats(&links_cache);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut targets_cache = TargetsCache::new();
+
+    let mut result = match args.incremental {
+        Some(ref state_file) => {
+            let state_file = Path::new(state_file);
+
+            let mut state = if state_file.is_file() {
+                IncrementalState::load(state_file, &options).unwrap_or_else(|err| {
+                    warn!("{}", err);
+                    IncrementalState::default()
+                })
+            } else {
+                IncrementalState::default()
+            };
+
+            let result = check_broken_links_incremental(
+                input,
+                args.recursive,
+                &options,
+                &mut links_cache,
+                &mut targets_cache,
+                &mut state,
+            );
+
+            if result.is_ok() {
+                state.save(state_file)?;
+
+                debug!(
+                    "Checked {} changed file(s) again.",
+                    state.rechecked_files().len()
+                );
+            }
+
+            result
+        }
+
+        // Annotations are line-based, so they are written as soon as each file is checked instead of once the check is done
+        None => {
+            let mut errors = vec![];
+
+            check_path_with_callback(
+                input,
+                args.recursive,
+                &options,
+                &mut links_cache,
+                &mut targets_cache,
+                &mut |error| {
+                    if args.format == "github" {
+                        let annotation = github_annotations(std::slice::from_ref(&error));
+                        write_line(annotation.trim_end_matches('\n').to_owned(), false);
+                    }
+
+                    errors.push(error);
+                },
+            )
+            .map(|()| errors)
+        }
+    };
+
+    // Number of findings whose annotation was already written
+    let annotated = match (
+        &result,
+        args.incremental.is_none() && args.format == "github",
+    ) {
+        (Ok(errors), true) => errors.len(),
+        _ => 0,
+    };
+
+    debug!(
+        "Resolved {} link targets, {:.1}% of them from the cache.",
+        targets_cache.hits() + targets_cache.misses(),
+        targets_cache.hit_rate() * 100.0
+    );
+
+    let stats = links_cache.stats();
+
+    debug!(
+        "Anchors cache: {} files with {} anchors, {} lookups answered from the cache, {} which required parsing, {} outdated entries dropped, {} target-only files parsed when linked to.",
+        stats.entries,
+        stats.slugs,
+        stats.hits,
+        stats.misses,
+        stats.evictions,
+        stats.targets_only
+    );
+
+    #[cfg(feature = "content-hash")]
+    log_content_hash_stats(&links_cache);
+
+    if let Some(cache_file) = cache_file {
+        save_links_cache(&links_cache, &options.slugs, cache_file)?;
+    }
+
+    if args.profile {
+        write_profile(Path::new(&args.profile_output), &mut targets_cache)?;
+    }
+
+    if let (Ok(errors), true) = (&mut result, args.graph_analysis) {
+        let root = input
+            .canonicalize()
+            .map_err(|err| format!("Failed to canonicalize path '{}': {}", input.display(), err))?;
+
+        let entry_points = if args.entry_points.is_empty() {
+            vec![root.join("README.md"), root.join("index.md")]
+        } else {
+            args.entry_points
+                .iter()
+                .map(|entry_point| {
+                    Path::new(entry_point).canonicalize().map_err(|err| {
+                        format!(
+                            "Failed to canonicalize entry point '{}': {}",
+                            entry_point, err
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let severity = match args.graph_analysis_severity.as_str() {
+            "info" => Severity::Info,
+            "warn" if args.error_on_warn => Severity::Error,
+            "warn" => Severity::Warning,
+            "error" => Severity::Error,
+            _ => unreachable!(),
+        };
+
+        errors.extend(analyze_link_graph(
+            targets_cache.link_graph(),
+            &root,
+            &entry_points,
+            severity,
+        ));
+    }
+
+    if let (Ok(errors), false) = (&mut result, options.partial_dirs.is_empty()) {
+        errors.extend(check_checked_partial_links(input, &options)?);
+    }
+
+    if let (Ok(errors), "json") = (&result, args.format.as_str()) {
+        // Grouped findings are provided alongside the flat list, which is still needed to locate each one
+        let report = JsonReport::new(
+            errors.clone(),
+            match args.group_by_target {
+                true => Some(group_by_target(errors)),
+                false => None,
+            },
+            targets_cache.take_check_stats(),
+        );
+
+        let report = serde_json::to_string_pretty(&report);
+
+        println!(
+            "{}",
+            report.map_err(|err| format!("Failed to serialize the report: {}", err))?
+        );
+    }
+
+    if let (Ok(errors), "github") = (&result, args.format.as_str()) {
+        print!("{}", github_annotations(&errors[annotated..]));
+    }
+
+    if let (Ok(errors), "sarif") = (&result, args.format.as_str()) {
+        let report = serde_json::to_string_pretty(&sarif_report(errors));
+
+        println!(
+            "{}",
+            report.map_err(|err| format!("Failed to serialize the report: {}", err))?
+        );
+    }
+
+    if args.report_unreachable {
+        let entry_points = if args.entry_points.is_empty() {
+            vec![input.join("README.md")]
+        } else {
+            args.entry_points.iter().map(PathBuf::from).collect()
+        };
+
+        let unreachable = find_unreachable_files(input, &entry_points, &options)
+            .map_err(|err| err.to_string())?;
+
+        for file in &unreachable {
+            warn!("Unreachable file: {}", safe_canonicalize(file).green());
+        }
+
+        info!(
+            "Found {} unreachable file{}.",
+            unreachable.len(),
+            if unreachable.len() > 1 { "s" } else { "" }
+        );
+    }
+
+    #[cfg(feature = "watch")]
+    if let (Ok(errors), true) = (&result, args.watch) {
+        return crate::watch::watch(
+            input,
+            args.recursive,
+            &options,
+            &mut links_cache,
+            &mut targets_cache,
+            errors,
+            args.format != "text",
+        );
+    }
+
+    match result {
+        Ok(errors) if errors.is_empty() => {
+            info!("OK.");
+            Ok(ExitCode::SUCCESS)
+        }
+        Ok(errors) => {
+            if args.group_by_target {
+                for group in group_by_target(&errors) {
+                    let message = format!(
+                        "Missing target '{}' is linked to from {} location{}:{}",
+                        group.target.green(),
+                        group.locations.len(),
+                        if group.locations.len() > 1 { "s" } else { "" },
+                        group
+                            .locations
+                            .iter()
+                            .map(|location| format!(
+                                "\n  - {}{}",
+                                safe_canonicalize(&location.file),
+                                format_location(location.line, location.cell).yellow()
+                            ))
+                            .collect::<String>()
+                    );
+
+                    if args.no_error {
+                        warn!("{}", message);
+                    } else {
+                        error!("{}", message);
+                    }
+                }
+            }
+
+            if let Some(top) = args.top_broken_targets {
+                let targets = broken_targets(&errors);
+
+                if !targets.is_empty() {
+                    warn!(
+                        "Missing targets with the most links pointing to them:{}",
+                        targets
+                            .iter()
+                            .take(top)
+                            .map(|target| format!(
+                                "\n  - '{}': {} link{} from {} file{}",
+                                target.target.green(),
+                                target.count,
+                                if target.count > 1 { "s" } else { "" },
+                                target.files.len(),
+                                if target.files.len() > 1 { "s" } else { "" }
+                            ))
+                            .collect::<String>()
+                    );
+                }
+            }
+
+            let kinds = if args.dedupe_findings {
+                let deduped = dedupe_findings(&errors);
+
+                for finding in deduped.iter().filter(|finding| finding.count() > 1) {
+                    info!(
+                        "In {}{} the same problem was found {} times, at lines {}: {}",
+                        safe_canonicalize(&finding.file).green(),
+                        match finding.cell {
+                            Some(cell) => format!(" (cell {})", cell),
+                            None => String::new(),
+                        }
+                        .yellow(),
+                        finding.count(),
+                        finding
+                            .lines
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                            .yellow(),
+                        finding.error
+                    );
+                }
+
+                deduped
+                    .iter()
+                    .map(|finding| (finding.kind, finding.severity))
+                    .collect::<Vec<_>>()
+            } else {
+                errors
+                    .iter()
+                    .map(|error| (error.kind, error.severity))
+                    .collect()
+            };
+
+            // The timeout is not a broken link, it's reported separately, and informational findings are not counted
+            let truncated = kinds
+                .iter()
+                .any(|(kind, _)| *kind == BrokenLinkKind::Timeout);
+            let kinds = kinds
+                .into_iter()
+                .filter(|(kind, severity)| {
+                    *kind != BrokenLinkKind::Timeout && *severity != Severity::Info
+                })
+                .collect::<Vec<_>>();
+
+            if kinds.is_empty() && !truncated {
+                info!("OK.");
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            // Warnings (including findings reported with the warning severity) are counted separately from broken links
+            let warnings = kinds
+                .iter()
+                .filter(|(kind, severity)| {
+                    *kind == BrokenLinkKind::Warning || *severity == Severity::Warning
+                })
+                .count();
+            let links = kinds.len() - warnings;
+
+            // Only warnings were found, and they are not treated as errors
+            let only_warnings = kinds
+                .iter()
+                .all(|(_, severity)| *severity == Severity::Warning);
+
+            let plural = |count: usize| if count > 1 { "s" } else { "" };
+
+            let message = match (links, warnings) {
+                (links, 0) => format!("Found {} broken or invalid link{}!", links, plural(links)),
+                (0, warnings) => format!("Found {} warning{}!", warnings, plural(warnings)),
+                (links, warnings) => format!(
+                    "Found {} broken or invalid link{} and {} warning{}!",
+                    links,
+                    plural(links),
+                    warnings,
+                    plural(warnings)
+                ),
+            };
+
+            if truncated {
+                match (links + warnings, args.no_error || only_warnings) {
+                    (0, _) => {}
+                    (_, true) => warn!("{}", message),
+                    (_, false) => error!("{}", message),
+                }
+
+                error!(
+                    "The timeout expired before all files were checked, results are incomplete."
+                );
+                Ok(ExitCode::from(TIMEOUT_EXIT_CODE))
+            } else if args.no_error || only_warnings {
+                warn!("{}", message);
+                Ok(ExitCode::SUCCESS)
+            } else {
+                Err(message)
+            }
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Log how many files had the same content as another one, and so were not parsed
+#[cfg(feature = "content-hash")]
+fn log_content_hash_stats(links_cache: &FileLinksCache) {
+    debug!(
+        "Reused the headers of {} files with the same content as another one ({} distinct contents).",
+        links_cache.dedupe_hits(),
+        links_cache.stored_contents()
+    );
+}
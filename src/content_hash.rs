@@ -0,0 +1,108 @@
+//! Content-addressed layer of the headers cache
+//!
+//! The anchors of Markdown files are also stored by a hash of their content, so files with an identical content
+//!  (e.g. vendored or generated copies) are only parsed once, and entries of persistent cache files can be reused
+//!  for files which were moved since the cache was written.
+//!
+//! Each [`FileLinksCache`](crate::FileLinksCache) has its own layer, shared by its clones.
+
+use crate::SlugOptions;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Hash the content of a file
+pub fn content_hash(content: &[u8]) -> u64 {
+    xxh3_64(content)
+}
+
+/// Identity of a file's anchors, shared by all files with the same content
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ContentKey {
+    /// Options the slugs were generated with
+    slug_options: SlugOptions,
+
+    /// Extension of the file, as notebooks are parsed differently
+    extension: Option<String>,
+
+    /// Hash of the file's content (see [`content_hash`])
+    hash: u64,
+}
+
+impl ContentKey {
+    /// Build the key of a file's anchors from the content it was parsed from
+    pub fn new(path: &Path, content: &[u8], slug_options: &SlugOptions) -> Self {
+        Self::from_hash(path, content_hash(content), slug_options)
+    }
+
+    /// Build the key of a file's anchors from the hash of its content
+    pub fn from_hash(path: &Path, hash: u64, slug_options: &SlugOptions) -> Self {
+        Self {
+            slug_options: slug_options.clone(),
+            extension: path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(str::to_owned),
+            hash,
+        }
+    }
+}
+
+/// Anchors of the files of a headers cache, indexed by content
+#[derive(Debug, Default)]
+pub(crate) struct ContentAnchors {
+    /// Anchors of all files seen so far, indexed by content
+    by_content: HashMap<ContentKey, Vec<String>>,
+
+    /// Content hash of the files whose anchors were stored, indexed by canonical path
+    hashes: HashMap<PathBuf, u64>,
+
+    /// Number of files whose anchors were reused from another file with the same content
+    dedupe_hits: usize,
+}
+
+impl ContentAnchors {
+    /// Get the anchors of a file from the ones of an identical file, counting a reuse if there is one
+    pub fn lookup(&mut self, key: &ContentKey) -> Option<Vec<String>> {
+        let anchors = self.by_content.get(key).cloned();
+
+        if anchors.is_some() {
+            self.dedupe_hits += 1;
+        }
+
+        anchors
+    }
+
+    /// Store the anchors of a file by content, remembering the content hash of the file
+    pub fn store(&mut self, path: &Path, key: ContentKey, anchors: &[String]) {
+        self.hashes.insert(path.to_owned(), key.hash);
+        self.store_content(key, anchors);
+    }
+
+    /// Store anchors by content only, e.g. for entries of a persistent cache file whose path may have changed
+    pub fn store_content(&mut self, key: ContentKey, anchors: &[String]) {
+        self.by_content
+            .entry(key)
+            .or_insert_with(|| anchors.to_vec());
+    }
+
+    /// Forget the content hash of a file, e.g. because it changed
+    pub fn forget(&mut self, path: &Path) {
+        self.hashes.remove(path);
+    }
+
+    /// Get the content hash of a file whose anchors were stored
+    pub fn file_hash(&self, path: &Path) -> Option<u64> {
+        self.hashes.get(path).copied()
+    }
+
+    /// Get the number of files whose anchors were reused from another file with the same content
+    pub fn dedupe_hits(&self) -> usize {
+        self.dedupe_hits
+    }
+
+    /// Get the number of distinct file contents whose anchors are stored
+    pub fn stored_contents(&self) -> usize {
+        self.by_content.len()
+    }
+}
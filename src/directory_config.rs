@@ -0,0 +1,399 @@
+//! Configuration files overriding some options for the files of the directory they are located in
+//!
+//! A `broken-md-links.toml` file overrides the options of the files located in its directory and subdirectories.
+//! When several configuration files apply to a file, the deepest one takes precedence,
+//!  and the options provided on the command line take precedence over all of them.
+
+use crate::{
+    safe_canonicalize, CheckerOptions, EmojiHandling, MathSlugHandling, Severity, SlugAlgorithm,
+    SlugOptions,
+};
+use colored::Colorize;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the configuration files
+pub static CONFIG_FILE_NAME: &str = "broken-md-links.toml";
+
+/// Options which can be overridden by a configuration file
+///
+/// Other options can't be overridden, as they apply to a whole check (e.g. the timeout) or could make checking a file
+///  depend on the options of another directory (e.g. anchor aliases).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ConfigOverrides {
+    /// How emojis in headers are handled when generating slugs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emojis: Option<EmojiHandling>,
+
+    /// How math formulas in headers are handled when generating slugs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub math_in_slugs: Option<MathSlugHandling>,
+
+    /// Algorithm used to generate slugs, e.g. to match the platform rendering a directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug_algorithm: Option<SlugAlgorithm>,
+
+    /// See [`CheckerOptions::ignore_header_links`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_header_links: Option<bool>,
+
+    /// See [`CheckerOptions::only_files`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub only_files: Option<bool>,
+
+    /// See [`CheckerOptions::check_mailto`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_mailto: Option<bool>,
+
+    /// See [`CheckerOptions::check_path_style`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_path_style: Option<bool>,
+
+    /// See [`CheckerOptions::check_extension_case`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_extension_case: Option<bool>,
+
+    /// See [`CheckerOptions::require_alt_text`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_alt_text: Option<bool>,
+
+    /// See [`CheckerOptions::check_toc`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_toc: Option<bool>,
+
+    /// See [`CheckerOptions::error_on_warnings`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_on_warnings: Option<bool>,
+
+    /// See [`PlaceholderOptions::unresolved_severity`](crate::PlaceholderOptions::unresolved_severity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unresolved_placeholders: Option<Severity>,
+
+    /// Files to skip, as `.gitignore` patterns relative to the configuration file's directory (e.g. `drafts/`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+
+    /// Files which are not checked but remain valid link targets, as `.gitignore` patterns relative to the configuration file's directory
+    ///
+    /// See [`CheckerOptions::targets_only`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets_only: Vec<String>,
+}
+
+impl ConfigOverrides {
+    /// Get the overridable options of the provided options, e.g. to display the effective configuration of a file
+    ///
+    /// The slug options are provided separately, as they depend on the file the slugs are generated for (see [`CheckerOptions::slugs_for`]).
+    pub fn of(options: &CheckerOptions, slugs: &SlugOptions) -> Self {
+        Self {
+            emojis: Some(slugs.emoji_handling),
+            math_in_slugs: Some(slugs.math_in_slug),
+            slug_algorithm: Some(slugs.algorithm),
+            ignore_header_links: Some(options.ignore_header_links),
+            only_files: Some(options.only_files),
+            check_mailto: Some(options.check_mailto),
+            check_path_style: Some(options.check_path_style),
+            check_extension_case: Some(options.check_extension_case),
+            require_alt_text: Some(options.require_alt_text),
+            check_toc: Some(options.check_toc),
+            error_on_warnings: Some(options.error_on_warnings),
+            unresolved_placeholders: Some(options.placeholders.unresolved_severity),
+            exclude: vec![],
+            targets_only: vec![],
+        }
+    }
+
+    /// Override the provided options (except the slug options, see [`ConfigOverrides::apply_slugs`])
+    pub fn apply(&self, options: &mut CheckerOptions) {
+        let overrides = [
+            (self.ignore_header_links, &mut options.ignore_header_links),
+            (self.only_files, &mut options.only_files),
+            (self.check_mailto, &mut options.check_mailto),
+            (self.check_path_style, &mut options.check_path_style),
+            (self.check_extension_case, &mut options.check_extension_case),
+            (self.require_alt_text, &mut options.require_alt_text),
+            (self.check_toc, &mut options.check_toc),
+            (self.error_on_warnings, &mut options.error_on_warnings),
+        ];
+
+        for (value, option) in overrides {
+            if let Some(value) = value {
+                *option = value;
+            }
+        }
+
+        if let Some(severity) = self.unresolved_placeholders {
+            options.placeholders.unresolved_severity = severity;
+        }
+    }
+
+    /// Override the provided slug options
+    pub fn apply_slugs(&self, slugs: &mut SlugOptions) {
+        if let Some(emojis) = self.emojis {
+            slugs.emoji_handling = emojis;
+        }
+
+        if let Some(math) = self.math_in_slugs {
+            slugs.math_in_slug = math;
+        }
+
+        if let Some(algorithm) = self.slug_algorithm {
+            slugs.algorithm = algorithm;
+        }
+    }
+}
+
+/// Configuration files of a directory (see [`CheckerOptions::directory_configs`])
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirectoryConfigs {
+    /// Options overridden by the configuration file of each directory, by canonical path of the directory
+    pub configs: BTreeMap<PathBuf, ConfigOverrides>,
+
+    /// Options provided on the command line, which take precedence over the configuration files
+    pub cli_overrides: ConfigOverrides,
+}
+
+impl DirectoryConfigs {
+    /// Load the configuration files (see [`CONFIG_FILE_NAME`]) located in a directory and its subdirectories
+    ///
+    /// Directories ignored by `.gitignore` and `.ignore` files are skipped, unless `no_ignore` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{check_broken_links_simple, CheckerOptions};
+    /// use broken_md_links::directory_config::DirectoryConfigs;
+    ///
+    /// let options = CheckerOptions {
+    ///     directory_configs: DirectoryConfigs::load(Path::new("docs"), false).unwrap(),
+    ///     ..CheckerOptions::default()
+    /// };
+    ///
+    /// let errors = check_broken_links_simple(Path::new("docs"), &options).unwrap();
+    /// ```
+    pub fn load(root: &Path, no_ignore: bool) -> Result<Self, String> {
+        let mut configs = BTreeMap::new();
+
+        for entry in WalkBuilder::new(root)
+            .standard_filters(!no_ignore)
+            .hidden(false)
+            .build()
+        {
+            let entry =
+                entry.map_err(|err| format!("Failed to find the configuration files: {}", err))?;
+
+            if entry.file_name() != CONFIG_FILE_NAME || !entry.path().is_file() {
+                continue;
+            }
+
+            let path = entry.path();
+            let canon = safe_canonicalize(path);
+
+            let content = std::fs::read_to_string(path).map_err(|err| {
+                format!(
+                    "Failed to read configuration file at '{}': {}",
+                    canon.green(),
+                    err
+                )
+            })?;
+
+            let config = toml::from_str::<ConfigOverrides>(&content).map_err(|err| {
+                format!(
+                    "Failed to parse configuration file at '{}': {}",
+                    canon.green(),
+                    err
+                )
+            })?;
+
+            for pattern in config.exclude.iter().chain(&config.targets_only) {
+                GitignoreBuilder::new(root)
+                    .add_line(None, pattern)
+                    .map_err(|err| {
+                        format!(
+                            "Invalid exclusion pattern '{}' in configuration file at '{}': {}",
+                            pattern,
+                            canon.green(),
+                            err
+                        )
+                    })?;
+            }
+
+            let dir = path.parent().unwrap().canonicalize().map_err(|err| {
+                format!(
+                    "Failed to canonicalize the directory of configuration file '{}': {}",
+                    canon.green(),
+                    err
+                )
+            })?;
+
+            configs.insert(dir, config);
+        }
+
+        Ok(Self {
+            configs,
+            cli_overrides: ConfigOverrides::default(),
+        })
+    }
+
+    /// Get the configuration files which apply to a file, from the shallowest to the deepest one, with their directory
+    pub fn applicable(&self, file: &Path) -> Vec<(&PathBuf, &ConfigOverrides)> {
+        if self.configs.is_empty() {
+            return vec![];
+        }
+
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_owned());
+
+        let mut applicable = file
+            .ancestors()
+            .skip(1)
+            .filter_map(|dir| self.configs.get_key_value(dir))
+            .collect::<Vec<_>>();
+
+        applicable.reverse();
+        applicable
+    }
+
+    /// Check if a file is excluded by a configuration file
+    pub fn is_excluded(&self, file: &Path) -> bool {
+        self.matches(file, |config| &config.exclude)
+    }
+
+    /// Check if a file is only a link target according to a configuration file (see [`ConfigOverrides::targets_only`])
+    pub fn is_target_only(&self, file: &Path) -> bool {
+        self.matches(file, |config| &config.targets_only)
+    }
+
+    /// Check if a file matches one of the patterns of the configuration files which apply to it
+    fn matches(&self, file: &Path, patterns: impl Fn(&ConfigOverrides) -> &Vec<String>) -> bool {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_owned());
+
+        self.applicable(&file)
+            .into_iter()
+            .filter(|(_, config)| !patterns(config).is_empty())
+            .any(|(dir, config)| {
+                // Patterns are validated when the configuration files are loaded
+                matches_gitignore_patterns(dir, patterns(config), &file)
+            })
+    }
+}
+
+/// Check if a file matches one of the provided `.gitignore` patterns, relative to a directory
+///
+/// Invalid patterns are ignored.
+pub(crate) fn matches_gitignore_patterns(dir: &Path, patterns: &[String], file: &Path) -> bool {
+    let mut builder = GitignoreBuilder::new(dir);
+
+    for pattern in patterns {
+        let _ = builder.add_line(None, pattern);
+    }
+
+    builder
+        .build()
+        .is_ok_and(|matcher| matcher.matched_path_or_any_parents(file, false).is_ignore())
+}
+
+/// `.gitignore` patterns relative to a directory, compiled once (see [`CheckerOptions::targets_only`])
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::directory_config::GitignorePatterns;
+///
+/// let patterns = GitignorePatterns::new(Path::new("docs"), &["drafts/".to_owned(), "*.generated.md".to_owned()]).unwrap();
+///
+/// if patterns.matches(Path::new("docs/drafts/wip.md")) {
+///     println!("Skipping a draft");
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct GitignorePatterns {
+    /// Canonical path of the directory the patterns are relative to
+    root: PathBuf,
+
+    /// Patterns, as provided
+    patterns: Vec<String>,
+
+    /// Compiled patterns
+    matcher: Option<Gitignore>,
+}
+
+impl GitignorePatterns {
+    /// Compile `.gitignore` patterns relative to a directory, failing if one of them is invalid
+    pub fn new(root: &Path, patterns: &[String]) -> Result<Self, String> {
+        let root = root.canonicalize().map_err(|err| {
+            format!(
+                "Failed to canonicalize path '{}': {}",
+                safe_canonicalize(root).green(),
+                err
+            )
+        })?;
+
+        let mut builder = GitignoreBuilder::new(&root);
+
+        for pattern in patterns {
+            builder
+                .add_line(None, pattern)
+                .map_err(|err| format!("Invalid pattern '{}': {}", pattern, err))?;
+        }
+
+        let matcher = builder
+            .build()
+            .map_err(|err| format!("Invalid patterns: {}", err))?;
+
+        Ok(Self {
+            root,
+            patterns: patterns.to_vec(),
+            matcher: Some(matcher),
+        })
+    }
+
+    /// Get the patterns, as provided
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Check if there is no pattern
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Check if a file matches one of the patterns
+    ///
+    /// Files located outside of the directory the patterns are relative to never match.
+    pub fn matches(&self, file: &Path) -> bool {
+        let matcher = match self.matcher {
+            Some(ref matcher) if !self.patterns.is_empty() => matcher,
+            _ => return false,
+        };
+
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_owned());
+
+        file.starts_with(&self.root)
+            && matcher
+                .matched_path_or_any_parents(&file, false)
+                .is_ignore()
+    }
+}
+
+impl std::fmt::Debug for GitignorePatterns {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitignorePatterns")
+            .field("root", &self.root)
+            .field("patterns", &self.patterns)
+            .finish()
+    }
+}
+
+impl PartialEq for GitignorePatterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root && self.patterns == other.patterns
+    }
+}
+
+impl Eq for GitignorePatterns {}
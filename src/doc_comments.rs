@@ -0,0 +1,184 @@
+//! Markdown content of the doc comments of Rust source files (see [`CheckerOptions::rust_doc_comments`](crate::CheckerOptions::rust_doc_comments))
+//!
+//! Doc comments are extracted into [`EmbeddedMarkdown`], which is shared with the Markdown cells of Jupyter notebooks.
+
+use crate::embedded_markdown::EmbeddedMarkdown;
+
+/// Comment excluding the doc comments it's part of from the check,
+///  which is also added by the `#[broken_links_ignore]` attribute of the `broken_md_links_derive` crate
+const IGNORE_MARKER: &str = "<!-- broken-md-links: ignore -->";
+
+/// Kind of a doc comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocKind {
+    /// Documentation of the following item (`///` and `/** */`)
+    Outer,
+
+    /// Documentation of the enclosing item (`//!` and `/*! */`)
+    Inner,
+}
+
+/// Extract the Markdown content of the doc comments of a Rust source file
+///
+/// The doc comments of each item (and the inner doc comments of each module) are concatenated into a block,
+///  which is parsed on its own as rustdoc does.
+/// Blank lines, attributes and regular comments located between the doc comments of an item don't split its block.
+///
+/// Blocks containing [`IGNORE_MARKER`] and the doc comments of items with the `#[broken_links_ignore]` attribute are left out.
+pub(crate) fn extract_doc_comments(source: &str) -> EmbeddedMarkdown {
+    let mut docs = EmbeddedMarkdown::default();
+
+    // Kind and lines (source line and text) of the block being collected
+    let mut block: Option<(DocKind, Vec<(usize, String)>)> = None;
+
+    // Does the item being documented have the `#[broken_links_ignore]` attribute?
+    let mut ignored = false;
+
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+
+    while let Some((line_number, line)) = lines.next() {
+        let trimmed = line.trim_start();
+
+        let (kind, comment_lines) = if let Some((kind, text)) = line_doc_comment(trimmed) {
+            (kind, vec![(line_number, text.to_owned())])
+        } else if let Some((kind, first_line)) = block_doc_comment_start(trimmed) {
+            let mut comment_lines = vec![(line_number, first_line.to_owned())];
+
+            // Collect the lines until the end of the comment
+            if !first_line.contains("*/") {
+                for (line_number, line) in lines.by_ref() {
+                    let is_last = line.contains("*/");
+                    comment_lines.push((line_number, line.to_owned()));
+
+                    if is_last {
+                        break;
+                    }
+                }
+            }
+
+            (kind, strip_block_comment_decorations(comment_lines))
+        } else {
+            if is_ignore_attribute(trimmed) {
+                ignored = true;
+            }
+
+            // Blank lines, attributes and comments don't end the block of the item being documented
+            if !(trimmed.is_empty() || trimmed.starts_with("#[") || trimmed.starts_with("//")) {
+                push_doc_block(&mut docs, block.take(), ignored);
+                ignored = false;
+            }
+
+            continue;
+        };
+
+        match block {
+            Some((block_kind, ref mut block_lines)) if block_kind == kind => {
+                block_lines.extend(comment_lines)
+            }
+            _ => {
+                push_doc_block(&mut docs, block.take(), ignored);
+                block = Some((kind, comment_lines));
+            }
+        }
+    }
+
+    push_doc_block(&mut docs, block, ignored);
+    docs
+}
+
+/// Check if a line is the `#[broken_links_ignore]` attribute (possibly written with its crate's path)
+fn is_ignore_attribute(line: &str) -> bool {
+    line.strip_prefix("#[")
+        .and_then(|attr| attr.trim_end().strip_suffix(']'))
+        .map(str::trim)
+        .is_some_and(|attr| {
+            attr == "broken_links_ignore" || attr.ends_with("::broken_links_ignore")
+        })
+}
+
+/// Add the doc comments of an item to the content, removing the indentation common to all of their lines (like rustdoc does)
+///
+/// Blocks containing [`IGNORE_MARKER`] are skipped, as well as outer doc comments if the item is ignored.
+fn push_doc_block(
+    docs: &mut EmbeddedMarkdown,
+    block: Option<(DocKind, Vec<(usize, String)>)>,
+    item_ignored: bool,
+) {
+    let lines = match block {
+        Some((DocKind::Outer, _)) if item_ignored => return,
+        Some((_, lines)) if lines.iter().any(|(_, text)| text.contains(IGNORE_MARKER)) => return,
+        Some((_, lines)) => lines,
+        None => return,
+    };
+
+    let indent = lines
+        .iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(_, text)| text.len() - text.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    docs.push_block(
+        lines
+            .into_iter()
+            .map(|(line_number, text)| {
+                (
+                    line_number,
+                    text.get(indent..).unwrap_or("").trim_end().to_owned(),
+                )
+            })
+            .collect(),
+        None,
+    );
+}
+
+/// Get the kind and text of a line doc comment (`/// text` or `//! text`)
+fn line_doc_comment(line: &str) -> Option<(DocKind, &str)> {
+    if let Some(text) = line.strip_prefix("///") {
+        // Comments starting with four slashes are not doc comments
+        if !text.starts_with('/') {
+            return Some((DocKind::Outer, text));
+        }
+    }
+
+    line.strip_prefix("//!").map(|text| (DocKind::Inner, text))
+}
+
+/// Get the kind of a block doc comment (`/** text */` or `/*! text */`) starting on a line, and the text following its opening
+fn block_doc_comment_start(line: &str) -> Option<(DocKind, &str)> {
+    if let Some(text) = line.strip_prefix("/**") {
+        // Neither `/***` nor `/**/` are doc comments
+        if !text.starts_with('*') && !text.starts_with('/') {
+            return Some((DocKind::Outer, text));
+        }
+    }
+
+    line.strip_prefix("/*!").map(|text| (DocKind::Inner, text))
+}
+
+/// Remove the closing of a block doc comment, and the leading `*` of its lines if all of them have one
+fn strip_block_comment_decorations(mut lines: Vec<(usize, String)>) -> Vec<(usize, String)> {
+    if let Some((_, last)) = lines.last_mut() {
+        if let Some(end) = last.rfind("*/") {
+            last.truncate(end);
+        }
+    }
+
+    let decorated = lines[1..]
+        .iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .all(|(_, text)| text.trim_start().starts_with('*'));
+
+    if decorated {
+        for (_, text) in lines.iter_mut().skip(1) {
+            if let Some(stripped) = text.trim_start().strip_prefix('*') {
+                *text = stripped.to_owned();
+            }
+        }
+    }
+
+    lines
+}
@@ -0,0 +1,48 @@
+//! Markdown content embedded in files which aren't Markdown files (Rust doc comments and Jupyter notebooks)
+
+use std::ops::Range;
+
+/// Markdown content embedded in a file which isn't a Markdown file (e.g. the doc comments of a Rust source file)
+///
+/// The content is made of blocks which are parsed independently, and each of its lines is mapped to its line in the file.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EmbeddedMarkdown {
+    /// Content of all blocks, one after the other
+    pub content: String,
+
+    /// Line of the file each line of the content comes from (starting from 1)
+    /// In notebooks, lines are counted from the beginning of the cell they are located in.
+    pub source_lines: Vec<usize>,
+
+    /// Location of each block in the content
+    pub blocks: Vec<Range<usize>>,
+
+    /// Notebook cell each block comes from (starting from 1), if any
+    pub cells: Vec<Option<usize>>,
+}
+
+impl EmbeddedMarkdown {
+    /// Add a block to the content, from its lines and the line of the file each of them comes from
+    pub fn push_block(&mut self, lines: Vec<(usize, String)>, cell: Option<usize>) {
+        let start = self.content.len();
+
+        for (line_number, text) in lines {
+            self.content.push_str(&text);
+            self.content.push('\n');
+            self.source_lines.push(line_number);
+        }
+
+        self.blocks.push(start..self.content.len());
+        self.cells.push(cell);
+    }
+
+    /// Get the notebook cell a byte offset of the content is located in, if any
+    pub fn cell(&self, offset: usize) -> Option<usize> {
+        let block = self
+            .blocks
+            .partition_point(|block| block.end <= offset)
+            .min(self.blocks.len().saturating_sub(1));
+
+        self.cells.get(block).copied().flatten()
+    }
+}
@@ -0,0 +1,70 @@
+//! Errors of the checker's functions (see [`find_unreachable_files`](crate::find_unreachable_files) and [`CheckResult::into_result`](crate::CheckResult::into_result))
+
+use crate::{safe_canonicalize, CheckResult};
+use colored::Colorize;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Error preventing a function of the checker from completing
+#[derive(Debug)]
+pub enum CheckerError {
+    /// A file or directory could not be read
+    Unreadable {
+        /// Path which could not be read
+        path: PathBuf,
+
+        /// Reason why it could not be read
+        error: String,
+    },
+
+    /// A file could not be parsed (e.g. an invalid notebook)
+    Invalid {
+        /// Path of the file
+        path: PathBuf,
+
+        /// Reason why it could not be parsed
+        error: String,
+    },
+
+    /// An entry point does not exist
+    MissingEntryPoint {
+        /// Path of the entry point
+        path: PathBuf,
+
+        /// Error returned when looking for it
+        error: std::io::Error,
+    },
+
+    /// Broken links were found, with all the findings of the check (warnings included)
+    BrokenLinks(CheckResult),
+}
+
+impl fmt::Display for CheckerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable { path, error } => write!(
+                f,
+                "Failed to read '{}': {}",
+                safe_canonicalize(path).green(),
+                error
+            ),
+            Self::Invalid { path, error } => {
+                write!(f, "File at '{}' {}", safe_canonicalize(path).green(), error)
+            }
+            Self::MissingEntryPoint { path, error } => write!(
+                f,
+                "Failed to canonicalize entry point '{}': {}",
+                safe_canonicalize(path).green(),
+                error
+            ),
+            Self::BrokenLinks(result) => write!(
+                f,
+                "Found {} broken link(s) in {} file(s)",
+                result.broken_count(),
+                result.stats.files
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CheckerError {}
@@ -0,0 +1,71 @@
+use crate::read_text_file;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Content of a Markdown file being parsed
+///
+/// With the `mmap` feature, valid UTF-8 files are memory-mapped instead of being copied on the heap,
+///  which reduces the peak memory usage when large files are checked.
+pub(crate) enum FileContent {
+    /// Content read in memory
+    Owned(String),
+
+    /// Memory-mapped content starting at the provided offset (after the byte order mark if any)
+    ///
+    /// The mapping is validated as UTF-8 each time it's accessed, as the file could have been modified since it was mapped.
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap, usize),
+}
+
+impl FileContent {
+    /// Read a text file like [`read_text_file`], memory-mapping it if possible
+    pub fn read(path: &Path) -> std::io::Result<(Self, Option<String>)> {
+        #[cfg(feature = "mmap")]
+        {
+            let file = std::fs::File::open(path)?;
+
+            // Empty files can't be mapped on all platforms
+            if file.metadata()?.len() > 0 {
+                // SAFETY: the mapping is only used as a string after being validated as UTF-8 (see `deref`),
+                //  so a file modified while it's being checked gives an inconsistent result like when it's read in memory,
+                //  but never an invalid string (a file truncated meanwhile still stops the process, like any mapped file)
+                let map = unsafe { memmap2::Mmap::map(&file)? };
+
+                let start = if map.starts_with(b"\xEF\xBB\xBF") {
+                    3
+                } else {
+                    0
+                };
+
+                if std::str::from_utf8(&map[start..]).is_ok() {
+                    return Ok((Self::Mapped(map, start), None));
+                }
+
+                let (content, problem) = crate::decode_text(map.to_vec());
+                return Ok((Self::Owned(content), problem));
+            }
+        }
+
+        let (content, problem) = read_text_file(path)?;
+        Ok((Self::Owned(content), problem))
+    }
+}
+
+impl Deref for FileContent {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Self::Owned(content) => content,
+
+            // If the file was modified since it was mapped, only its valid beginning is used
+            #[cfg(feature = "mmap")]
+            Self::Mapped(map, start) => {
+                let bytes = &map[*start..];
+
+                std::str::from_utf8(bytes)
+                    .unwrap_or_else(|err| std::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap())
+            }
+        }
+    }
+}
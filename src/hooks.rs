@@ -0,0 +1,247 @@
+//! Installation of git hooks running the checker
+//!
+//! The commands added to a hook are located between two marker lines, so they can be added to an existing hook
+//!  and removed from it without touching the rest of the script.
+
+use crate::safe_canonicalize;
+use colored::Colorize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Line starting the commands added to a hook
+static HOOK_START_MARKER: &str = "# >>> broken-md-links >>>";
+
+/// Line ending the commands added to a hook
+static HOOK_END_MARKER: &str = "# <<< broken-md-links <<<";
+
+/// Beginning of the hook scripts created from scratch
+static HOOK_SHEBANG: &str = "#!/bin/sh";
+
+/// Git hook running the checker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GitHook {
+    /// Check the links of the staged Markdown files before committing, as they are staged
+    PreCommit,
+
+    /// Check the links of all Markdown files of the repository before pushing
+    PrePush,
+}
+
+impl GitHook {
+    /// Get the name of the hook's script
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PreCommit => "pre-commit",
+            Self::PrePush => "pre-push",
+        }
+    }
+
+    /// Get the commands added to the hook, running the provided executable
+    fn commands(self, exe: &Path) -> String {
+        // Quote the executable's path for the shell
+        let exe = format!("'{}'", exe.to_string_lossy().replace('\'', r"'\''"));
+
+        match self {
+            // The staged content of the repository is checked, from a copy of the index
+            Self::PreCommit => format!(
+                "(\n  \
+                     staged=\"$(mktemp -d)\" || exit 1\n  \
+                     trap 'rm -rf \"$staged\"' EXIT\n  \
+                     git checkout-index --all --prefix=\"$staged/\" || exit 1\n  \
+                     git -c core.quotePath=false diff --cached --name-only --diff-filter=ACMR -- '*.md' | while IFS= read -r file; do\n    \
+                         (cd \"$staged\" && {} --config-root . -- \"$file\") || exit 1\n  \
+                     done\n\
+                 ) || exit 1",
+                exe
+            ),
+            Self::PrePush => format!("{} \"$(git rev-parse --show-toplevel)\" -r || exit 1", exe),
+        }
+    }
+}
+
+/// What to do when installing a hook which already exists (and wasn't installed by the checker)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExistingHook {
+    /// Fail
+    Refuse,
+
+    /// Replace the existing script
+    Replace,
+
+    /// Add the commands at the end of the existing script
+    Append,
+}
+
+/// Run a git command in a directory, returning its output
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|err| format!("Failed to run git: {}", err))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to find the git repository at '{}': {}",
+            safe_canonicalize(dir).green(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Get the path of a hook's script in the git repository a directory is located in
+///
+/// The `core.hooksPath` setting is respected. As hooks are run from the root of the working tree,
+///  git is queried from there (relative hooks paths are relative to it).
+fn hook_path(dir: &Path, hook: GitHook) -> Result<PathBuf, String> {
+    let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?);
+    let hooks_dir = git(&root, &["rev-parse", "--git-path", "hooks"])?;
+
+    Ok(root.join(hooks_dir).join(hook.name()))
+}
+
+/// Install a hook running the checker in the git repository a directory is located in, returning the path of its script
+///
+/// If the checker was already added to the hook, its commands are updated.
+pub(crate) fn install_hook(
+    dir: &Path,
+    hook: GitHook,
+    existing: ExistingHook,
+    exe: &Path,
+) -> Result<PathBuf, String> {
+    let path = hook_path(dir, hook)?;
+
+    let commands = format!(
+        "{}\n{}\n{}\n",
+        HOOK_START_MARKER,
+        hook.commands(exe),
+        HOOK_END_MARKER
+    );
+
+    let script = match fs::read_to_string(&path) {
+        Ok(script) => match (marked_range(&script), existing) {
+            (Some(range), _) => {
+                let mut script = script;
+                script.replace_range(range, &commands);
+                script
+            }
+            (None, ExistingHook::Refuse) => {
+                return Err(format!(
+                    "A {} hook already exists at '{}', use '--force' to replace it or '--append' to add the check to it",
+                    hook.name(),
+                    safe_canonicalize(&path).green()
+                ))
+            }
+            (None, ExistingHook::Replace) => format!("{}\n\n{}", HOOK_SHEBANG, commands),
+            (None, ExistingHook::Append) => {
+                format!("{}\n\n{}", script.trim_end(), commands)
+            }
+        },
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            format!("{}\n\n{}", HOOK_SHEBANG, commands)
+        }
+        Err(err) => {
+            return Err(format!(
+                "Failed to read the hook at '{}': {}",
+                safe_canonicalize(&path).green(),
+                err
+            ))
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| {
+            format!(
+                "Failed to create the hooks directory at '{}': {}",
+                safe_canonicalize(parent).green(),
+                err
+            )
+        })?;
+    }
+
+    fs::write(&path, script).map_err(|err| {
+        format!(
+            "Failed to write the hook at '{}': {}",
+            safe_canonicalize(&path).green(),
+            err
+        )
+    })?;
+
+    // Hooks are only run if they are executable
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).map_err(|err| {
+            format!(
+                "Failed to make the hook at '{}' executable: {}",
+                safe_canonicalize(&path).green(),
+                err
+            )
+        })?;
+    }
+
+    Ok(path)
+}
+
+/// Remove the checker from a hook of the git repository a directory is located in, returning the path of its script
+///
+/// The rest of the script is left untouched, and the script is removed if nothing else remains in it.
+pub(crate) fn uninstall_hook(dir: &Path, hook: GitHook) -> Result<PathBuf, String> {
+    let path = hook_path(dir, hook)?;
+
+    let mut script = fs::read_to_string(&path).map_err(|err| {
+        format!(
+            "Failed to read the {} hook at '{}': {}",
+            hook.name(),
+            safe_canonicalize(&path).green(),
+            err
+        )
+    })?;
+
+    let range = marked_range(&script).ok_or_else(|| {
+        format!(
+            "The {} hook at '{}' doesn't run broken-md-links",
+            hook.name(),
+            safe_canonicalize(&path).green()
+        )
+    })?;
+
+    script.replace_range(range, "");
+
+    let result = match script.trim() {
+        "" => fs::remove_file(&path),
+        rest if rest == HOOK_SHEBANG => fs::remove_file(&path),
+        rest => fs::write(&path, format!("{}\n", rest)),
+    };
+
+    result.map_err(|err| {
+        format!(
+            "Failed to update the hook at '{}': {}",
+            safe_canonicalize(&path).green(),
+            err
+        )
+    })?;
+
+    Ok(path)
+}
+
+/// Get the location of the commands added by the checker in a hook's script (markers included), if any
+fn marked_range(script: &str) -> Option<std::ops::Range<usize>> {
+    let start = script.find(HOOK_START_MARKER)?;
+    let end = start + script[start..].find(HOOK_END_MARKER)? + HOOK_END_MARKER.len();
+
+    // Include the line break ending the block
+    let end = if script[end..].starts_with('\n') {
+        end + 1
+    } else {
+        end
+    };
+
+    Some(start..end)
+}
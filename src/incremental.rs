@@ -0,0 +1,750 @@
+//! Incremental checking across runs
+//!
+//! The state of each checked file (its fingerprint, the broken links found in it and the targets it depends on) is persisted
+//!  between runs, so only the files which changed or whose targets changed are checked again. The broken links of the other
+//!  files are replayed from the state.
+
+use crate::mdbook_includes;
+use crate::validate::{anchor_near_miss, validate_fragment_link, AnchorNearMiss, ValidationResult};
+use crate::{
+    changelog_issues_dir, check_broken_links_with, check_deadline, check_walked_file,
+    deadline_expired, embedded_markdown, markdown_blocks, markdown_events, modification_time,
+    read_text_file, report_unreadable_paths, safe_canonicalize, skip_large_files,
+    substitute_placeholders, timeout_finding, unused_definitions_as_links, walk_markdown_files,
+    BrokenLinkKind, CheckerOptions, DetectedBrokenLink, FileLinksCache, Fragment, MarkdownEvents,
+    Severity, TargetsCache, EMAIL_REGEX, ISSUE_LINK_REGEX, URL_SCHEME_REGEX,
+    WINDOWS_ABSOLUTE_PATH_REGEX,
+};
+use colored::Colorize;
+use log::{debug, error, info, warn};
+use pulldown_cmark::{Event, LinkType, Tag};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Version of the state's format, states with another version are ignored
+static STATE_VERSION: u32 = 9;
+
+/// Hash some content, with a hash which doesn't depend on the platform nor on the compiler's version as it's persisted
+fn stable_hash(content: &[u8]) -> String {
+    format!("{:016x}", xxh3_64(content))
+}
+
+/// Fingerprint of a file's content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileFingerprint {
+    /// Size in bytes
+    size: u64,
+
+    /// Modification time
+    modified: String,
+
+    /// Hash of the content
+    hash: String,
+}
+
+impl FileFingerprint {
+    /// Compute the fingerprint of a file
+    fn compute(path: &Path) -> Option<Self> {
+        let content = std::fs::read(path).ok()?;
+
+        Some(Self {
+            size: content.len() as u64,
+            modified: modification_time(path)?,
+            hash: stable_hash(&content),
+        })
+    }
+
+    /// Check if a file still has this fingerprint
+    /// The content is only hashed if the size is the same but the modification time is not
+    fn matches(&self, path: &Path) -> bool {
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() && metadata.len() == self.size => {
+                modification_time(path).as_ref() == Some(&self.modified)
+                    || FileFingerprint::compute(path).map(|current| current.hash)
+                        == Some(self.hash.clone())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// State of a link's target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum TargetState {
+    /// The target does not exist
+    Missing,
+
+    /// The target is a directory
+    Directory,
+
+    /// The target is a file
+    File(FileFingerprint),
+}
+
+impl TargetState {
+    /// Get the current state of a target
+    fn compute(target: &Path) -> Self {
+        let target = Path::new(&safe_canonicalize(target)).to_owned();
+
+        if target.is_dir() {
+            Self::Directory
+        } else if target.is_file() {
+            FileFingerprint::compute(&target)
+                .map(Self::File)
+                .unwrap_or(Self::Missing)
+        } else {
+            Self::Missing
+        }
+    }
+}
+
+/// A target a file depends on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Dependency {
+    /// State of the target when the file was checked
+    state: TargetState,
+
+    /// Page the target redirects to when the file was checked (see [`CheckerOptions::redirect_markers`])
+    redirect: Option<String>,
+
+    /// Marker of the target if it was a draft when the file was checked (see [`CheckerOptions::draft_markers`])
+    draft: Option<String>,
+
+    /// Anchor aliases declared in the target when the file was checked (see [`inline_anchor_aliases`](crate::inline_anchor_aliases))
+    anchor_aliases: BTreeMap<String, String>,
+
+    /// Fragments of the target the file links to, with their validation outcome when the file was checked
+    fragments: BTreeMap<String, FragmentOutcome>,
+}
+
+impl Dependency {
+    /// Get the current state of a target, without any fragment
+    fn compute(target: &Path, options: &CheckerOptions, targets_cache: &mut TargetsCache) -> Self {
+        let state = TargetState::compute(target);
+
+        let canonical = match state {
+            TargetState::File(_) => target.canonicalize().ok(),
+            _ => None,
+        };
+
+        let redirect = canonical
+            .as_ref()
+            .filter(|_| !options.redirect_markers.is_empty())
+            .and_then(|canonical| {
+                targets_cache.redirect_target(canonical, &options.redirect_markers)
+            });
+
+        let draft = canonical
+            .as_ref()
+            .filter(|_| !options.draft_markers.is_empty())
+            .and_then(|canonical| targets_cache.draft_marker(canonical, &options.draft_markers));
+
+        let anchor_aliases = canonical
+            .as_ref()
+            .map(|canonical| {
+                targets_cache
+                    .inline_anchor_aliases(canonical)
+                    .iter()
+                    .map(|(alias, anchor)| (alias.clone(), anchor.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            state,
+            redirect,
+            draft,
+            anchor_aliases,
+            fragments: BTreeMap::new(),
+        }
+    }
+
+    /// Check if a target changed in a way that could change the broken links found in a file depending on it
+    fn changed(
+        &self,
+        target: &Path,
+        options: &CheckerOptions,
+        links_cache: &mut FileLinksCache,
+        targets_cache: &mut TargetsCache,
+    ) -> bool {
+        // Avoid hashing the target if it didn't change
+        if let TargetState::File(ref fingerprint) = self.state {
+            if fingerprint.matches(target) {
+                return false;
+            }
+        }
+
+        let current = Self::compute(target, options, targets_cache);
+
+        match (&self.state, &current.state) {
+            // If a target's content changed, only what the file sees of it matters
+            (TargetState::File(_), TargetState::File(_)) => {
+                self.redirect != current.redirect
+                    || self.draft != current.draft
+                    || self.anchor_aliases != current.anchor_aliases
+                    || self.fragments.iter().any(|(fragment, outcome)| {
+                        FragmentOutcome::compute(target, fragment, options, links_cache) != *outcome
+                    })
+            }
+
+            (previous, current) => previous != current,
+        }
+    }
+}
+
+/// Outcome of the validation of a link's fragment, classified like the checker reports it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum FragmentOutcome {
+    /// The fragment points to an existing location
+    Valid,
+
+    /// The fragment can't be checked, or points to a location in an unexpected way
+    Warning(String),
+
+    /// The fragment points to no location
+    Broken,
+
+    /// The fragment points to no anchor, but is very likely meant to point to an existing one
+    NearMiss(AnchorNearMiss, String),
+}
+
+impl FragmentOutcome {
+    /// Validate a link's fragment
+    fn compute(
+        target: &Path,
+        fragment: &str,
+        options: &CheckerOptions,
+        cache: &mut FileLinksCache,
+    ) -> Self {
+        let fragment = Fragment::parse(fragment);
+
+        match validate_fragment_link(target, &fragment, &options.slugs_for(target), cache) {
+            ValidationResult::Valid => Self::Valid,
+            ValidationResult::Warning(warning) => Self::Warning(warning),
+            ValidationResult::Broken(_) => {
+                let anchor = match fragment {
+                    Fragment::Slug(anchor) | Fragment::HtmlId(anchor) => anchor,
+                    _ => return Self::Broken,
+                };
+
+                target
+                    .canonicalize()
+                    .ok()
+                    .and_then(|canonical| cache.get(&canonical))
+                    .and_then(|anchors| {
+                        anchor_near_miss(&anchor, &anchors)
+                            .map(|(near_miss, anchor)| Self::NearMiss(near_miss, anchor.to_owned()))
+                    })
+                    .unwrap_or(Self::Broken)
+            }
+        }
+    }
+}
+
+/// State of a checked file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileState {
+    /// Fingerprint of the file when it was checked
+    fingerprint: FileFingerprint,
+
+    /// Broken links found in the file
+    findings: Vec<DetectedBrokenLink>,
+
+    /// Targets the file depends on, indexed by their path
+    dependencies: BTreeMap<PathBuf, Dependency>,
+}
+
+/// State of incremental checks, persisted between runs
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{CheckerOptions, FileLinksCache, TargetsCache};
+/// use broken_md_links::incremental::{check_broken_links_incremental, IncrementalState};
+///
+/// let options = CheckerOptions::default();
+/// let state_file = Path::new(".broken-md-links-state.json");
+///
+/// let mut state = IncrementalState::load(state_file, &options).unwrap_or_default();
+/// let errors = check_broken_links_incremental(Path::new("docs"), true, &options, &mut FileLinksCache::new(), &mut TargetsCache::new(), &mut state).unwrap();
+/// state.save(state_file).unwrap();
+///
+/// println!("{} broken link(s), {} file(s) checked again", errors.len(), state.rechecked_files().len());
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IncrementalState {
+    /// Version of the state's format
+    version: u32,
+
+    /// Fingerprint of the options the files were checked with, as the broken links depend on them
+    options: String,
+
+    /// State of each checked file
+    files: BTreeMap<PathBuf, FileState>,
+
+    /// Files which were checked again during the last run
+    #[serde(skip)]
+    rechecked: Vec<PathBuf>,
+}
+
+impl IncrementalState {
+    /// Load a state from a file previously written by [`IncrementalState::save`]
+    ///
+    /// If the state was written by another version or with different options, an empty state is returned.
+    pub fn load(path: &Path, options: &CheckerOptions) -> Result<Self, String> {
+        // Get the canonicalized path for display
+        let canon = safe_canonicalize(path);
+
+        debug!("Loading incremental state from file: {}", canon);
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read state file at '{}': {}", canon.green(), err))?;
+
+        let state: Self = serde_json::from_str(&content).map_err(|err| {
+            format!(
+                "State file at '{}' is not in a supported format: {}",
+                canon.green(),
+                err
+            )
+        })?;
+
+        if state.version != STATE_VERSION || state.options != options_fingerprint(options) {
+            debug!("Ignoring state file written by another version or with different options");
+            return Ok(Self::default());
+        }
+
+        Ok(state)
+    }
+
+    /// Write the state to a file, so it can be loaded again with [`IncrementalState::load`]
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        // Get the canonicalized path for display
+        let canon = safe_canonicalize(path);
+
+        debug!("Writing incremental state to file: {}", canon);
+
+        let content = serde_json::to_string(self)
+            .map_err(|err| format!("Failed to serialize state for '{}': {}", canon.green(), err))?;
+
+        std::fs::write(path, content)
+            .map_err(|err| format!("Failed to write state file at '{}': {}", canon.green(), err))
+    }
+
+    /// Get the files which were checked again during the last run (the other ones were replayed from the state)
+    pub fn rechecked_files(&self) -> &[PathBuf] {
+        &self.rechecked
+    }
+}
+
+/// Get the fingerprint of checker options
+///
+/// All options are listed, so the ones added later have to be considered here.
+fn options_fingerprint(options: &CheckerOptions) -> String {
+    let CheckerOptions {
+        ignore_header_links,
+        only_files,
+        no_errors,
+        check_mailto,
+        check_path_style,
+        check_extension_case,
+        max_link_length,
+        changelog_mode,
+        issues_dir,
+        require_alt_text,
+        check_image_dimensions,
+        ignore_images,
+        ignore_unresolved_references,
+        ignore_html_blocks,
+        check_toc,
+        check_mdbook_includes,
+        navigation_file,
+        navigation_order_severity,
+        lenient_space_destinations,
+        no_ignore,
+        halt_on_io_error,
+        follow_symlinks,
+        max_file_size,
+        check_http_links,
+        follow_redirects,
+        http_timeout,
+        invalid_utf8,
+        redirect_markers,
+        draft_markers,
+        frontmatter_required_fields,
+        partial_dirs,
+        partial_context_severity,
+        anchor_aliases,
+        warn_anchor_aliases,
+        anchor_case_severity,
+        anchor_punctuation_severity,
+        anchor_plural_severity,
+        link_text_is_url,
+        placeholders,
+        scheme_handlers,
+        rust_doc_comments,
+        error_on_warnings,
+        slugs,
+        directory_configs,
+        targets_only,
+        // These options don't change the results of the checked files, only how and when they are displayed
+        dedupe_findings: _,
+        group_by_target: _,
+        timeout: _,
+        profile: _,
+        max_concurrent_dirs: _,
+        parallel: _,
+    } = options;
+
+    let mut fingerprint = String::new();
+
+    macro_rules! fingerprint {
+        ($($option: ident),*) => {
+            $( writeln!(fingerprint, "{}: {:?}", stringify!($option), $option).unwrap(); )*
+        };
+    }
+
+    fingerprint!(
+        ignore_header_links,
+        only_files,
+        no_errors,
+        check_mailto,
+        check_path_style,
+        check_extension_case,
+        max_link_length,
+        changelog_mode,
+        issues_dir,
+        require_alt_text,
+        check_image_dimensions,
+        ignore_images,
+        ignore_unresolved_references,
+        ignore_html_blocks,
+        check_toc,
+        check_mdbook_includes,
+        navigation_file,
+        navigation_order_severity,
+        lenient_space_destinations,
+        no_ignore,
+        halt_on_io_error,
+        follow_symlinks,
+        max_file_size,
+        check_http_links,
+        follow_redirects,
+        http_timeout,
+        invalid_utf8,
+        redirect_markers,
+        draft_markers,
+        frontmatter_required_fields,
+        partial_dirs,
+        partial_context_severity,
+        anchor_aliases,
+        warn_anchor_aliases,
+        anchor_case_severity,
+        anchor_punctuation_severity,
+        anchor_plural_severity,
+        link_text_is_url,
+        placeholders,
+        scheme_handlers,
+        rust_doc_comments,
+        error_on_warnings,
+        slugs,
+        directory_configs,
+        targets_only
+    );
+
+    stable_hash(fingerprint.as_bytes())
+}
+
+/// Get the targets a file depends on
+fn file_dependencies(
+    path: &Path,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> Result<BTreeMap<PathBuf, Dependency>, String> {
+    let (content, _) = read_text_file(path).map_err(|err| {
+        format!(
+            "Failed to read file at '{}': {}",
+            safe_canonicalize(path).green(),
+            err
+        )
+    })?;
+
+    let options = &*options.resolve(path);
+    let issues_dir = changelog_issues_dir(path, options);
+
+    let mut dependencies = BTreeMap::<PathBuf, Dependency>::new();
+    let mut add_dependency = |target: PathBuf, fragment: Option<&str>| {
+        let key = PathBuf::from(safe_canonicalize(&target));
+
+        let dependency = dependencies
+            .entry(key)
+            .or_insert_with(|| Dependency::compute(&target, options, targets_cache));
+
+        if let Some(fragment) = fragment {
+            if !dependency.fragments.contains_key(fragment) {
+                let outcome = FragmentOutcome::compute(&target, fragment, options, links_cache);
+                dependency.fragments.insert(fragment.to_owned(), outcome);
+            }
+        }
+    };
+
+    // Files whose embedded Markdown can't be extracted (e.g. invalid notebooks) are skipped by the checker
+    let embedded = match embedded_markdown(path, &content, options.rust_doc_comments) {
+        Ok(embedded) => embedded,
+        Err(_) => return Ok(dependencies),
+    };
+
+    let (content, _, blocks) = markdown_blocks(&content, embedded.as_ref());
+
+    let MarkdownEvents {
+        events,
+        definitions,
+        ..
+    } = markdown_events(content, &blocks, options);
+
+    // Files included by mdBook's directives are read when checking the file
+    if options.check_mdbook_includes && embedded.is_none() {
+        for include in mdbook_includes::includes(path, content) {
+            add_dependency(include.path, None);
+        }
+    }
+
+    // Unused reference definitions are checked like links
+    let events = unused_definitions_as_links(events, definitions);
+
+    for (event, range, _) in events {
+        // Images only depend on the existence of their target
+        if let Event::Start(Tag::Image { dest_url, .. }) = event {
+            let target = dest_url.split('#').next().unwrap();
+
+            if !options.ignore_images
+                && !target.is_empty()
+                && !URL_SCHEME_REGEX.is_match(target)
+                && !WINDOWS_ABSOLUTE_PATH_REGEX.is_match(target)
+            {
+                add_dependency(path.parent().unwrap().join(target), None);
+            }
+
+            continue;
+        }
+
+        if let Event::Start(Tag::Link {
+            link_type:
+                LinkType::Inline | LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut,
+            dest_url,
+            ..
+        }) = event
+        {
+            // Issue links of changelogs depend on the issue's file
+            if let Some(ref issues_dir) = issues_dir {
+                if let Some(issue) = ISSUE_LINK_REGEX.captures(&content[range]) {
+                    add_dependency(issues_dir.join(format!("{}.md", &issue[1])), None);
+                }
+            }
+
+            // Links with unresolved placeholders don't depend on any target
+            let dest_url = match substitute_placeholders(&dest_url, &options.placeholders) {
+                Ok(dest_url) => dest_url,
+                Err(_) => continue,
+            };
+
+            let (target, fragment) = match dest_url.split_once('#') {
+                Some((target, fragment)) => (target, Some(fragment)),
+                None => (dest_url.as_ref(), None),
+            };
+
+            if WINDOWS_ABSOLUTE_PATH_REGEX.is_match(target)
+                || URL_SCHEME_REGEX.is_match(target)
+                || EMAIL_REGEX.is_match(target)
+            {
+                continue;
+            }
+
+            let target = if target.is_empty() {
+                path.to_owned()
+            } else {
+                path.parent().unwrap().join(target)
+            };
+
+            add_dependency(target, fragment.filter(|_| !options.ignore_header_links));
+        }
+    }
+
+    Ok(dependencies)
+}
+
+/// Check broken links in a Markdown file or directory, only checking again the files which changed since the previous run
+///  or whose targets changed
+///
+/// The broken links of the other files are replayed from the provided state, which is then updated.
+/// The result is the same as [`check_broken_links_with`] would return.
+///
+/// Scheme handlers must have a version (see [`CheckerOptions::versioned_scheme_handler`]), which is part of the options' fingerprint.
+/// HTTP and HTTPS links can't be checked (see [`CheckerOptions::check_http_links`]), as their results would be replayed forever.
+pub fn check_broken_links_incremental(
+    path: &Path,
+    dir: bool,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+    state: &mut IncrementalState,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    // The verdicts of scheme handlers are replayed, so they must tell when they may change
+    if !options.scheme_handlers.are_versioned() {
+        return Err("Incremental checks require scheme handlers to have a version, see 'CheckerOptions::versioned_scheme_handler'".to_owned());
+    }
+
+    // URLs may break at any time, without any local change to invalidate the results
+    if cfg!(feature = "check-http") && options.check_http_links {
+        return Err("Incremental checks can't check HTTP and HTTPS links".to_owned());
+    }
+
+    let deadline = check_deadline(options);
+    let mut errors = vec![];
+
+    let files = if dir {
+        let (files, failures, warnings) = walk_markdown_files(path, options);
+
+        // Unreadable and skipped entries are always reported again
+        errors.extend(report_unreadable_paths(failures, options)?);
+        errors.extend(warnings);
+
+        files
+    } else {
+        vec![path.to_owned()]
+    };
+
+    let (files, warnings) = skip_large_files(files, options);
+    errors.extend(warnings);
+
+    let mut previous_files = std::mem::take(&mut state.files);
+
+    state.version = STATE_VERSION;
+    state.options = options_fingerprint(options);
+    state.rechecked.clear();
+
+    // Findings of each file, in the order of the files
+    let mut findings = vec![vec![]; files.len()];
+
+    // Files which must be checked again, after replaying the results of the others so a timeout only affects them
+    let mut changed = vec![];
+
+    for (index, file) in files.into_iter().enumerate() {
+        let previous = previous_files.remove(&file).filter(|previous| {
+            previous.fingerprint.matches(&file)
+                // Suggestions for machine-specific paths depend on the whole tree
+                && previous
+                    .findings
+                    .iter()
+                    .all(|finding| finding.kind != BrokenLinkKind::MachineSpecificPath)
+                && previous
+                    .dependencies
+                    .iter()
+                    .all(|(target, dependency)| !dependency.changed(target, options, links_cache, targets_cache))
+        });
+
+        let previous = match previous {
+            Some(previous) => previous,
+            None => {
+                changed.push((index, file));
+                continue;
+            }
+        };
+
+        debug!(
+            "Replaying results of unchanged file: {}",
+            safe_canonicalize(&file)
+        );
+
+        let mut displayed = HashSet::new();
+
+        for finding in &previous.findings {
+            if options.dedupe_findings
+                && !displayed.insert((finding.cell, finding.kind, &finding.error))
+            {
+                continue;
+            }
+
+            if options.group_by_target && finding.kind == BrokenLinkKind::MissingTarget {
+                continue;
+            }
+
+            let message = format!(
+                "In {}{} {}",
+                safe_canonicalize(&finding.file).green(),
+                finding.location().yellow(),
+                finding.error
+            );
+
+            if finding.severity == Severity::Info {
+                info!("{}", message);
+            } else if finding.severity == Severity::Warning || options.no_errors {
+                warn!("{}", message);
+            } else {
+                error!("{}", message);
+            }
+        }
+
+        findings[index] = previous.findings.clone();
+        state.files.insert(file, previous);
+    }
+
+    let changed_count = changed.len();
+
+    for (checked, (index, file)) in changed.into_iter().enumerate() {
+        // Files left unchecked have no state, so they are checked on the next run
+        if deadline_expired(deadline) {
+            findings.push(vec![timeout_finding(
+                path,
+                changed_count - checked,
+                options,
+            )]);
+            break;
+        }
+
+        let file_findings = if dir {
+            check_walked_file(&file, options, links_cache, targets_cache)?
+        } else {
+            check_broken_links_with(&file, false, options, links_cache, targets_cache)?
+        };
+
+        // Unreadable files are checked again on the next run
+        if file_findings
+            .iter()
+            .any(|finding| finding.kind == BrokenLinkKind::UnreadablePath)
+        {
+            findings[index] = file_findings;
+            continue;
+        }
+
+        let fingerprint = FileFingerprint::compute(&file).ok_or_else(|| {
+            format!(
+                "Failed to read file at '{}'",
+                safe_canonicalize(&file).green()
+            )
+        })?;
+
+        state.rechecked.push(file.clone());
+
+        findings[index] = file_findings.clone();
+
+        state.files.insert(
+            file.clone(),
+            FileState {
+                fingerprint,
+                findings: file_findings,
+                dependencies: file_dependencies(&file, options, links_cache, targets_cache)?,
+            },
+        );
+    }
+
+    errors.extend(findings.into_iter().flatten());
+
+    Ok(errors)
+}
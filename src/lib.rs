@@ -3,6 +3,9 @@
 //! By default, this tool detects broken links like "[foo](file.md)" (target file does not exist)
 //! and broken header links like "[foo](file.md#header)" (target file exists but specific header does not exist)
 //!
+//! The other checks, the options and the configuration files are described in the
+//! [README](https://github.com/ClementNerma/broken-md-links#readme).
+//!
 //! ## Command-line usage
 //!
 //! Check a single file:
@@ -32,480 +35,5597 @@
 //!
 //! ## Library usage
 //!
-//! ```
-//! use broken_md_links::check_broken_links;
+//! ```no_run
+//! use std::path::Path;
+//! use broken_md_links::{check_broken_links_simple, CheckerOptions};
 //!
-//! match check_broken_links(Path::new("file.md"), false, false, false, &mut HashMap::new()) {
-//!   Ok(0) => println!("No broken link :D"),
-//!   Ok(errors @ _) => println!("There are {} broken links :(", errors),
+//! let options = CheckerOptions::builder().check_toc(true).build();
+//!
+//! match check_broken_links_simple(Path::new("file.md"), &options) {
+//!   Ok(errors) if errors.is_empty() => println!("No broken link :D"),
+//!   Ok(errors) => println!("There are {} broken links :(", errors.len()),
 //!   Err(err) => println!("Something went wrong :( : {}", err)
 //! }
 //! ```
+//!
+//! All public types can be imported at once with `use broken_md_links::types::*;`.
+//! `check_broken_links` is deprecated: use `check_broken_links_simple` instead (directories are detected automatically),
+//! or `check_broken_links_with` to keep the caches between several checks.
+//! The command-line tool itself can be embedded in other programs with `cli::run`, which takes the same arguments (with the default `serde` feature).
 
 use colored::Colorize;
+use ignore::{WalkBuilder, WalkState};
 use lazy_static::lazy_static;
 use log::{debug, error, info, trace, warn};
-use pulldown_cmark::{BrokenLink, Event, LinkType, Options, Parser, Tag};
+use pulldown_cmark::{BrokenLink, CowStr, Event, LinkType, Options, Parser, Tag, TagEnd};
 use regex::Regex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
+use std::ops::Range;
 use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+use validate::{
+    missing_target_reason, validate_fragment_link, validate_same_file_fragment, ValidationResult,
+};
 
-lazy_static! {
-    static ref EMAIL_REGEX: Regex = Regex::new("\
-        (?:[a-z0-9!#$%&'*+/=?^_`{|}~-]+(?:\\.[a-z0-9!#$%&'*+/=?^_`{|}~-]+)*|\"\
-        (?:[\\x01-\\x08\\x0b\\x0c\\x0e-\\x1f\\x21\\x23-\\x5b\\x5d-\\x7f]|\\\\[\\x01-\\x09\\x0b\\x0c\\x0e-\\x7f])*\")@\
-        (?:(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\\.)+[a-z0-9](?:[a-z0-9-]*[a-z0-9])?|\\[\
-        (?:(?:(2(5[0-5]|[0-4][0-9])|1[0-9][0-9]|[1-9]?[0-9]))\\.){3}(?:(2(5[0-5]|[0-4][0-9])|1[0-9][0-9]|[1-9]?[0-9])|[a-z0-9-]*[a-z0-9]:\
-        (?:[\\x01-\\x08\\x0b\\x0c\\x0e-\\x1f\\x21-\\x5a\\x53-\\x7f]|\\\\[\\x01-\\x09\\x0b\\x0c\\x0e-\\x7f])+)\\])"
-    ).unwrap();
-}
+#[cfg(feature = "serde")]
+pub mod cli;
+pub mod directory_config;
+#[cfg(feature = "serde")]
+pub mod incremental;
+pub mod link_graph;
+pub mod partials;
+pub mod profile;
+#[cfg(feature = "serde")]
+pub mod report_diff;
+pub mod validate;
 
-/// Canonicalize a path and display it as a lossy string
+mod check_stats;
+#[cfg(feature = "content-hash")]
+pub mod content_hash;
+mod doc_comments;
+mod embedded_markdown;
+mod error;
+mod file_content;
+#[cfg(feature = "serde")]
+mod hooks;
+mod line_index;
+mod link_checks;
+mod links_cache;
+mod mdbook_includes;
+#[cfg(feature = "notebooks")]
+mod notebooks;
+mod options_builder;
+mod output;
+#[cfg(feature = "async")]
+mod streaming;
+mod targets_cache;
+#[cfg(feature = "watch")]
+mod watch;
+
+pub use check_stats::{CheckResult, CheckStats};
+use directory_config::{DirectoryConfigs, GitignorePatterns};
+use doc_comments::extract_doc_comments;
+use embedded_markdown::EmbeddedMarkdown;
+pub use error::CheckerError;
+use file_content::FileContent;
+pub use line_index::LineIndex;
+use link_checks::LinkFinding;
+pub use links_cache::{FileLinksCache, LinksCache, LinksCacheStats};
+pub use options_builder::CheckerOptionsBuilder;
+#[cfg(feature = "async")]
+pub use streaming::check_broken_links_streaming;
+pub use targets_cache::{ResolvedTarget, TargetsCache};
+
+/// All public types of the crate, to import them at once
 ///
 /// # Examples
 ///
 /// ```
-/// use std::path::Path;
-/// use broken_md_links::safe_canonicalize;
+/// use broken_md_links::types::*;
 ///
-/// let path = Path::new("../a/b/../c");
+/// let options = CheckerOptions { slugs: SlugOptions { emoji_handling: EmojiHandling::Transliterate, ..SlugOptions::default() }, ..CheckerOptions::default() };
+/// let cache = FileLinksCache::new();
 ///
-/// assert_eq!(path.to_string_lossy(), "../a/b/../c");
-/// assert_eq!(safe_canonicalize(path), "../a/c");
+/// assert_eq!(Fragment::parse("L3"), Fragment::LineNumber(3));
+/// # let _ = (options, cache);
 /// ```
-pub fn safe_canonicalize(path: &Path) -> String {
-    // Components of the canonicalized path
-    let mut out = vec![];
+pub mod types {
+    pub use super::directory_config::{ConfigOverrides, DirectoryConfigs, GitignorePatterns};
+    #[cfg(feature = "serde")]
+    pub use super::incremental::IncrementalState;
+    #[cfg(feature = "serde")]
+    pub use super::report_diff::{JsonReport, ReportDiff, ReportSummary};
+    pub use super::validate::ValidationResult;
+    pub use super::{
+        AnchorAliases, BrokenLinkKind, BrokenTarget, CheckResult, CheckStats, CheckerError,
+        CheckerOptions, CheckerOptionsBuilder, DedupedBrokenLink, DetectedBrokenLink,
+        EmojiHandling, ExtractedLink, FileLinksCache, FindingLocation, Fragment,
+        InvalidUtf8Handling, LineIndex, LinksCache, LinksCacheStats, MathSlugHandling,
+        PlaceholderOptions, ResolvedTarget, SchemeHandler, SchemeHandlers, SchemeVerdict, Severity,
+        SlugAlgorithm, SlugOptions, TargetGroup, TargetsCache,
+    };
+}
 
-    for comp in path.components() {
-        match comp {
-            // Prefixes, root directories and normal components are kept "as is"
-            Component::Prefix(_) | Component::RootDir | Component::Normal(_) => out.push(comp),
+/// Pattern matching an e-mail address
+static EMAIL_PATTERN: &str = "\
+        (?:[a-z0-9!#$%&'*+/=?^_`{|}~-]+(?:\\.[a-z0-9!#$%&'*+/=?^_`{|}~-]+)*|\"\
+        (?:[\\x01-\\x08\\x0b\\x0c\\x0e-\\x1f\\x21\\x23-\\x5b\\x5d-\\x7f]|\\\\[\\x01-\\x09\\x0b\\x0c\\x0e-\\x7f])*\")@\
+        (?:(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\\.)+[a-z0-9](?:[a-z0-9-]*[a-z0-9])?|\\[\
+        (?:(?:(2(5[0-5]|[0-4][0-9])|1[0-9][0-9]|[1-9]?[0-9]))\\.){3}(?:(2(5[0-5]|[0-4][0-9])|1[0-9][0-9]|[1-9]?[0-9])|[a-z0-9-]*[a-z0-9]:\
+        (?:[\\x01-\\x08\\x0b\\x0c\\x0e-\\x1f\\x21-\\x5a\\x53-\\x7f]|\\\\[\\x01-\\x09\\x0b\\x0c\\x0e-\\x7f])+)\\])";
 
-            // "Current dir" symbols (e.g. ".") are useless so they are not kept
-            Component::CurDir => {}
+lazy_static! {
+    static ref EMAIL_REGEX: Regex = Regex::new(EMAIL_PATTERN).unwrap();
+    static ref EMAIL_ADDRESS_REGEX: Regex =
+        Regex::new(&format!("(?i)^(?:{})$", EMAIL_PATTERN)).unwrap();
+    static ref URL_SCHEME_REGEX: Regex = Regex::new("^([a-zA-Z][a-zA-Z0-9+.-]*):").unwrap();
+    static ref HTML_MARKDOWN_LINK_REGEX: Regex = Regex::new(
+        r#"(!?)\[([^\]]*)\]\(\s*(?:<([^>]*)>|([^\s)]*))(?:\s+(?:"[^"]*"|'[^']*'))?\s*\)"#
+    )
+    .unwrap();
+    static ref SPACED_DESTINATION_LINK_REGEX: Regex =
+        Regex::new(r"(!?)\[([^\[\]]*)\]\(([^()<>\s]+(?: +[^()<>\s]+)+)\)").unwrap();
+    static ref ISSUE_LINK_REGEX: Regex = Regex::new(r"^\[#(\d+)\]").unwrap();
+    static ref HTML_ANCHOR_REGEX: Regex =
+        Regex::new(r#"(?i)\b(?:id|name)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    static ref LINE_NUMBER_FRAGMENT_REGEX: Regex = Regex::new("^L([1-9][0-9]*)$").unwrap();
+    static ref WINDOWS_ABSOLUTE_PATH_REGEX: Regex = Regex::new(r"^(?:[a-zA-Z]:[\\/]|\\)").unwrap();
+    static ref ANCHOR_ALIAS_REGEX: Regex =
+        Regex::new(r"<!--\s*broken-md-links:anchor-alias\s+#?([^\s=]+)=#?(\S+?)\s*-->").unwrap();
+    static ref NUMERIC_PREFIX_REGEX: Regex = Regex::new(r"^(\d+)[-_. ]").unwrap();
+    static ref LATEX_COMMAND_REGEX: Regex = Regex::new(r"\\([a-zA-Z]+|.)").unwrap();
+    static ref QUOTED_VALUE_REGEX: Regex =
+        Regex::new(r"(^|[\s(])((?:header|anchor) )?'([^'\n]+)'").unwrap();
+}
 
-            // "Parent dir" symbols (e.g. "..") will remove the previous component *ONLY* if it's a normal one
-            // Else, if the path is relative the symbol will be kept to preserve the relativety of the path
-            Component::ParentDir => {
-                if let Some(Component::Normal(_)) = out.last() {
-                    out.pop();
-                } else if path.is_relative() {
-                    out.push(Component::ParentDir)
-                }
-            }
-        }
-    }
+/// Beginning of the first line of persistent cache files, used to detect unsupported formats
+static CACHE_FILE_HEADER: &str = "broken-md-links cache v2";
 
-    // Create a path from the components and display it as a lossy string
-    out.iter()
-        .collect::<PathBuf>()
-        .to_string_lossy()
-        .into_owned()
+/// How emojis in headers are handled when generating slugs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmojiHandling {
+    /// Remove emojis
+    #[default]
+    Strip,
+
+    /// Replace emojis with their short name (e.g. 🚀 becomes `rocket`)
+    Transliterate,
 }
 
-/// Slugify a Markdown header
-/// This function is used to generate slugs from all headers of a Markdown file (see the 'generate_slugs' function)
+/// How math formulas in headers (e.g. `$E = mc^2$`) are handled when generating slugs
 ///
 /// # Examples
 ///
-/// ```
-/// use broken_md_links::slugify;
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{generate_slugs_with, MathSlugHandling, SlugOptions};
 ///
-/// assert_eq!(slugify("My super header"), "my-super-header");
-/// assert_eq!(slugify("I love headers!"), "i-love-headers");
+/// let options = SlugOptions { math_in_slug: MathSlugHandling::IncludePlain, ..SlugOptions::default() };
+///
+/// // A `## Vector $\mathbf{v}$` header gets the `vector-v` slug
+/// let slugs = generate_slugs_with(Path::new("physics.md"), &options).unwrap();
 /// ```
-pub fn slugify(header: &str) -> String {
-    header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum MathSlugHandling {
+    /// Remove math formulas
+    #[default]
+    #[serde(rename = "strip")]
+    Strip,
+
+    /// Keep the LaTeX source of math formulas (e.g. `\mathbf{v}` becomes `mathbfv` once slugified)
+    #[serde(rename = "raw")]
+    IncludeRaw,
+
+    /// Convert math formulas to a plain text approximation, removing formatting commands (e.g. `\mathbf{x}` becomes `x`)
+    #[serde(rename = "plain")]
+    IncludePlain,
+}
+
+/// LaTeX commands which only change how math is displayed, so they are removed from plain text approximations
+static LATEX_FORMATTING_COMMANDS: &[&str] = &[
+    "left",
+    "right",
+    "big",
+    "Big",
+    "bigg",
+    "Bigg",
+    "displaystyle",
+    "textstyle",
+    "mathrm",
+    "mathbf",
+    "mathit",
+    "mathsf",
+    "mathtt",
+    "mathcal",
+    "mathbb",
+    "mathfrak",
+    "boldsymbol",
+    "operatorname",
+    "text",
+    "textbf",
+    "textit",
+    "quad",
+    "qquad",
+    ",",
+    ";",
+    ":",
+    "!",
+    " ",
+];
+
+/// Convert LaTeX math to a plain text approximation
+/// Commands are replaced with their name (e.g. `\alpha` becomes `alpha`), except formatting ones which are removed,
+///  and grouping characters (braces, subscripts and superscripts) are removed as well.
+fn latex_to_plain(math: &str) -> String {
+    LATEX_COMMAND_REGEX
+        .replace_all(math, |captures: &regex::Captures| {
+            let command = &captures[1];
+
+            if LATEX_FORMATTING_COMMANDS.contains(&command) {
+                String::new()
+            } else {
+                format!("{} ", command)
+            }
+        })
         .chars()
-        .map(|c| if c == ' ' { '-' } else { c })
-        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .filter(|c| !matches!(c, '{' | '}' | '^' | '_'))
         .collect::<String>()
-        .to_lowercase()
+        .trim_end()
+        .to_owned()
 }
 
-/// Get all headers of a Markdown file as slugs
-/// This function is used to check if the header specified in a link exists in the target file
-/// Returns an error message if the operation failed for any reason
-pub fn generate_slugs(path: &Path) -> Result<Vec<String>, String> {
-    // Get the canonicalized path for display
-    let canon = safe_canonicalize(path);
+/// How Markdown files which are not valid UTF-8 are handled by the checker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidUtf8Handling {
+    /// Skip the file with a warning
+    Skip,
 
-    debug!("Generating slugs for file: {}", canon);
+    /// Check the file with a warning, replacing invalid characters with `U+FFFD` (line numbers are unaffected)
+    #[default]
+    Lossy,
 
-    // Read the input file
-    let content = std::fs::read_to_string(path)
-        .map_err(|err| format!("Failed to read file at '{}': {}", canon.green(), err))?;
+    /// Fail with an error
+    Error,
+}
 
-    trace!(
-        "In '{}': just read file, which is {} bytes long.",
-        canon,
-        content.len()
-    );
+/// Options for generating slugs from headers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SlugOptions {
+    /// How emojis are handled
+    pub emoji_handling: EmojiHandling,
 
-    // The list of slugified headers
-    let mut headers = vec![];
+    /// How math formulas are handled
+    pub math_in_slug: MathSlugHandling,
 
-    // Counter of slugs for suffixes
-    let mut header_counts = HashMap::<String, usize>::new();
+    /// Algorithm generating the slugs, once emojis are handled
+    pub algorithm: SlugAlgorithm,
+}
 
-    // When the 'pulldown_cmark' library encounters a heading, the actual title can be got between a Start() and an End() events
-    // This variable contains the pending title's content
-    let mut header: Option<String> = None;
+/// Substitution of placeholders in the destination of links (e.g. `releases/{{version}}/CHANGELOG.md`), which happens before they are resolved
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaceholderOptions {
+    /// Value of the placeholders, by name
+    pub variables: BTreeMap<String, String>,
 
-    // Create a pull-down markdown parser
-    let parser = Parser::new_ext(&content, Options::all());
+    /// Opening and closing delimiters of the placeholders (e.g. `{{` and `}}`), no substitution happens if there is none
+    pub delimiters: Vec<(String, String)>,
 
-    for (event, range) in parser.into_offset_iter() {
-        macro_rules! format_msg {
-            ($($param: expr),*) => {{
-                // TODO: Optimize the computation of the line number
-                let line = content.chars().take(range.start).filter(|c| *c == '\n').count();
-                format!("In '{}', line {}: {}", canon.green(), (line + 1).to_string().bright_magenta(), format!($($param),*))
-            }}
-        }
+    /// Severity of the findings about placeholders which have no value
+    pub unresolved_severity: Severity,
+}
 
-        // If the last event was an heading, we are now expecting to get its title
-        if let Some(ref mut header_str) = header {
-            match event {
-                // Event indicating the header is now complete
-                Event::End(Tag::Heading(_)) => {
-                    // Get its slug
-                    let slug = slugify(&header_str);
-                    debug!("{}", format_msg!("found header: #{}", slug));
+/// Verdict of a scheme handler on a link's destination (see [`CheckerOptions::scheme_handler`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemeVerdict {
+    /// The link is valid
+    Valid,
 
-                    // Print a warning if the title is empty
-                    if header_str.trim().is_empty() {
-                        // We did not get a piece of text, which means this heading does not have a title
-                        warn!(
-                            "{}",
-                            format_msg!("heading was not directly followed by a title")
-                        );
-                        trace!("Faulty event: {:?}", event);
-                    }
+    /// The link is invalid, for the provided reason
+    Invalid(String),
 
-                    // Get the number of duplicates this slug has
-                    let duplicates = header_counts
-                        .entry(slug.clone())
-                        .and_modify(|d| *d += 1)
-                        .or_insert(0);
+    /// The link is not handled, so it's treated like links to URLs of other schemes
+    Skip,
+}
 
-                    // Add a suffix for duplicates
-                    if *duplicates > 0 {
-                        headers.push(format!("{}-{}", slug, duplicates));
-                    } else {
-                        headers.push(slug);
-                    }
+/// Handler validating the destination (including its fragment) of links with a given scheme
+pub type SchemeHandler = Arc<dyn Fn(&str) -> SchemeVerdict + Send + Sync>;
 
-                    // Header is now complete
-                    header = None;
-                }
+/// Handlers of custom URI schemes (e.g. `jira://PROJ-123`), indexed by lowercase scheme (see [`CheckerOptions::scheme_handler`])
+///
+/// Each handler may have a version (see [`CheckerOptions::versioned_scheme_handler`]).
+/// Handlers are compared by identity and version, and only their scheme and version are displayed when debugging.
+#[derive(Clone, Default)]
+pub struct SchemeHandlers(BTreeMap<String, (SchemeHandler, Option<String>)>);
 
-                Event::Start(_)
-                | Event::End(_)
-                | Event::SoftBreak
-                | Event::HardBreak
-                | Event::Rule
-                | Event::TaskListMarker(_) => {}
-                Event::Text(text)
-                | Event::Code(text)
-                | Event::Html(text)
-                | Event::FootnoteReference(text) => header_str.push_str(&text),
-            }
-        }
-        // If we encounted the beginning of a heading...
-        else if let Event::Start(Tag::Heading(_)) = event {
-            // Expect to get the related title just after
-            header = Some(String::new())
-        }
+impl SchemeHandlers {
+    /// Get the handler of a scheme (which is case-insensitive)
+    pub fn get(&self, scheme: &str) -> Option<&SchemeHandler> {
+        self.0
+            .get(&scheme.to_ascii_lowercase())
+            .map(|(handler, _)| handler)
     }
 
-    // Everything went fine :D
-    Ok(headers)
+    /// Check if there is no handler
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check if all handlers have a version
+    pub fn are_versioned(&self) -> bool {
+        self.0.values().all(|(_, version)| version.is_some())
+    }
 }
 
-/// Check broken links in a Markdown file or directory
-///
-/// The input `path` will be checked recursively as a directory if `dir` is set to `true`, else as a single file.
-///
-/// By default, when a header points to a specific header (e.g. `other_file.md#some-header`), the target file will be opened and
-///  the function will check if it contains the said header. As this feature may slow down the whole process, it's possible to disable it by
-///  settings `ignore_header_links` to `true`.
-///
-/// In order to improve performances when looking at header-specific links, when a file's list of headers is made, it is stored inside a cache
-/// This cache is shared recursively through the `links_cache` argument. As it uses a specific format, it's recommanded to just pass a mutable
-///  reference to an empty HashMap to this function, and not build your own one which may cause detection problems.
-///
-/// If the `only_files` parameter is set, all links pointing to directories will be refused.
-///
-/// If the `no_errors` parameter is set, all broken/invalid link errors will be displayed as simple warnings (but errors will still be counted).
-///
-/// The function returns an error is something goes wrong, or else the number of broken and invalid (without target) links.
-///
-/// # Examples
-///
-/// ```
-/// use std::path::Path;
-/// use std::collections::HashMap;
-/// use broken_md_links::check_broken_links;
-///
-/// // Single file
-/// assert_eq!(check_broken_links(Path::new("file.md"), false, false, false, false, &mut HashMap::new()), Ok(0));
-///
-/// // Directory
-/// assert_eq!(check_broken_links(Path::new("dir/"), true, false, false, false, &mut HashMap::new()), Ok(0));
-pub fn check_broken_links(
-    path: &Path,
-    dir: bool,
-    ignore_header_links: bool,
-    only_files: bool,
-    no_errors: bool,
-    mut links_cache: &mut HashMap<PathBuf, Vec<String>>,
-) -> Result<u64, String> {
-    /// Display a broken/invalid link error
-    macro_rules! err_or_warn {
-        ($($arg: expr),*) => {
-            if no_errors {
-                warn!($($arg),*);
-            } else {
-                error!($($arg),*);
-            }
-        }
+impl std::fmt::Debug for SchemeHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|(scheme, (_, version))| (scheme, version)),
+            )
+            .finish()
     }
+}
 
-    // Get the canonicalized path for display
-    let canon = safe_canonicalize(path);
+impl PartialEq for SchemeHandlers {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().zip(&other.0).all(
+                |((a_scheme, (a, a_version)), (b_scheme, (b, b_version)))| {
+                    a_scheme == b_scheme && Arc::ptr_eq(a, b) && a_version == b_version
+                },
+            )
+    }
+}
 
-    // Count errors
-    let mut errors = 0;
+impl Eq for SchemeHandlers {}
 
-    if dir {
-        debug!("Analyzing directory: {}", canon);
+/// Cache of the results of the checks of HTTP and HTTPS links, indexed by URL (see [`CheckerOptions::check_http_links`])
+pub type UrlCheckCache = HashMap<String, ValidationResult>;
 
-        for item in path.read_dir().map_err(|err| {
-            format!(
-                "Failed to read input directory at '{}': {}",
-                canon.green(),
-                err
-            )
-        })? {
-            let item = item.map_err(|err| {
-                format!(
-                    "Failed to get item from directory at '{}': {}",
-                    canon.green(),
-                    err
-                )
-            })?;
-            let path = item.path();
-            let file_type = item.file_type().map_err(|err| {
-                format!(
-                    "Failed to read file type of item at '{}': {}",
-                    canon.green(),
-                    err
-                )
-            })?;
-
-            if file_type.is_dir() {
-                // Check broken links recursively
-                errors += check_broken_links(
-                    &path,
-                    true,
-                    ignore_header_links,
-                    only_files,
-                    no_errors,
-                    &mut links_cache,
-                )?;
-            } else if file_type.is_file() {
-                // Only check ".md" files
-                if let Some(ext) = path.extension() {
-                    if let Some(ext) = ext.to_str() {
-                        if ext == "md" {
-                            // Check this Markdown file
-                            errors += check_broken_links(
-                                &path,
-                                false,
-                                ignore_header_links,
-                                only_files,
-                                no_errors,
-                                links_cache,
-                            )?;
-                        }
-                    }
-                }
-            } else {
-                warn!(
-                    "Item at path '{}' is neither a file nor a directory so it will be ignored",
-                    canon
-                );
-            }
-        }
-    } else {
-        // Treat input as a file
-        info!("Analyzing: {}", canon);
+/// Aliases of renamed anchors, from `(file, old anchor)` to `(file, new anchor)` with canonical paths (see [`CheckerOptions::anchor_aliases`])
+pub type AnchorAliases = BTreeMap<(PathBuf, String), (PathBuf, String)>;
 
-        let content = std::fs::read_to_string(path)
-            .map_err(|err| format!("Failed to read file at '{}': {}", canon.green(), err))?;
+/// Options for the links checker
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckerOptions {
+    /// Do not check if the header exists in links pointing to a specific header (e.g. `other_file.md#some-header`)
+    pub ignore_header_links: bool,
 
-        trace!(
-            "In '{}': just read file, which is {} bytes long.",
-            canon,
-            content.len()
-        );
+    /// Refuse all links pointing to directories
+    pub only_files: bool,
 
-        // Count links without a target (like `[link name]`) as an error
-        let mut handle_broken_links = |link: BrokenLink| {
-            err_or_warn!(
-                "In '{}': Missing target for link '{}'",
-                canon.green(),
-                link.reference.yellow()
-            );
+    /// Display all broken/invalid link errors as simple warnings (errors are still counted)
+    pub no_errors: bool,
 
-            None
-        };
+    /// Validate the address(es) of `mailto:` links, reporting malformed ones as warnings
+    pub check_mailto: bool,
 
-        // Create a pull-down parser
-        let parser = Parser::new_with_broken_link_callback(
-            &content,
-            Options::all(),
-            Some(&mut handle_broken_links),
-        );
+    /// Warn about links whose target is not written in its shortest relative form (e.g. `./docs/../docs/a.md` instead of `docs/a.md`)
+    pub check_path_style: bool,
 
-        for (event, range) in parser.into_offset_iter() {
-            macro_rules! format_msg {
-                ($($param: expr),*) => {{
-                    // TODO: Optimize the computation of the line number
-                    let line = content.chars().take(range.start).filter(|c| *c == '\n').count();
-                    format!("In {}{} {}", canon.green(), format!(":{}", line + 1).yellow(), format!($($param),*))
-                }}
-            }
+    /// Report links whose target's extension is not written with the same casing as on disk (e.g. `setup.MD` for `setup.md`)
+    pub check_extension_case: bool,
 
-            // Check inline links only (not URLs or e-mail addresses in autolinks for instance)
-            if let Event::End(Tag::Link(LinkType::Inline, unsplit_target, _)) = event {
-                // Get the link's target file and optionally its header
-                let (target, header): (String, Option<String>) =
-                    match unsplit_target.chars().position(|c| c == '#') {
-                        Some(index) => (
-                            unsplit_target.chars().take(index).collect(),
-                            Some(unsplit_target.chars().skip(index + 1).collect()),
-                        ),
-                        None => (unsplit_target.into_string(), None),
-                    };
+    /// Warn about local link targets longer than this number of characters, which often are paste errors
+    pub max_link_length: Option<usize>,
 
-                // Don't care about URLs
-                if target.starts_with("http://")
-                    || target.starts_with("https://")
-                    || target.starts_with("ftp://")
-                {
-                    trace!("{}", format_msg!("found link to URL: {}", target));
-                    continue;
-                }
+    /// In changelog files (named `CHANGELOG*.md`), check that links named after an issue number (e.g. `[#123](...)`)
+    ///  reference an issue which exists in the local issues directory (if there is one)
+    pub changelog_mode: bool,
 
-                if EMAIL_REGEX.is_match(&target) {
-                    trace!("{}", format_msg!("found link to e-mail addres: {}", target));
-                    continue;
-                }
+    /// Directory containing the local issues, as `<number>.md` files (relative to the changelog, defaults to `issues`)
+    pub issues_dir: Option<PathBuf>,
 
-                let target = if !target.is_empty() {
-                    path.parent().unwrap().join(Path::new(&target))
-                } else {
-                    path.to_owned()
-                };
+    /// Warn about images whose alternative text is empty
+    pub require_alt_text: bool,
 
-                let target_canon = safe_canonicalize(&target);
+    /// Warn about local images which can't be decoded in the format their extension stands for (e.g. a text file named `diagram.png`)
+    ///
+    /// This requires the `image-check` feature, and is ignored otherwise.
+    pub check_image_dimensions: bool,
 
-                match std::fs::canonicalize(&target_canon) {
-                    Ok(path) => {
-                        if only_files && !path.is_file() {
-                            err_or_warn!("{}", format_msg!("invalid link found: path '{}' is a directory but only file links are allowed", target_canon.blue()));
-                            errors += 1;
-                            continue;
-                        }
-                    }
+    /// Do not check that the targets of images exist (e.g. for repositories whose images are located outside of the tree)
+    pub ignore_images: bool,
 
-                    Err(_) => {
-                        err_or_warn!(
-                            "{}",
-                            format_msg!(
-                                "broken link found: path '{}' does not exist",
-                                target_canon.green()
-                            )
-                        );
-                        errors += 1;
-                        continue;
-                    }
-                }
+    /// Do not report links without a target (like `[link name]` without a `[link name]: target` definition),
+    ///  for documents which use square brackets as plain text
+    pub ignore_unresolved_references: bool,
 
-                trace!("{}", format_msg!("valid link found: {}", target_canon));
+    /// Do not look for Markdown links inside raw HTML blocks (e.g. in a `<details>` block without blank lines)
+    pub ignore_html_blocks: bool,
 
-                // If header links must be checked...
-                if !ignore_header_links {
-                    // If the link points to a specific header...
-                    if let Some(header) = header {
-                        // Then the target must be a file
-                        if !target.is_file() {
-                            err_or_warn!(
-                                "{}",
-                                format_msg!(
-                                    "invalid header link found: path '{}' exists but is not a file",
-                                    target_canon.green()
-                                )
-                            );
-                            errors += 1;
-                        } else {
-                            debug!(
-                                "{}",
-                                format_msg!(
-                                    "now checking link '{}' from file '{}'",
-                                    header,
-                                    target_canon
-                                )
-                            );
-
-                            // Canonicalize properly the target path to avoid irregularities in cache's keys
-                            //  like 'dir/../file.md' and 'file.md' which are identical but do not have the same Path representation
-                            let unified_target = target.canonicalize().unwrap();
-
-                            // If the target file is not already in cache...
-                            if !links_cache.contains_key(&unified_target) {
-                                // 2. Push all slugs in the cache
-                                links_cache.insert(
-                                    unified_target.clone(),
-                                    // 1. Get all its headers as slugs
-                                    // We do not use the fully canonicalized path to not force displaying an absolute path
-                                    generate_slugs(&target).map_err(|err| {
-                                        format!(
-                                            "failed to generate slugs for file '{}': {}",
-                                            target_canon.green(),
-                                            err
-                                        )
-                                    })?,
-                                );
-                            }
+    /// Warn about the headers which are missing from the file's Table of Contents, if it has one
+    ///
+    /// The Table of Contents is the first list made only of links to headers of the same file (e.g. `[Usage](#usage)`).
+    /// Only the headers located after it and whose level is one of its entries' are expected in it.
+    pub check_toc: bool,
 
-                            // Get the file's slugs from the cache
-                            let slugs = links_cache.get(&unified_target).unwrap();
+    /// Check the files included with mdBook's `{{#include}}`, `{{#rustdoc_include}}` and `{{#playground}}` directives,
+    ///  as well as the anchors they include (e.g. `{{#include listing.rs:main}}` requires `ANCHOR: main` in `listing.rs`)
+    pub check_mdbook_includes: bool,
 
-                            // Ensure the link points to an existing header
-                            if !slugs.contains(&header) {
-                                err_or_warn!(
-                                    "{}",
-                                    format_msg!(
-                                        "broken link found: header '{}' not found in '{}'",
-                                        header.yellow(),
-                                        target_canon.green()
-                                    )
-                                );
-                                errors += 1;
-                            } else {
-                                trace!("{}", format_msg!("valid header link found: {}", header));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    /// Navigation file (e.g. `SUMMARY.md`) whose entries must be ordered like the numeric prefixes of their targets' names,
+    ///  see [`check_navigation_order`]
+    pub navigation_file: Option<PathBuf>,
 
-    // Everything went fine :D
-    Ok(errors)
+    /// Severity of the findings about the order of the navigation file's entries
+    pub navigation_order_severity: Severity,
+
+    /// Check links whose destination contains spaces without being wrapped in angle brackets (e.g. `[Setup](my setup.md)`),
+    ///  which are not links for CommonMark but are accepted by many renderers, if their target exists
+    ///
+    /// They are reported with a warning recommending to write them `<my setup.md>` or `my%20setup.md`.
+    /// Without this option, they are reported with a warning saying they are not links.
+    pub lenient_space_destinations: bool,
+
+    /// Only display the first occurrence of identical findings in a file (all of them are still returned, see [`dedupe_findings`])
+    pub dedupe_findings: bool,
+
+    /// Don't display the links to missing targets when they are detected (they are still returned),
+    ///  so they can be displayed grouped by target afterwards (see [`group_by_target`])
+    pub group_by_target: bool,
+
+    /// Also check the files ignored by `.gitignore` and `.ignore` files when checking a directory
+    pub no_ignore: bool,
+
+    /// Fail as soon as a file or directory can't be read when checking a directory,
+    ///  instead of reporting it as an unreadable path and checking the other files
+    pub halt_on_io_error: bool,
+
+    /// Follow symbolic links when checking a directory
+    ///
+    /// Links to one of their parent directories are skipped with a warning, as they would make the walk endless.
+    pub follow_symlinks: bool,
+
+    /// Skip (with a warning) the files larger than this number of bytes, as they are fully loaded in memory to be parsed
+    pub max_file_size: Option<u64>,
+
+    /// Stop checking the files of a directory once this duration has elapsed since the beginning of the check
+    ///
+    /// The deadline is checked between files. The files left unchecked are counted in a finding of the [`BrokenLinkKind::Timeout`] kind.
+    /// In incremental mode, the files which changed are checked after replaying the results of the other ones.
+    pub timeout: Option<Duration>,
+
+    /// Check that HTTP and HTTPS links are reachable, by sending them a `HEAD` request
+    ///
+    /// Links to URLs answering with a 4xx or 5xx status are reported as [`BrokenLinkKind::BrokenUrl`] findings,
+    ///  and the ones which can't be reached are reported with a warning. Each URL is only requested once per check.
+    /// This requires the `check-http` feature, and is ignored otherwise. Incremental checks refuse this option (see [`incremental`]).
+    pub check_http_links: bool,
+
+    /// Follow the redirects of HTTP and HTTPS links (see [`CheckerOptions::check_http_links`]), instead of reporting them with a warning
+    pub follow_redirects: bool,
+
+    /// Timeout of the requests sent to check HTTP and HTTPS links ([`validate::DEFAULT_HTTP_TIMEOUT`] if not provided)
+    pub http_timeout: Option<Duration>,
+
+    /// How the Markdown files which are not valid UTF-8 are handled
+    pub invalid_utf8: InvalidUtf8Handling,
+
+    /// Maximum number of directories whose files are checked at the same time (directories are checked one by one if lower than 2)
+    ///
+    /// The files of each directory are checked in parallel as well, on its share of the available threads, and all threads share the same anchors cache.
+    /// Results are still provided in the same order as when checking directories one by one, but messages may be displayed in any order.
+    pub max_concurrent_dirs: usize,
+
+    /// Check the files of a directory on all available threads, instead of grouping them by directory (see [`CheckerOptions::max_concurrent_dirs`])
+    ///
+    /// All threads share the same anchors cache. Results are still provided in the same order as when checking files one by one.
+    pub parallel: bool,
+
+    /// Front matter keys indicating that a file is a redirect (e.g. `redirect_to`)
+    ///
+    /// Links to such files are reported with a warning, so they can point directly to the redirect's target.
+    pub redirect_markers: Vec<String>,
+
+    /// Front matter keys with the value marking a file as unpublished (e.g. `draft` and `true`, or `publish` and `false`), see [`draft_marker`]
+    ///
+    /// Links to such files are reported as [`BrokenLinkKind::LinksToDraft`] warnings, as they won't work once published.
+    /// The files themselves are still checked.
+    pub draft_markers: Vec<(String, String)>,
+
+    /// Fields each Markdown file's front matter must have (e.g. `title` and `date`), see [`validate_frontmatter_schema`]
+    pub frontmatter_required_fields: Vec<String>,
+
+    /// Names of the directories containing partials, i.e. files inlined into the pages including them when the site is published
+    ///  (e.g. `_includes`), see [`partials::check_partial_links`]
+    pub partial_dirs: Vec<String>,
+
+    /// Severity of the findings about links of partials which don't resolve once included (see [`partials::check_partial_links`])
+    pub partial_context_severity: Severity,
+
+    /// Aliases of renamed anchors (see [`load_anchor_aliases`])
+    ///
+    /// Links to an anchor which doesn't exist are accepted if it has an alias which exists, and still reported if it doesn't.
+    /// Aliases can also be declared in the file itself (see [`inline_anchor_aliases`]), the ones provided here take precedence.
+    pub anchor_aliases: AnchorAliases,
+
+    /// Report links resolved through an anchor alias as warnings, so they can be updated
+    pub warn_anchor_aliases: bool,
+
+    /// Severity of the links to a header's slug which only differs from an existing anchor by its casing (see [`validate::anchor_near_miss`])
+    pub anchor_case_severity: Severity,
+
+    /// Severity of the links to a header's slug which only differs from an existing anchor by repeated, leading or trailing dashes
+    pub anchor_punctuation_severity: Severity,
+
+    /// Severity of the links to a header's slug which is the plural or singular form of an existing anchor
+    pub anchor_plural_severity: Severity,
+
+    /// Severity of the links whose text is their destination (e.g. `[https://example.com](https://example.com)` or `[guide.md](guide.md)`),
+    ///  which are not reported if `None`
+    ///
+    /// Autolinks (e.g. `<https://example.com>`) are the way to display a bare destination, so they are not reported.
+    pub link_text_is_url: Option<Severity>,
+
+    /// Substitution of placeholders in the destination of links
+    pub placeholders: PlaceholderOptions,
+
+    /// Handlers of links with custom URI schemes (see [`CheckerOptions::scheme_handler`])
+    pub scheme_handlers: SchemeHandlers,
+
+    /// Measure the time spent checking each file, which can then be got from the targets cache of the check (see [`TargetsCache::take_file_profiles`])
+    pub profile: bool,
+
+    /// Check the Markdown located in the doc comments (`///`, `//!`, `/** */` and `/*! */`) of Rust source files
+    ///
+    /// Rust source files (`.rs`) are included when checking a directory, and findings point to their lines.
+    /// Links made only of square brackets (e.g. ``[`Vec`]`` or `[Vec][std::vec::Vec]`) are rustdoc's intra-doc links, so they are not reported.
+    pub rust_doc_comments: bool,
+
+    /// Give the warnings (e.g. headers without a title or skipped files) the [`Severity::Error`] severity instead of [`Severity::Warning`],
+    ///  to enforce a zero-warning policy
+    pub error_on_warnings: bool,
+
+    /// Options for generating slugs from headers
+    ///
+    /// These are the options of files to which no configuration file applies, see [`CheckerOptions::slugs_for`].
+    pub slugs: SlugOptions,
+
+    /// Configuration files overriding some options for the files of their directory (see [`directory_config`])
+    pub directory_configs: DirectoryConfigs,
+
+    /// Files which are not checked when walking a directory, but remain valid link targets whose anchors are generated when linked to
+    ///  (e.g. generated documentation), as `.gitignore` patterns relative to a directory (e.g. `assets/`)
+    ///
+    /// Configuration files can provide more patterns (see [`ConfigOverrides::targets_only`](directory_config::ConfigOverrides::targets_only)).
+    pub targets_only: GitignorePatterns,
+}
+
+impl CheckerOptions {
+    /// Get the options to check a file with, once overridden by the configuration files which apply to it (see [`CheckerOptions::directory_configs`])
+    ///
+    /// The slug options are not overridden, as they depend on the file the slugs are generated for (see [`CheckerOptions::slugs_for`]).
+    pub fn resolve(&self, file: &Path) -> Cow<'_, Self> {
+        let applicable = self.directory_configs.applicable(file);
+
+        if applicable.is_empty() {
+            return Cow::Borrowed(self);
+        }
+
+        let mut options = self.clone();
+
+        for (_, config) in applicable {
+            config.apply(&mut options);
+        }
+
+        self.directory_configs.cli_overrides.apply(&mut options);
+        Cow::Owned(options)
+    }
+
+    /// Get the options to generate the slugs of a file's headers with, once overridden by the configuration files which apply to it
+    pub fn slugs_for(&self, file: &Path) -> SlugOptions {
+        let applicable = self.directory_configs.applicable(file);
+
+        if applicable.is_empty() {
+            return self.slugs.clone();
+        }
+
+        let mut slugs = self.slugs.clone();
+
+        for (_, config) in applicable {
+            config.apply_slugs(&mut slugs);
+        }
+
+        self.directory_configs.cli_overrides.apply_slugs(&mut slugs);
+        slugs
+    }
+
+    /// Check if a file is only a link target, and must not be checked when walking a directory (see [`CheckerOptions::targets_only`])
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::CheckerOptions;
+    /// use broken_md_links::directory_config::GitignorePatterns;
+    ///
+    /// let options = CheckerOptions {
+    ///     targets_only: GitignorePatterns::new(Path::new("docs"), &["reference/generated/".to_owned()]).unwrap(),
+    ///     ..CheckerOptions::default()
+    /// };
+    ///
+    /// // The generated files are not checked, but links to their headers are
+    /// assert!(options.is_target_only(Path::new("docs/reference/generated/api.md")));
+    /// ```
+    pub fn is_target_only(&self, file: &Path) -> bool {
+        self.targets_only.matches(file) || self.directory_configs.is_target_only(file)
+    }
+
+    /// Register the handler of a custom URI scheme (e.g. `jira`, case-insensitive), replacing the previous one if any
+    ///
+    /// The handler is called with the destination of each link with this scheme, before the links to URLs are skipped
+    ///  (or validated, for `mailto:` links). Invalid links are reported like broken ones.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{check_broken_links_simple, CheckerOptions, SchemeVerdict};
+    ///
+    /// let mut options = CheckerOptions::default();
+    ///
+    /// options.scheme_handler("jira", |dest| {
+    ///     match dest.trim_start_matches("jira://").split_once('-') {
+    ///         Some((project, _)) if project.chars().all(|c| c.is_ascii_uppercase()) => SchemeVerdict::Valid,
+    ///         _ => SchemeVerdict::Invalid("not an issue key".to_owned()),
+    ///     }
+    /// });
+    ///
+    /// let errors = check_broken_links_simple(Path::new("docs"), &options).unwrap();
+    /// ```
+    pub fn scheme_handler(
+        &mut self,
+        scheme: &str,
+        handler: impl Fn(&str) -> SchemeVerdict + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.scheme_handlers
+            .0
+            .insert(scheme.to_ascii_lowercase(), (Arc::new(handler), None));
+        self
+    }
+
+    /// Register the handler of a custom URI scheme like [`CheckerOptions::scheme_handler`], with a version identifying its behavior
+    ///
+    /// Incremental checks (see [`incremental`]) replay the verdicts of handlers from previous runs, so they need to know
+    ///  when these verdicts may change: the version must change whenever the handler's behavior does (e.g. the pattern it matches links against).
+    /// Incremental checks refuse options with handlers which don't have a version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use broken_md_links::{CheckerOptions, FileLinksCache, SchemeVerdict, TargetsCache};
+    /// use broken_md_links::incremental::{check_broken_links_incremental, IncrementalState};
+    ///
+    /// let mut options = CheckerOptions::default();
+    ///
+    /// options.versioned_scheme_handler("jira", "v2", |dest| match dest.starts_with("jira://PROJ-") {
+    ///     true => SchemeVerdict::Valid,
+    ///     false => SchemeVerdict::Invalid("not a PROJ issue".to_owned()),
+    /// });
+    ///
+    /// assert!(options.scheme_handlers.are_versioned());
+    /// assert_eq!(format!("{:?}", options.scheme_handlers), r#"{"jira": Some("v2")}"#);
+    ///
+    /// // Handlers without a version can't be used in incremental checks
+    /// options.scheme_handler("confluence", |_| SchemeVerdict::Valid);
+    ///
+    /// let mut check = |options: &CheckerOptions| check_broken_links_incremental(
+    ///     Path::new("README.md"), false, options, &mut FileLinksCache::new(), &mut TargetsCache::new(), &mut IncrementalState::default(),
+    /// );
+    ///
+    /// assert!(check(&options).is_err());
+    /// ```
+    pub fn versioned_scheme_handler(
+        &mut self,
+        scheme: &str,
+        version: &str,
+        handler: impl Fn(&str) -> SchemeVerdict + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.scheme_handlers.0.insert(
+            scheme.to_ascii_lowercase(),
+            (Arc::new(handler), Some(version.to_owned())),
+        );
+        self
+    }
+}
+
+/// Kind of a broken or invalid link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum BrokenLinkKind {
+    /// The target file or directory does not exist
+    MissingTarget,
+
+    /// The target header does not exist in the target file
+    MissingHeader,
+
+    /// The target HTML anchor (element with an `id` or `name` attribute) or line does not exist in the target file
+    MissingAnchor,
+
+    /// The target header does not exist, but an anchor of the target file only differs from it by its casing
+    AnchorCase,
+
+    /// The target header does not exist, but an anchor of the target file only differs from it by repeated, leading or trailing dashes
+    AnchorPunctuation,
+
+    /// The target header does not exist, but an anchor of the target file is its plural or singular form
+    AnchorPlural,
+
+    /// The target URL answered with an error status (see [`CheckerOptions::check_http_links`])
+    BrokenUrl,
+
+    /// The target of a reference definition (e.g. `[label]: target.md`) which no link uses does not exist
+    /// The targets of the definitions which are used are reported where they are used.
+    DefinitionTargetMissing,
+
+    /// A reference-style link has no definition providing its target (e.g. `[link name]` without `[link name]: target`)
+    UnresolvedReference,
+
+    /// The target is a directory but only file links are allowed
+    DirectoryLink,
+
+    /// The target is not written in its shortest relative form
+    PathStyle,
+
+    /// The target of an image does not exist or is not a file
+    MissingImage,
+
+    /// The target's extension is not written with the same casing as on disk
+    ExtensionCase,
+
+    /// The target is an absolute Windows path (e.g. `C:\docs\page.md` or `\\server\docs\page.md`)
+    MachineSpecificPath,
+
+    /// The issue referenced by a changelog link (e.g. `[#123](...)`) does not exist in the local issues directory
+    MissingIssue,
+
+    /// An image has an empty alternative text
+    MissingAltText,
+
+    /// A file or directory could not be read while walking the input directory
+    UnreadablePath,
+
+    /// The timeout expired before all files were checked, so the results are incomplete (see [`CheckerOptions::timeout`])
+    Timeout,
+
+    /// A link's destination contains a placeholder which has no value (see [`PlaceholderOptions`])
+    UnresolvedPlaceholder,
+
+    /// A cluster of files is not reachable from any entry point (see [`link_graph::analyze_link_graph`])
+    UnreachableCluster,
+
+    /// Files reference each other circularly (see [`link_graph::analyze_link_graph`])
+    LinkCycle,
+
+    /// A chain of files only linking to the next one ends with a file which doesn't link to any other file (see [`link_graph::analyze_link_graph`])
+    DeadEndChain,
+
+    /// A link with a custom URI scheme was rejected by its handler (see [`CheckerOptions::scheme_handler`])
+    InvalidSchemeLink,
+
+    /// An entry of the navigation file is out of order, or its numeric prefix is duplicated (see [`CheckerOptions::navigation_file`])
+    NavigationOrder,
+
+    /// The target is a draft which won't be published (see [`CheckerOptions::draft_markers`])
+    LinksToDraft,
+
+    /// A field required in the front matter is missing (see [`CheckerOptions::frontmatter_required_fields`])
+    MissingFrontmatterField,
+
+    /// A link of a partial only resolves from the partial's directory, not from a page including it (see [`partials::check_partial_links`])
+    PartialContextLink,
+
+    /// A link's text is its destination instead of a description of it (see [`CheckerOptions::link_text_is_url`])
+    LinkTextIsUrl,
+
+    /// A warning (the message describes it), whose severity is [`Severity::Warning`] unless [`CheckerOptions::error_on_warnings`] is set
+    Warning,
+}
+
+impl BrokenLinkKind {
+    /// Get the identifier of this kind (e.g. `missing-target`)
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::MissingTarget => "missing-target",
+            Self::MissingHeader => "missing-header",
+            Self::MissingAnchor => "missing-anchor",
+            Self::AnchorCase => "anchor-case",
+            Self::AnchorPunctuation => "anchor-punctuation",
+            Self::AnchorPlural => "anchor-plural",
+            Self::BrokenUrl => "broken-url",
+            Self::UnresolvedReference => "unresolved-reference",
+            Self::DefinitionTargetMissing => "definition-target-missing",
+            Self::DirectoryLink => "directory-link",
+            Self::PathStyle => "path-style",
+            Self::MissingImage => "missing-image",
+            Self::ExtensionCase => "extension-case",
+            Self::MachineSpecificPath => "machine-specific-path",
+            Self::MissingIssue => "missing-issue",
+            Self::MissingAltText => "missing-alt-text",
+            Self::UnreadablePath => "unreadable-path",
+            Self::Timeout => "timeout",
+            Self::UnresolvedPlaceholder => "unresolved-placeholder",
+            Self::UnreachableCluster => "unreachable-cluster",
+            Self::LinkCycle => "link-cycle",
+            Self::DeadEndChain => "dead-end-chain",
+            Self::MissingFrontmatterField => "missing-frontmatter-field",
+            Self::LinksToDraft => "links-to-draft",
+            Self::InvalidSchemeLink => "invalid-scheme-link",
+            Self::NavigationOrder => "navigation-order",
+            Self::PartialContextLink => "partial-context-link",
+            Self::LinkTextIsUrl => "link-text-is-url",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// Severity of a finding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    /// The finding makes the check fail
+    #[default]
+    Error,
+
+    /// The finding is only reported (see [`CheckerOptions::error_on_warnings`])
+    Warning,
+
+    /// The finding is informational (e.g. the analysis of the links graph, see [`link_graph`])
+    Info,
+}
+
+/// Fragment of a link's target (the part after `#`), which identifies a location in the target file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Fragment {
+    /// Slug of a header (e.g. `#getting-started`, or `#Getting-Started` with another casing), which may also be the identifier of an HTML element
+    Slug(String),
+
+    /// Identifier of an HTML element (e.g. `#Section_2.1`), which can't be the slug of a header, even with another casing
+    HtmlId(String),
+
+    /// Line number (starting from 1), as used by some code hosting platforms and static site generators (e.g. `#L42`)
+    LineNumber(usize),
+
+    /// Fragment that has no recognized form (e.g. it's empty or contains whitespace)
+    Unknown(String),
+}
+
+impl Fragment {
+    /// Determine the type of a fragment from its content
+    ///
+    /// Fragments which only differ from their slug by their casing are slugs, as they are most likely links to a header written with another casing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use broken_md_links::Fragment;
+    ///
+    /// assert_eq!(Fragment::parse("getting-started"), Fragment::Slug("getting-started".to_owned()));
+    /// assert_eq!(Fragment::parse("Getting-Started"), Fragment::Slug("Getting-Started".to_owned()));
+    /// assert_eq!(Fragment::parse("Section_2.1"), Fragment::HtmlId("Section_2.1".to_owned()));
+    /// assert_eq!(Fragment::parse("L42"), Fragment::LineNumber(42));
+    /// assert_eq!(Fragment::parse("some header"), Fragment::Unknown("some header".to_owned()));
+    /// assert_eq!(Fragment::parse(""), Fragment::Unknown("".to_owned()));
+    /// ```
+    pub fn parse(fragment: &str) -> Self {
+        if let Some(captures) = LINE_NUMBER_FRAGMENT_REGEX.captures(fragment) {
+            if let Ok(line) = captures[1].parse() {
+                return Self::LineNumber(line);
+            }
+        }
+
+        if fragment.is_empty() || fragment.chars().any(char::is_whitespace) {
+            Self::Unknown(fragment.to_owned())
+        } else if slugify(fragment) == fragment.to_lowercase() {
+            Self::Slug(fragment.to_owned())
+        } else {
+            Self::HtmlId(fragment.to_owned())
+        }
+    }
+}
+
+impl std::fmt::Display for Fragment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Slug(fragment) | Self::HtmlId(fragment) | Self::Unknown(fragment) => {
+                write!(f, "{}", fragment)
+            }
+            Self::LineNumber(line) => write!(f, "L{}", line),
+        }
+    }
+}
+
+/// A broken or invalid link detected by the checker
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DetectedBrokenLink {
+    /// Path of the file containing the link
+    pub file: PathBuf,
+
+    /// Line the link is located at (starting from 1), or 0 if the problem is not located in the file's content (e.g. an unreadable directory)
+    pub line: usize,
+
+    /// Notebook cell the link is located in (starting from 1), in which case the line is counted from the beginning of the cell
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cell: Option<usize>,
+
+    /// Column the link is located at (starting from 1, counted in characters), if the problem is located in the file's content
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub column: Option<usize>,
+
+    /// Text of the link the problem is about (e.g. `Guide` for `[Guide](guide.md)`), if any
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub link_text: Option<String>,
+
+    /// Kind of problem
+    pub kind: BrokenLinkKind,
+
+    /// Severity of the problem (findings written before severities existed are errors)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub severity: Severity,
+
+    /// Description of the problem
+    pub error: String,
+
+    /// Suggested replacement for the link's target, if any
+    pub suggestion: Option<String>,
+
+    /// Destination of the link the problem is about, after placeholders substitution (see [`PlaceholderOptions`])
+    ///
+    /// This is `None` for problems which are not about a link (e.g. unreadable files).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, rename = "link_target", alias = "target")
+    )]
+    pub target: Option<String>,
+}
+
+/// A link found in a file, before any validation (see [`generate_link_map`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExtractedLink {
+    /// Line the link is located at (starting from 1)
+    pub line: usize,
+
+    /// Notebook cell the link is located in (starting from 1), in which case the line is counted from the beginning of the cell
+    pub cell: Option<usize>,
+
+    /// Destination of the link, as written (e.g. `guide.md#setup`)
+    pub destination: String,
+
+    /// Label of the reference definition providing the destination, for reference links (e.g. `[Guide][guide]`)
+    pub reference: Option<String>,
+
+    /// Is the link an image?
+    pub is_image: bool,
+
+    /// Is the link located in a raw HTML block?
+    pub in_html_block: bool,
+}
+
+impl ExtractedLink {
+    /// Get the path part of the destination (empty for links to a location of the same file, e.g. `#setup`)
+    pub fn path(&self) -> &str {
+        self.destination.split('#').next().unwrap()
+    }
+
+    /// Get the fragment of the destination (the part after `#`), if any
+    pub fn fragment(&self) -> Option<&str> {
+        self.destination
+            .split_once('#')
+            .map(|(_, fragment)| fragment)
+    }
+}
+
+impl DetectedBrokenLink {
+    /// Get the location of the link in its file, as displayed after the file's path (e.g. `:3`, or ` (cell 2, line 3)` in notebooks)
+    pub fn location(&self) -> String {
+        format_location(self.line, self.cell)
+    }
+}
+
+/// Format the location of a link in its file (see [`DetectedBrokenLink::location`])
+pub(crate) fn format_location(line: usize, cell: Option<usize>) -> String {
+    match (line, cell) {
+        (0, _) => String::new(),
+        (line, None) => format!(":{}", line),
+        (line, Some(cell)) => format!(" (cell {}, line {})", cell, line),
+    }
+}
+
+/// Identical broken or invalid links detected in a file, collapsed into a single entry (see [`dedupe_findings`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DedupedBrokenLink {
+    /// Path of the file containing the links
+    pub file: PathBuf,
+
+    /// Lines the links are located at, in the order they were detected
+    pub lines: Vec<usize>,
+
+    /// Notebook cell the links are located in, if any (identical links of different cells are not collapsed)
+    pub cell: Option<usize>,
+
+    /// Kind of problem
+    pub kind: BrokenLinkKind,
+
+    /// Severity of the problem
+    pub severity: Severity,
+
+    /// Description of the problem
+    pub error: String,
+
+    /// Suggested replacement for the links' target, if any
+    pub suggestion: Option<String>,
+
+    /// Destination of the links, with their fragment (see [`DetectedBrokenLink::target`])
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, rename = "link_target", alias = "target")
+    )]
+    pub target: Option<String>,
+}
+
+impl DedupedBrokenLink {
+    /// Get the number of links which were collapsed into this entry
+    pub fn count(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// Format broken or invalid links as a bulleted list, one per line, like they are displayed by the checker
+///
+/// Colors are only used if they are enabled globally (see [`colored::control`]).
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use broken_md_links::{format_error_summary, BrokenLinkKind, DetectedBrokenLink, Severity};
+///
+/// colored::control::set_override(false);
+///
+/// let errors = [
+///     DetectedBrokenLink {
+///         file: PathBuf::from("docs/api.md"),
+///         line: 3,
+///         cell: None,
+///         kind: BrokenLinkKind::MissingTarget,
+///         severity: Severity::Error,
+///         error: "broken link found: path 'docs/missing.md' does not exist".to_owned(),
+///         suggestion: None,
+///         target: None,
+///         column: None,
+///         link_text: None,
+///     },
+///     DetectedBrokenLink {
+///         file: PathBuf::from("docs/private"),
+///         line: 0,
+///         cell: None,
+///         kind: BrokenLinkKind::UnreadablePath,
+///         severity: Severity::Error,
+///         error: "Failed to read directory entry: permission denied".to_owned(),
+///         suggestion: None,
+///         target: None,
+///         column: None,
+///         link_text: None,
+///     },
+/// ];
+///
+/// assert_eq!(
+///     format_error_summary(&errors),
+///     "* In docs/api.md:3 broken link found: path 'docs/missing.md' does not exist [missing-target]\n\
+///      * In docs/private Failed to read directory entry: permission denied [unreadable-path]"
+/// );
+/// ```
+pub fn format_error_summary(errors: &[DetectedBrokenLink]) -> String {
+    errors
+        .iter()
+        .map(|error| {
+            format!(
+                "* In {}{} {} [{}]",
+                safe_canonicalize(&error.file).green(),
+                error.location().yellow(),
+                error.error,
+                error.kind.id()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Merge the results of two checks (e.g. of several roots), removing the links reported by both
+///
+/// Links are considered identical if they have the same file, location, kind and message (which includes the target).
+/// The result is sorted by file and location, links located at the same place keeping their relative order.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use broken_md_links::{merge_broken_links, BrokenLinkKind, DetectedBrokenLink, Severity};
+///
+/// let link = |file: &str, line, error: &str| DetectedBrokenLink {
+///     file: PathBuf::from(file),
+///     line,
+///     cell: None,
+///     kind: BrokenLinkKind::MissingTarget,
+///     severity: Severity::Error,
+///     error: error.to_owned(),
+///     suggestion: None,
+///     target: None,
+///     column: None,
+///     link_text: None,
+/// };
+///
+/// let a = vec![link("b.md", 1, "first"), link("a.md", 3, "second")];
+/// let b = vec![link("a.md", 3, "second"), link("a.md", 3, "third"), link("a.md", 1, "fourth")];
+///
+/// assert_eq!(
+///     merge_broken_links(a, b),
+///     vec![link("a.md", 1, "fourth"), link("a.md", 3, "second"), link("a.md", 3, "third"), link("b.md", 1, "first")]
+/// );
+/// ```
+pub fn merge_broken_links(
+    a: Vec<DetectedBrokenLink>,
+    b: Vec<DetectedBrokenLink>,
+) -> Vec<DetectedBrokenLink> {
+    let mut seen = HashSet::new();
+
+    let mut merged = a
+        .into_iter()
+        .chain(b)
+        .filter(|link| {
+            seen.insert((
+                link.file.clone(),
+                link.cell,
+                link.line,
+                link.kind,
+                link.error.clone(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    merged.sort_by(|x, y| (&x.file, x.cell, x.line).cmp(&(&y.file, y.cell, y.line)));
+    merged
+}
+
+/// Collapse the identical findings of each file (same kind, destination and fragment) into a single entry
+///
+/// Findings which are not about a link (see [`DetectedBrokenLink::target`]) are only collapsed if they have the same message.
+/// Entries are sorted by first occurrence.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_simple, dedupe_findings, CheckerOptions};
+///
+/// let errors = check_broken_links_simple(Path::new("docs"), &CheckerOptions::default()).unwrap();
+///
+/// for finding in dedupe_findings(&errors) {
+///     println!("{} (lines {:?}): {}", finding.file.display(), finding.lines, finding.error);
+/// }
+/// ```
+pub fn dedupe_findings(errors: &[DetectedBrokenLink]) -> Vec<DedupedBrokenLink> {
+    let mut deduped = Vec::<DedupedBrokenLink>::new();
+    let mut indexes =
+        HashMap::<(&Path, Option<usize>, BrokenLinkKind, Option<&str>, &str), usize>::new();
+
+    for error in errors {
+        // Findings about links are identified by their destination, the other ones by their message
+        let message = match error.target {
+            Some(_) => "",
+            None => &error.error,
+        };
+
+        match indexes.entry((
+            &error.file,
+            error.cell,
+            error.kind,
+            error.target.as_deref(),
+            message,
+        )) {
+            Entry::Occupied(entry) => deduped[*entry.get()].lines.push(error.line),
+            Entry::Vacant(entry) => {
+                entry.insert(deduped.len());
+
+                deduped.push(DedupedBrokenLink {
+                    file: error.file.clone(),
+                    lines: vec![error.line],
+                    cell: error.cell,
+                    kind: error.kind,
+                    severity: error.severity,
+                    error: error.error.clone(),
+                    suggestion: error.suggestion.clone(),
+                    target: error.target.clone(),
+                });
+            }
+        }
+    }
+
+    deduped
+}
+
+/// Location of a finding (see [`TargetGroup`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FindingLocation {
+    /// Path of the file containing the link
+    pub file: PathBuf,
+
+    /// Line the link is located at (starting from 1)
+    pub line: usize,
+
+    /// Notebook cell the link is located in, if any
+    pub cell: Option<usize>,
+}
+
+/// Links to the same missing target, grouped together (see [`group_by_target`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TargetGroup {
+    /// Lexically canonicalized path of the missing target (see [`safe_canonicalize`])
+    pub target: String,
+
+    /// Locations of the links to the target, in the order they were detected
+    pub locations: Vec<FindingLocation>,
+}
+
+/// Group the links to missing targets by target, across all files
+///
+/// When a file is removed, the links pointing to it are grouped together instead of being reported separately.
+/// Groups are sorted by decreasing number of links, then by target.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_simple, group_by_target, CheckerOptions};
+///
+/// let errors = check_broken_links_simple(Path::new("docs"), &CheckerOptions::default()).unwrap();
+///
+/// for group in group_by_target(&errors) {
+///     println!("{} is linked from {} location(s)", group.target, group.locations.len());
+/// }
+/// ```
+pub fn group_by_target(errors: &[DetectedBrokenLink]) -> Vec<TargetGroup> {
+    let mut groups = Vec::<TargetGroup>::new();
+    let mut indexes = HashMap::<String, usize>::new();
+
+    for error in errors {
+        if error.kind != BrokenLinkKind::MissingTarget {
+            continue;
+        }
+
+        // Findings without a destination (from reports written before destinations were stored) are grouped by message
+        let target = match error.target {
+            Some(ref destination) => safe_canonicalize(
+                &error
+                    .file
+                    .parent()
+                    .unwrap_or_else(|| Path::new(""))
+                    .join(destination.split('#').next().unwrap()),
+            ),
+            None => error.error.clone(),
+        };
+
+        let location = FindingLocation {
+            file: error.file.clone(),
+            line: error.line,
+            cell: error.cell,
+        };
+
+        match indexes.entry(target) {
+            Entry::Occupied(entry) => groups[*entry.get()].locations.push(location),
+            Entry::Vacant(entry) => {
+                groups.push(TargetGroup {
+                    target: entry.key().clone(),
+                    locations: vec![location],
+                });
+
+                entry.insert(groups.len() - 1);
+            }
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        b.locations
+            .len()
+            .cmp(&a.locations.len())
+            .then_with(|| a.target.cmp(&b.target))
+    });
+
+    groups
+}
+
+/// Missing target with the number of links pointing to it (see [`broken_targets`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BrokenTarget {
+    /// Lexically canonicalized path of the missing target (see [`safe_canonicalize`])
+    pub target: String,
+
+    /// Number of links to the target
+    pub count: usize,
+
+    /// Files containing links to the target, in the order they were detected
+    pub files: Vec<PathBuf>,
+}
+
+/// Count the links to each missing target across all files, to prioritize the targets breaking the most links
+///
+/// Targets are sorted like the groups of [`group_by_target`]: by decreasing number of links, then by target.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{broken_targets, check_broken_links_simple, CheckerOptions};
+///
+/// let errors = check_broken_links_simple(Path::new("docs"), &CheckerOptions::default()).unwrap();
+///
+/// for target in broken_targets(&errors) {
+///     println!("{}: {} link(s) in {} file(s)", target.target, target.count, target.files.len());
+/// }
+/// ```
+pub fn broken_targets(errors: &[DetectedBrokenLink]) -> Vec<BrokenTarget> {
+    group_by_target(errors)
+        .into_iter()
+        .map(|group| {
+            let mut files = Vec::<PathBuf>::new();
+
+            for location in &group.locations {
+                if !files.contains(&location.file) {
+                    files.push(location.file.clone());
+                }
+            }
+
+            BrokenTarget {
+                target: group.target,
+                count: group.locations.len(),
+                files,
+            }
+        })
+        .collect()
+}
+
+/// Get the options used to parse Markdown files
+/// All extensions are enabled, with GitHub-flavored footnotes (which allow multi-paragraph definitions)
+fn markdown_options() -> Options {
+    (Options::all() - Options::ENABLE_OLD_FOOTNOTES) | Options::ENABLE_FOOTNOTES
+}
+
+/// Find Markdown-style links and images in the raw HTML content of a block, which the Markdown parser does not look into
+/// (e.g. in a `<details>` block whose content is not separated from the tags by blank lines)
+///
+/// The content is provided as the lines the parser emitted for the block, with their offset in the file:
+///  the prefixes of the containers the block is located in (e.g. `> ` for blockquotes) are not part of the lines.
+/// Links located in HTML comments are ignored, even when the comment spans multiple lines.
+///
+/// Returns the events the parser would have emitted for these links if they were outside of HTML,
+///  with ranges covering the links in the file
+fn links_in_html(lines: &[(CowStr, usize)]) -> Vec<(Event<'static>, Range<usize>)> {
+    let mut html = String::new();
+
+    // Position of each line in the HTML content, with its offset in the file
+    let mut line_starts = vec![];
+
+    for (line, offset) in lines {
+        line_starts.push((html.len(), *offset));
+        html.push_str(line);
+    }
+
+    // Blank out the comments, preserving the positions of the remaining content
+    let mut pos = 0;
+
+    while let Some(start) = html[pos..].find("<!--").map(|index| pos + index) {
+        let end = html[start..]
+            .find("-->")
+            .map(|index| start + index + "-->".len())
+            .unwrap_or(html.len());
+
+        html.replace_range(start..end, &" ".repeat(end - start));
+        pos = end;
+    }
+
+    // Get the offset in the file of a position in the HTML content
+    let file_offset = |pos: usize| {
+        let line = line_starts.partition_point(|(start, _)| *start <= pos) - 1;
+        let (line_start, offset) = line_starts[line];
+        offset + pos - line_start
+    };
+
+    let mut events = vec![];
+
+    for captures in HTML_MARKDOWN_LINK_REGEX.captures_iter(&html) {
+        let whole = captures.get(0).unwrap();
+        let range = file_offset(whole.start())..file_offset(whole.end() - 1) + 1;
+
+        let text = CowStr::from(captures[2].to_owned());
+        let dest_url = CowStr::from(
+            captures
+                .get(3)
+                .or_else(|| captures.get(4))
+                .unwrap()
+                .as_str()
+                .to_owned(),
+        );
+
+        let (start, end) = if captures[1].is_empty() {
+            (
+                Tag::Link {
+                    link_type: LinkType::Inline,
+                    dest_url,
+                    title: CowStr::from(""),
+                    id: CowStr::from(""),
+                },
+                TagEnd::Link,
+            )
+        } else {
+            (
+                Tag::Image {
+                    link_type: LinkType::Inline,
+                    dest_url,
+                    title: CowStr::from(""),
+                    id: CowStr::from(""),
+                },
+                TagEnd::Image,
+            )
+        };
+
+        events.push((Event::Start(start), range.clone()));
+        events.push((Event::Text(text), range.clone()));
+        events.push((Event::End(end), range));
+    }
+
+    events
+}
+
+/// Find links whose destination contains spaces without being wrapped in angle brackets (e.g. `[Setup](my setup.md)`),
+///  which the parser doesn't consider as links (see [`CheckerOptions::lenient_space_destinations`])
+///
+/// Returns the events the parser would have emitted for each link if its destination was wrapped in angle brackets,
+///  with ranges covering the whole link offset by `offset` (which is the position of the content in the file)
+fn spaced_destination_links(
+    content: &str,
+    offset: usize,
+) -> Vec<Vec<(Event<'static>, Range<usize>)>> {
+    SPACED_DESTINATION_LINK_REGEX
+        .captures_iter(content)
+        .filter(|captures| captures[1].is_empty())
+        .map(|captures| {
+            let whole = captures.get(0).unwrap();
+            let range = offset + whole.start()..offset + whole.end();
+
+            let start = Tag::Link {
+                link_type: LinkType::Inline,
+                dest_url: CowStr::from(captures[3].to_owned()),
+                title: CowStr::from(""),
+                id: CowStr::from(""),
+            };
+
+            vec![
+                (Event::Start(start), range.clone()),
+                (
+                    Event::Text(CowStr::from(captures[2].to_owned())),
+                    range.clone(),
+                ),
+                (Event::End(TagEnd::Link), range),
+            ]
+        })
+        .collect()
+}
+
+/// Where an event of [`MarkdownEvents`] comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EventOrigin {
+    /// Emitted by the Markdown parser
+    Parser,
+
+    /// Markdown link or image located in a raw HTML block (see [`links_in_html`])
+    HtmlBlock,
+
+    /// Link whose destination contains spaces (see [`spaced_destination_links`])
+    SpacedDestination,
+
+    /// Reference definition (like `[label]: target.md`) which no link uses, checked as a link (see [`unused_definitions_as_links`])
+    ReferenceDefinition,
+}
+
+/// Events of the blocks of a Markdown content, with the reference definitions and links without a target found by the parser
+pub(crate) struct MarkdownEvents<'a> {
+    /// Events with their range in the content and their origin
+    pub(crate) events: Vec<(Event<'a>, Range<usize>, EventOrigin)>,
+
+    /// Reference definitions (like `[label]: target.md`), with their destination and range, in the order of the content
+    pub(crate) definitions: Vec<(String, String, Range<usize>)>,
+
+    /// Offsets of the links without a target (like `[link name]`), with their reference
+    /// Links followed by a destination containing spaces are not included, as they are part of the events.
+    pub(crate) unresolved_references: Vec<(usize, String)>,
+}
+
+/// Parse the blocks of a Markdown content (see [`markdown_blocks`])
+///
+/// The events the parser would have emitted for links it doesn't see are added:
+///  the links located in raw HTML blocks (unless [`CheckerOptions::ignore_html_blocks`] is set) before the end of the block,
+///  and the links whose destination contains spaces after the text containing their end (outside of code blocks).
+///
+/// Every reader of Markdown content goes through this function, so the links found in a file are the same
+///  whether they are checked, extracted or followed.
+pub(crate) fn markdown_events<'a>(
+    content: &'a str,
+    blocks: &[Range<usize>],
+    options: &CheckerOptions,
+) -> MarkdownEvents<'a> {
+    let mut events = vec![];
+    let mut definitions = vec![];
+    let mut unresolved_references = Vec::<(usize, String)>::new();
+
+    // Lines of the HTML block being visited, if any
+    let mut html_block: Option<Vec<(CowStr, usize)>> = None;
+
+    for block in blocks {
+        // Links with a destination containing spaces, which the parser doesn't consider as links
+        let spaced_links = spaced_destination_links(&content[block.clone()], block.start);
+        let mut spaced_links_iter = spaced_links.into_iter().peekable();
+        let spaced_starts = spaced_links_iter
+            .clone()
+            .map(|link| link[0].1.start)
+            .collect::<HashSet<_>>();
+
+        // Links without a target which are followed by a destination containing spaces are not unresolved
+        let mut handle_broken_links = |link: BrokenLink| {
+            let offset = block.start + link.span.start;
+
+            if !spaced_starts.contains(&offset)
+                && unresolved_references.last().map(|(last, _)| *last) != Some(offset)
+            {
+                unresolved_references.push((offset, link.reference.to_string()));
+            }
+
+            None
+        };
+
+        let parser = Parser::new_with_broken_link_callback(
+            &content[block.clone()],
+            markdown_options(),
+            Some(&mut handle_broken_links),
+        );
+
+        let definitions_start = definitions.len();
+
+        definitions.extend(
+            parser
+                .reference_definitions()
+                .iter()
+                .map(|(label, definition)| {
+                    (
+                        label.to_owned(),
+                        definition.dest.to_string(),
+                        block.start + definition.span.start..block.start + definition.span.end,
+                    )
+                }),
+        );
+
+        definitions[definitions_start..].sort_by_key(|(_, _, range)| range.start);
+
+        let mut in_code_block = false;
+
+        for (event, range) in parser.into_offset_iter() {
+            let range = block.start + range.start..block.start + range.end;
+            let mut added_links = vec![];
+
+            match event {
+                Event::Start(Tag::HtmlBlock) if !options.ignore_html_blocks => {
+                    html_block = Some(vec![])
+                }
+                Event::Html(ref html) => {
+                    if let Some(ref mut lines) = html_block {
+                        lines.push((html.clone(), range.start));
+                    }
+                }
+                // The links of HTML blocks are added before their end, as comments may span multiple lines
+                Event::End(TagEnd::HtmlBlock) => {
+                    if let Some(lines) = html_block.take() {
+                        events.extend(
+                            links_in_html(&lines)
+                                .into_iter()
+                                .map(|(event, range)| (event, range, EventOrigin::HtmlBlock)),
+                        );
+                    }
+                }
+                Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                _ => {}
+            }
+
+            // Add the links with a destination containing spaces after the text containing their end
+            while let Some(link) = spaced_links_iter.peek() {
+                // Position of the link's closing parenthesis
+                let end = link[0].1.end - 1;
+
+                if end < range.start {
+                    spaced_links_iter.next();
+                } else if !in_code_block && matches!(event, Event::Text(_)) && range.contains(&end)
+                {
+                    added_links.extend(
+                        spaced_links_iter
+                            .next()
+                            .unwrap()
+                            .into_iter()
+                            .map(|(event, range)| (event, range, EventOrigin::SpacedDestination)),
+                    );
+                } else {
+                    break;
+                }
+            }
+
+            events.push((event, range, EventOrigin::Parser));
+            events.extend(added_links);
+        }
+    }
+
+    MarkdownEvents {
+        events,
+        definitions,
+        unresolved_references,
+    }
+}
+
+/// Normalize the label of a reference, as labels are matched case-insensitively with consecutive whitespaces collapsed
+fn normalize_reference_label(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Add the reference definitions which no link or image uses to the events of a content (see [`markdown_events`]),
+///  as the events of a reference link located at the definition
+///
+/// Definitions which are used are checked through the links using them, so their problems are reported only once.
+fn unused_definitions_as_links<'a>(
+    events: Vec<(Event<'a>, Range<usize>, EventOrigin)>,
+    definitions: Vec<(String, String, Range<usize>)>,
+) -> Vec<(Event<'a>, Range<usize>, EventOrigin)> {
+    let used_labels = events
+        .iter()
+        .filter_map(|(event, _, _)| match event {
+            Event::Start(
+                Tag::Link {
+                    link_type: LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut,
+                    id,
+                    ..
+                }
+                | Tag::Image {
+                    link_type: LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut,
+                    id,
+                    ..
+                },
+            ) => Some(normalize_reference_label(id)),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut unused = definitions
+        .into_iter()
+        .filter(|(label, _, _)| !used_labels.contains(&normalize_reference_label(label)))
+        .map(|(label, destination, range)| {
+            let start = Tag::Link {
+                link_type: LinkType::Reference,
+                dest_url: CowStr::from(destination),
+                title: CowStr::from(""),
+                id: CowStr::from(label),
+            };
+
+            [
+                (
+                    Event::Start(start),
+                    range.clone(),
+                    EventOrigin::ReferenceDefinition,
+                ),
+                (
+                    Event::End(TagEnd::Link),
+                    range,
+                    EventOrigin::ReferenceDefinition,
+                ),
+            ]
+        })
+        .peekable();
+
+    // Keep the events in the order of the content
+    let mut merged = Vec::with_capacity(events.len());
+
+    for event in events {
+        while let Some(definition) =
+            unused.next_if(|[(_, range, _), _]| range.start < event.1.start)
+        {
+            merged.extend(definition);
+        }
+
+        merged.push(event);
+    }
+
+    merged.extend(unused.flatten());
+    merged
+}
+
+/// Canonicalize a path and display it as a lossy string
+///
+/// The verbatim prefix of Windows paths returned by [`std::fs::canonicalize`] is removed (see [`strip_verbatim_prefix`]).
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use broken_md_links::safe_canonicalize;
+///
+/// let path = Path::new("../a/b/../c");
+///
+/// assert_eq!(path.to_string_lossy(), "../a/b/../c");
+/// assert_eq!(safe_canonicalize(path), "../a/c");
+///
+/// #[cfg(windows)]
+/// assert!(!safe_canonicalize(&std::fs::canonicalize("src").unwrap()).starts_with(r"\\?\"));
+/// ```
+pub fn safe_canonicalize(path: &Path) -> String {
+    // Components of the canonicalized path
+    let mut out = vec![];
+
+    for comp in path.components() {
+        match comp {
+            // Prefixes, root directories and normal components are kept "as is"
+            Component::Prefix(_) | Component::RootDir | Component::Normal(_) => out.push(comp),
+
+            // "Current dir" symbols (e.g. ".") are useless so they are not kept
+            Component::CurDir => {}
+
+            // "Parent dir" symbols (e.g. "..") will remove the previous component *ONLY* if it's a normal one
+            // Else, if the path is relative the symbol will be kept to preserve the relativety of the path
+            Component::ParentDir => {
+                if let Some(Component::Normal(_)) = out.last() {
+                    out.pop();
+                } else if path.is_relative() {
+                    out.push(Component::ParentDir)
+                }
+            }
+        }
+    }
+
+    // Create a path from the components and display it as a lossy string
+    strip_verbatim_prefix(&out.iter().collect::<PathBuf>().to_string_lossy()).into_owned()
+}
+
+/// Remove the verbatim prefix of a Windows path (e.g. `\\?\C:\docs` becomes `C:\docs`), which is only useful internally
+///
+/// Verbatim UNC paths are displayed as regular UNC paths, and other verbatim paths are kept as is.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::strip_verbatim_prefix;
+///
+/// assert_eq!(strip_verbatim_prefix(r"\\?\C:\docs\README.md"), r"C:\docs\README.md");
+/// assert_eq!(strip_verbatim_prefix(r"\\?\UNC\server\share\README.md"), r"\\server\share\README.md");
+/// assert_eq!(strip_verbatim_prefix(r"\\?\Volume{0000}\README.md"), r"\\?\Volume{0000}\README.md");
+/// assert_eq!(strip_verbatim_prefix("/home/docs/README.md"), "/home/docs/README.md");
+/// ```
+pub fn strip_verbatim_prefix(path: &str) -> Cow<'_, str> {
+    let rest = match path.strip_prefix(r"\\?\") {
+        Some(rest) => rest,
+        None => return Cow::Borrowed(path),
+    };
+
+    if let Some(unc) = rest.strip_prefix(r"UNC\") {
+        return Cow::Owned(format!(r"\\{}", unc));
+    }
+
+    match rest.as_bytes() {
+        [drive, b':', b'\\', ..] if drive.is_ascii_alphabetic() => Cow::Borrowed(rest),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Slugify a Markdown header
+/// This function is used to generate slugs from all headers of a Markdown file (see the 'generate_slugs' function)
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::slugify;
+///
+/// assert_eq!(slugify("My super header"), "my-super-header");
+/// assert_eq!(slugify("I love headers!"), "i-love-headers");
+/// ```
+pub fn slugify(header: &str) -> String {
+    slugify_with(header, &SlugOptions::default())
+}
+
+/// Slugify a Markdown header with custom options
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::{slugify_with, EmojiHandling, SlugOptions};
+///
+/// let options = SlugOptions { emoji_handling: EmojiHandling::Transliterate, ..SlugOptions::default() };
+///
+/// assert_eq!(slugify_with("🚀 Quick Start", &options), "rocket-quick-start");
+/// assert_eq!(slugify_with("Release 🎉", &options), "release-tada");
+/// assert_eq!(slugify_with("✨ Features", &options), "sparkles-features");
+/// assert_eq!(slugify_with("Known 🐛 list", &options), "known-bug-list");
+/// assert_eq!(slugify_with("📝 Notes", &options), "memo-notes");
+/// assert_eq!(slugify_with("Made with ❤️", &options), "made-with-heart");
+///
+/// assert_eq!(slugify_with("🚀 Quick Start", &SlugOptions::default()), "-quick-start");
+/// ```
+pub fn slugify_with(header: &str, options: &SlugOptions) -> String {
+    let header = match options.emoji_handling {
+        EmojiHandling::Strip => header.to_owned(),
+        EmojiHandling::Transliterate => transliterate_emojis(header),
+    };
+
+    match options.algorithm {
+        SlugAlgorithm::Default => header
+            .chars()
+            .map(|c| if c == ' ' { '-' } else { c })
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect::<String>()
+            .to_lowercase(),
+
+        algorithm => algorithm.slugify(&header),
+    }
+}
+
+/// Algorithm used by a rendering platform to generate slugs from headers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugAlgorithm {
+    /// Algorithm used by the checker (see [`slugify`]), which only keeps ASCII letters and digits
+    #[default]
+    Default,
+
+    /// Algorithm used by GitHub, which keeps non-ASCII letters and each space as a dash
+    GitHub,
+
+    /// Algorithm used by GitLab, which is like GitHub's but collapses consecutive dashes
+    GitLab,
+
+    /// Algorithm used by mdBook, which keeps non-ASCII letters and turns every whitespace character into a dash
+    MdBook,
+
+    /// Algorithm used by MkDocs (Python-Markdown's `toc` extension), which strips accents, drops non-ASCII letters
+    ///  and collapses whitespaces and dashes into a single dash
+    MkDocs,
+}
+
+impl SlugAlgorithm {
+    /// All supported algorithms
+    pub const ALL: [Self; 5] = [
+        Self::Default,
+        Self::GitHub,
+        Self::GitLab,
+        Self::MdBook,
+        Self::MkDocs,
+    ];
+
+    /// Slugify a Markdown header with this algorithm
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use broken_md_links::SlugAlgorithm;
+    ///
+    /// assert_eq!(SlugAlgorithm::Default.slugify("Café & Co"), "caf--co");
+    /// assert_eq!(SlugAlgorithm::GitHub.slugify("Café & Co"), "café--co");
+    /// assert_eq!(SlugAlgorithm::GitLab.slugify("Café & Co"), "café-co");
+    /// assert_eq!(SlugAlgorithm::MdBook.slugify("Café\t& Co"), "café--co");
+    /// assert_eq!(SlugAlgorithm::MkDocs.slugify("Café & Co"), "cafe-co");
+    /// assert_eq!(SlugAlgorithm::MkDocs.slugify(" Déjà  vu - 2 "), "deja-vu-2");
+    /// ```
+    pub fn slugify(self, header: &str) -> String {
+        let is_kept = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+
+        match self {
+            Self::Default => slugify(header),
+
+            Self::GitHub => header
+                .to_lowercase()
+                .chars()
+                .filter_map(|c| match c {
+                    ' ' => Some('-'),
+                    c if is_kept(c) => Some(c),
+                    _ => None,
+                })
+                .collect(),
+
+            Self::GitLab => {
+                let slug = Self::GitHub.slugify(header.trim());
+                let mut out = String::with_capacity(slug.len());
+
+                for c in slug.chars() {
+                    if c != '-' || !out.ends_with('-') {
+                        out.push(c);
+                    }
+                }
+
+                out
+            }
+
+            Self::MdBook => header
+                .chars()
+                .filter_map(|c| match c {
+                    c if is_kept(c) => Some(c.to_ascii_lowercase()),
+                    c if c.is_whitespace() => Some('-'),
+                    _ => None,
+                })
+                .collect(),
+
+            Self::MkDocs => {
+                let ascii = header
+                    .nfkd()
+                    .filter(|c| c.is_ascii())
+                    .filter(|c| is_kept(*c) || c.is_whitespace())
+                    .collect::<String>();
+
+                let mut out = String::with_capacity(ascii.len());
+
+                for c in ascii.trim().to_lowercase().chars() {
+                    if c == '-' || c.is_whitespace() {
+                        if !out.ends_with('-') {
+                            out.push('-');
+                        }
+                    } else {
+                        out.push(c);
+                    }
+                }
+
+                out
+            }
+        }
+    }
+}
+
+/// Slugify a Markdown header with all supported algorithms, to find on which platforms a link to it is valid
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::{generate_slug_variants, SlugAlgorithm};
+///
+/// let variants = generate_slug_variants("Déjà vu");
+///
+/// assert_eq!(variants[0], (SlugAlgorithm::Default, "dj-vu".to_owned()));
+///
+/// // A link to '#déjà-vu' is valid on GitHub, but not with the default algorithm
+/// assert!(variants.contains(&(SlugAlgorithm::GitHub, "déjà-vu".to_owned())));
+/// assert!(!variants.contains(&(SlugAlgorithm::Default, "déjà-vu".to_owned())));
+/// ```
+pub fn generate_slug_variants(header: &str) -> Vec<(SlugAlgorithm, String)> {
+    SlugAlgorithm::ALL
+        .iter()
+        .map(|algorithm| (*algorithm, algorithm.slugify(header)))
+        .collect()
+}
+
+/// Replace all emojis in a string with their short name
+/// Emojis made of multiple characters (e.g. with a variation selector) are matched as a whole
+fn transliterate_emojis(text: &str) -> String {
+    /// Maximum number of characters an emoji can be made of
+    const MAX_EMOJI_CHARS: usize = 10;
+
+    // Byte offsets of each character, including the end of the string
+    let offsets = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(text.len()))
+        .collect::<Vec<_>>();
+
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i + 1 < offsets.len() {
+        // Find the longest emoji starting at this character
+        let emoji = (i + 1..offsets.len().min(i + 1 + MAX_EMOJI_CHARS))
+            .rev()
+            .find_map(|j| {
+                emojis::get(&text[offsets[i]..offsets[j]])
+                    .and_then(|emoji| emoji.shortcode())
+                    .map(|shortcode| (j, shortcode))
+            });
+
+        match emoji {
+            Some((end, shortcode)) => {
+                out.push_str(shortcode);
+                i = end;
+            }
+            None => {
+                out.push_str(&text[offsets[i]..offsets[i + 1]]);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Check if a string is a valid e-mail address (case-insensitive)
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::is_valid_email;
+///
+/// assert!(is_valid_email("user@example.com"));
+/// assert!(is_valid_email("First.Last+tag@sub.example.org"));
+/// assert!(!is_valid_email("user@"));
+/// assert!(!is_valid_email("user.example.com"));
+/// assert!(!is_valid_email("user@example.com and more"));
+/// ```
+pub fn is_valid_email(email: &str) -> bool {
+    EMAIL_ADDRESS_REGEX.is_match(email)
+}
+
+/// Check if a link's target is a `mailto:` link (the scheme is case-insensitive)
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::is_mailto_link;
+///
+/// assert!(is_mailto_link("mailto:user@example.com"));
+/// assert!(is_mailto_link("MAILTO:user@example.com?subject=Hello"));
+/// assert!(!is_mailto_link("user@example.com"));
+/// assert!(!is_mailto_link("https://example.com/mailto:user"));
+/// ```
+pub fn is_mailto_link(target: &str) -> bool {
+    URL_SCHEME_REGEX
+        .captures(target)
+        .is_some_and(|scheme| scheme[1].eq_ignore_ascii_case("mailto"))
+}
+
+/// Get the malformed addresses of a `mailto:` link
+/// The scheme and the optional query part (e.g. `?subject=...`) are stripped, then each comma-separated recipient is validated
+fn malformed_mailto_addresses(target: &str) -> Vec<&str> {
+    // Strip the "mailto:" scheme
+    let addresses = &target["mailto:".len()..];
+
+    // Strip the query part
+    let addresses = match addresses.find('?') {
+        Some(index) => &addresses[..index],
+        None => addresses,
+    };
+
+    addresses
+        .split(',')
+        .filter(|address| !is_valid_email(address))
+        .collect()
+}
+
+/// Collector of the headers of a Markdown file as slugs and of the identifiers of its HTML elements, fed with the parser's events
+///
+/// It is shared by the slugs generator and the checker, which collects the headers of the files it checks in the same parse pass.
+struct HeadersCollector<'a> {
+    /// Options to use when generating slugs
+    slug_options: &'a SlugOptions,
+
+    /// The list of slugified headers
+    headers: Vec<String>,
+
+    /// Counter of slugs for suffixes
+    header_counts: HashMap<String, usize>,
+
+    /// When the 'pulldown_cmark' library encounters a heading, the actual title can be got between a Start() and an End() events
+    /// This field contains the pending title's content
+    header: Option<String>,
+
+    /// Identifiers of the HTML elements (`id` and `name` attributes)
+    html_anchors: Vec<String>,
+}
+
+impl<'a> HeadersCollector<'a> {
+    /// Create an empty collector
+    fn new(slug_options: &'a SlugOptions) -> Self {
+        Self {
+            slug_options,
+            headers: vec![],
+            header_counts: HashMap::new(),
+            header: None,
+            html_anchors: vec![],
+        }
+    }
+
+    /// Feed an event of the parser
+    /// If it completes a header, its title and (suffixed) slug are returned
+    fn feed(&mut self, event: &Event) -> Option<(String, String)> {
+        if let Event::Html(html) | Event::InlineHtml(html) = event {
+            self.html_anchors
+                .extend(HTML_ANCHOR_REGEX.captures_iter(html).map(|captures| {
+                    captures
+                        .get(1)
+                        .or_else(|| captures.get(2))
+                        .unwrap()
+                        .as_str()
+                        .to_owned()
+                }));
+        }
+
+        // If the last event was an heading, we are now expecting to get its title
+        if let Some(ref mut header_str) = self.header {
+            match event {
+                // Event indicating the header is now complete
+                Event::End(TagEnd::Heading(_)) => {
+                    // Get its slug
+                    let slug = slugify_with(header_str, self.slug_options);
+
+                    // Get the number of duplicates this slug has
+                    let duplicates = self
+                        .header_counts
+                        .entry(slug.clone())
+                        .and_modify(|d| *d += 1)
+                        .or_insert(0);
+
+                    // Add a suffix for duplicates
+                    let slug = if *duplicates > 0 {
+                        format!("{}-{}", slug, duplicates)
+                    } else {
+                        slug
+                    };
+
+                    self.headers.push(slug.clone());
+
+                    // Header is now complete
+                    return self.header.take().map(|title| (title, slug));
+                }
+
+                Event::Text(text)
+                | Event::Code(text)
+                | Event::Html(text)
+                | Event::InlineHtml(text)
+                | Event::FootnoteReference(text) => header_str.push_str(text),
+
+                Event::InlineMath(math) | Event::DisplayMath(math) => {
+                    match self.slug_options.math_in_slug {
+                        MathSlugHandling::Strip => {}
+                        MathSlugHandling::IncludeRaw => header_str.push_str(math),
+                        MathSlugHandling::IncludePlain => {
+                            header_str.push_str(&latex_to_plain(math))
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+        // If we encounted the beginning of a heading...
+        else if let Event::Start(Tag::Heading { .. }) = event {
+            // Expect to get the related title just after
+            self.header = Some(String::new())
+        }
+
+        None
+    }
+
+    /// Get the slugs of all complete headers
+    fn into_slugs(self) -> Vec<String> {
+        self.headers
+    }
+
+    /// Get the slugs of all complete headers, followed by the identifiers of the HTML elements
+    fn into_anchors(self) -> Vec<String> {
+        let mut anchors = self.headers;
+        anchors.extend(self.html_anchors);
+        anchors
+    }
+}
+
+/// Get all headers of a Markdown file as slugs
+/// This function is used to check if the header specified in a link exists in the target file
+/// Returns an error message if the operation failed for any reason
+///
+/// Links inside headers contribute their text (not their target) to the slug, like on GitHub.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::generate_slugs;
+///
+/// // A `## See also: [the old guide](legacy/guide.md)` header gets the `see-also-the-old-guide` slug
+/// let slugs = generate_slugs(Path::new("doc.md")).unwrap();
+/// ```
+pub fn generate_slugs(path: &Path) -> Result<Vec<String>, String> {
+    generate_slugs_with(path, &SlugOptions::default())
+}
+
+/// Get all headers of a Markdown file as slugs, with custom slug options
+pub fn generate_slugs_with(path: &Path, slug_options: &SlugOptions) -> Result<Vec<String>, String> {
+    collect_headers(path, slug_options).map(HeadersCollector::into_slugs)
+}
+
+/// Get all anchors of a Markdown file, which are the slugs of its headers followed by the identifiers of its HTML elements
+///  (`id` and `name` attributes)
+///
+/// This is what links to a specific location of the file (e.g. `file.md#some-header`) are checked against.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{generate_anchors_with, SlugOptions};
+///
+/// let anchors = generate_anchors_with(Path::new("doc.md"), &SlugOptions::default()).unwrap();
+/// ```
+pub fn generate_anchors_with(
+    path: &Path,
+    slug_options: &SlugOptions,
+) -> Result<Vec<String>, String> {
+    collect_headers(path, slug_options).map(HeadersCollector::into_anchors)
+}
+
+/// Get all anchors of a Markdown file like [`generate_anchors_with`], reusing the ones of an identical file
+///  stored in the links cache if there is one
+pub(crate) fn generate_shared_anchors(
+    path: &Path,
+    slug_options: &SlugOptions,
+    links_cache: &FileLinksCache,
+) -> Result<Vec<String>, String> {
+    profile::time_slug_generation(|| {
+        generate_unprofiled_shared_anchors(path, slug_options, links_cache)
+    })
+}
+
+/// Get all anchors of a Markdown file like [`generate_shared_anchors`], without measuring the time it takes
+#[cfg_attr(not(feature = "content-hash"), allow(unused_variables))]
+fn generate_unprofiled_shared_anchors(
+    path: &Path,
+    slug_options: &SlugOptions,
+    links_cache: &FileLinksCache,
+) -> Result<Vec<String>, String> {
+    #[cfg(feature = "content-hash")]
+    {
+        let source = read_headers_source(path)?;
+
+        // Mapped contents are validated each time they are accessed
+        let content: &str = &source;
+        let key = content_hash::ContentKey::new(path, content.as_bytes(), slug_options);
+
+        if let Some(anchors) = links_cache.contents().lookup(&key) {
+            trace!(
+                "Reusing slugs of an identical file for: {}",
+                safe_canonicalize(path)
+            );
+            return Ok(anchors);
+        }
+
+        let anchors = collect_content_headers(path, content, slug_options)?.into_anchors();
+        links_cache.contents().store(path, key, &anchors);
+
+        Ok(anchors)
+    }
+
+    #[cfg(not(feature = "content-hash"))]
+    generate_anchors_with(path, slug_options)
+}
+
+/// Build a map of slugs (or any anchors) to their index in the provided list, to look them up in constant time
+///
+/// If a slug appears multiple times, its first index is kept.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::build_slug_lookup;
+///
+/// let slugs = vec!["installation".to_owned(), "usage".to_owned(), "usage".to_owned()];
+/// let lookup = build_slug_lookup(&slugs);
+///
+/// assert_eq!(lookup.get("usage"), Some(&1));
+/// assert_eq!(lookup.get("license"), None);
+/// ```
+pub fn build_slug_lookup(slugs: &[String]) -> HashMap<String, usize> {
+    let mut lookup = HashMap::with_capacity(slugs.len());
+
+    for (index, slug) in slugs.iter().enumerate() {
+        lookup.entry(slug.clone()).or_insert(index);
+    }
+
+    lookup
+}
+
+/// Find the slugs which are not covered by any fragment of a Table of Contents, in their order of appearance
+///
+/// Fragments may start with a `#`. A slug appearing multiple times (e.g. for headers with the same title which
+///  were not made unique) only needs to be covered once, and is only returned once if it isn't.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::check_header_coverage;
+///
+/// let all_slugs = ["installation", "usage", "usage", "faq", "license"].map(String::from);
+/// let toc_fragments = ["#installation", "#usage", "#license"].map(String::from);
+///
+/// assert_eq!(check_header_coverage(&toc_fragments, &all_slugs), vec!["faq".to_owned()]);
+/// assert_eq!(check_header_coverage(&[], &all_slugs[1..3]), vec!["usage".to_owned()]);
+/// ```
+pub fn check_header_coverage(toc_fragments: &[String], all_slugs: &[String]) -> Vec<String> {
+    let covered = toc_fragments
+        .iter()
+        .map(|fragment| fragment.strip_prefix('#').unwrap_or(fragment))
+        .collect::<HashSet<_>>();
+
+    let mut reported = HashSet::new();
+
+    all_slugs
+        .iter()
+        .filter(|slug| !covered.contains(slug.as_str()) && reported.insert(slug.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Parse a Markdown file and collect its headers
+fn collect_headers<'a>(
+    path: &Path,
+    slug_options: &'a SlugOptions,
+) -> Result<HeadersCollector<'a>, String> {
+    let content = read_headers_source(path)?;
+    collect_content_headers(path, &content, slug_options)
+}
+
+/// Read a Markdown file to collect its headers
+fn read_headers_source(path: &Path) -> Result<FileContent, String> {
+    // Get the canonicalized path for display
+    let canon = safe_canonicalize(path);
+
+    debug!("Generating slugs for file: {}", canon);
+
+    // Read the input file
+    let (content, _) = FileContent::read(path)
+        .map_err(|err| format!("Failed to read file at '{}': {}", canon.green(), err))?;
+
+    trace!(
+        "In '{}': just read file, which is {} bytes long.",
+        canon,
+        content.len()
+    );
+
+    Ok(content)
+}
+
+/// Parse the content of a Markdown file and collect its headers
+fn collect_content_headers<'a>(
+    path: &Path,
+    content: &str,
+    slug_options: &'a SlugOptions,
+) -> Result<HeadersCollector<'a>, String> {
+    // Get the canonicalized path for display
+    let canon = safe_canonicalize(path);
+
+    // Collector of the slugified headers
+    let mut headers = HeadersCollector::new(slug_options);
+
+    // Only the headers of the Markdown cells of notebooks can be linked to
+    let embedded = embedded_markdown(path, content, false)
+        .map_err(|err| format!("File at '{}' {}", canon.green(), err))?;
+
+    // Content to parse, with the index of its lines (to get the line number of events) and the blocks to parse independently
+    let (content, line_index, blocks) = markdown_blocks(content, embedded.as_ref());
+
+    // Parse the blocks the same way as when the file is checked, so the same headers are found
+    let events = markdown_events(content, &blocks, &CheckerOptions::default()).events;
+
+    for (event, range, _) in events {
+        macro_rules! format_msg {
+            ($($param: expr),*) => {{
+                let line = line_index.line(range.start);
+                format!("In '{}', line {}: {}", canon.green(), line.to_string().bright_magenta(), format!($($param),*))
+            }}
+        }
+
+        if let Some((title, slug)) = headers.feed(&event) {
+            debug!("{}", format_msg!("found header: #{}", slug));
+
+            // Headers without a title are reported as warnings when the file itself is checked
+            if title.trim().is_empty() {
+                // We did not get a piece of text, which means this heading does not have a title
+                debug!(
+                    "{}",
+                    format_msg!("heading was not directly followed by a title")
+                );
+                trace!("Faulty event: {:?}", event);
+            }
+        }
+    }
+
+    // Everything went fine :D
+    Ok(headers)
+}
+
+/// Get the shortest relative link from a directory to a path, both being absolute and canonicalized
+/// Components are separated by slashes on all platforms, as in Markdown links
+fn relative_link(from_dir: &Path, to: &Path) -> String {
+    // Skip the components the two paths have in common
+    let mut from_comps = from_dir.components().peekable();
+    let mut to_comps = to.components().peekable();
+
+    while from_comps.peek().is_some() && from_comps.peek() == to_comps.peek() {
+        from_comps.next();
+        to_comps.next();
+    }
+
+    // Go up for each remaining component of the directory, then down to the target
+    let link = from_comps
+        .map(|_| "..".to_owned())
+        .chain(to_comps.map(|comp| comp.as_os_str().to_string_lossy().into_owned()))
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if link.is_empty() {
+        ".".to_owned()
+    } else {
+        link
+    }
+}
+
+/// Find a file by its name in a directory, recursively
+/// Entries are visited in alphabetical order and hidden directories (like `.git`) are skipped.
+fn find_file_by_name(dir: &Path, name: &str, max_depth: usize) -> Option<PathBuf> {
+    let mut entries = dir
+        .read_dir()
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .collect::<Vec<_>>();
+
+    entries.sort_by_key(|entry| entry.file_name());
+
+    let mut subdirs = vec![];
+
+    for entry in entries {
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(_) => continue,
+        };
+
+        if file_type.is_file() && entry.file_name() == name {
+            return Some(entry.path());
+        }
+
+        if file_type.is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+            subdirs.push(entry.path());
+        }
+    }
+
+    if max_depth == 0 {
+        return None;
+    }
+
+    subdirs
+        .iter()
+        .find_map(|subdir| find_file_by_name(subdir, name, max_depth - 1))
+}
+
+/// Suggest a relative link for an absolute Windows path, by looking for a file with the same name
+///  in the directory containing the linking file, then in the current directory
+fn suggest_for_machine_specific_path(file: &Path, written: &str) -> Option<String> {
+    /// Maximum depth of the directories to look into
+    const MAX_DEPTH: usize = 16;
+
+    let name = written.rsplit(['\\', '/']).next()?;
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let base = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize().ok()?,
+        _ => std::env::current_dir().ok()?,
+    };
+
+    let found = find_file_by_name(&base, name, MAX_DEPTH).or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .and_then(|cwd| find_file_by_name(&cwd, name, MAX_DEPTH))
+    })?;
+
+    Some(relative_link(&base, &found.canonicalize().ok()?))
+}
+
+/// Get the shortest relative form of a link's target, if it's not written this way
+/// A leading `./` is allowed, as well as a trailing `/`
+///
+/// Returns `None` if the target is already written in its shortest form or if it's not a relative path.
+fn non_shortest_relative_target(file: &Path, written: &str) -> Option<String> {
+    if written.is_empty() || !Path::new(written).is_relative() {
+        return None;
+    }
+
+    // Absolute path of the directory containing the file
+    let base = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize().ok()?,
+        _ => std::env::current_dir().ok()?,
+    };
+
+    // Absolute path of the target, without resolving symbolic links
+    let target = PathBuf::from(safe_canonicalize(&base.join(written)));
+
+    let mut shortest = relative_link(&base, &target);
+
+    if written.ends_with('/') && !shortest.ends_with('/') {
+        shortest.push('/');
+    }
+
+    if written == shortest || written.strip_prefix("./") == Some(&shortest) {
+        None
+    } else {
+        Some(shortest)
+    }
+}
+
+/// Get the actual name of a file on disk, by looking for an entry of its parent directory with the same name, ignoring case
+/// An entry with the exact same name is preferred if there is one.
+///
+/// Returns `None` if no entry matches or if the parent directory cannot be read.
+fn on_disk_file_name(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut found = None;
+
+    for entry in parent.read_dir().ok()? {
+        let entry_name = entry.ok()?.file_name();
+
+        match entry_name.to_str() {
+            Some(entry_name) if entry_name == name => return Some(entry_name.to_owned()),
+            Some(entry_name) if entry_name.eq_ignore_ascii_case(name) => {
+                found = Some(entry_name.to_owned())
+            }
+            _ => {}
+        }
+    }
+
+    found
+}
+
+/// Check if the extension of a path is written with a different casing than on disk
+/// Returns the actual name of the file on disk if it only differs from the path's by its extension's casing
+fn extension_case_mismatch(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let on_disk = on_disk_file_name(path)?;
+
+    let stem_len = path.file_stem()?.len();
+
+    if on_disk != name && on_disk[..stem_len] == name[..stem_len] {
+        Some(on_disk)
+    } else {
+        None
+    }
+}
+
+/// Check if a file is a changelog (its name starts with `CHANGELOG`, case-insensitively)
+fn is_changelog(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_ascii_uppercase().starts_with("CHANGELOG"))
+        .unwrap_or(false)
+}
+
+/// Get the directory containing the local issues if a file is a changelog which must be checked against them
+fn changelog_issues_dir(path: &Path, options: &CheckerOptions) -> Option<PathBuf> {
+    if !options.changelog_mode || !is_changelog(path) {
+        return None;
+    }
+
+    let issues_dir = path.parent().unwrap().join(
+        options
+            .issues_dir
+            .as_deref()
+            .unwrap_or_else(|| Path::new("issues")),
+    );
+
+    if issues_dir.is_dir() {
+        Some(issues_dir)
+    } else {
+        debug!(
+            "No local issues directory found at '{}', issue links will be treated as external ones",
+            safe_canonicalize(&issues_dir)
+        );
+        None
+    }
+}
+
+/// Get the modification time of a file as a string
+/// This is used to detect outdated entries in persistent cache files
+fn modification_time(path: &Path) -> Option<String> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let elapsed = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+
+    Some(format!(
+        "{}.{:09}",
+        elapsed.as_secs(),
+        elapsed.subsec_nanos()
+    ))
+}
+
+/// Get the first line of a persistent cache file
+/// It contains the slug options the cache was generated with, as slugs depend on them
+fn cache_file_header(slug_options: &SlugOptions) -> String {
+    format!("{} {:?}", CACHE_FILE_HEADER, slug_options)
+}
+
+/// Load a links cache from a file previously written by [`save_links_cache`]
+///
+/// Entries of files which have been modified (or removed) since the cache was written are ignored.
+/// With the `content-hash` feature, they are still reused for files with the same content (e.g. if they were moved).
+/// If the cache was written with different slug options, an empty cache is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{load_links_cache, save_links_cache, CheckerOptions, FileLinksCache, LinksCache};
+///
+/// let options = CheckerOptions::default();
+/// let cache_file = Path::new("headers.cache");
+///
+/// let mut cache = load_links_cache(cache_file, &options.slugs).unwrap_or_else(|_| FileLinksCache::new());
+/// cache.warm(Path::new("docs"), &options);
+///
+/// save_links_cache(&cache, &options.slugs, cache_file).unwrap();
+/// ```
+pub fn load_links_cache(path: &Path, slug_options: &SlugOptions) -> Result<FileLinksCache, String> {
+    // Get the canonicalized path for display
+    let canon = safe_canonicalize(path);
+
+    debug!("Loading links cache from file: {}", canon);
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read cache file at '{}': {}", canon.green(), err))?;
+
+    let mut lines = content.lines();
+
+    match lines.next() {
+        Some(header) if header == cache_file_header(slug_options) => {}
+
+        Some(header) if header.starts_with(CACHE_FILE_HEADER) => {
+            debug!("Ignoring cache file generated with different slug options");
+            return Ok(FileLinksCache::new());
+        }
+
+        _ => {
+            return Err(format!(
+                "Cache file at '{}' is not in a supported format",
+                canon.green()
+            ))
+        }
+    }
+
+    // Entries of the cache file, with their modification time and content hash (if any)
+    let mut entries = Vec::<(PathBuf, &str, Option<&str>, Vec<String>)>::new();
+
+    for line in lines {
+        // Slugs are indented with a tab, files are not
+        if let Some(slug) = line.strip_prefix('\t') {
+            if let Some((_, _, _, slugs)) = entries.last_mut() {
+                slugs.push(slug.to_owned());
+            }
+
+            continue;
+        }
+
+        let mut fields = line.split('\t');
+
+        let (file, modified, hash) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(file), Some(modified), hash) => (file, modified, hash),
+            _ => return Err(format!("Cache file at '{}' is malformed", canon.green())),
+        };
+
+        entries.push((PathBuf::from(file), modified, hash, vec![]));
+    }
+
+    let links_cache = FileLinksCache::new();
+
+    #[cfg_attr(not(feature = "content-hash"), allow(unused_variables))]
+    for (file, modified, hash, slugs) in entries {
+        // Entries are shared with identical files even if outdated, as the file may have been moved
+        #[cfg(feature = "content-hash")]
+        if let Some(hash) = hash.and_then(|hash| u64::from_str_radix(hash, 16).ok()) {
+            let key = content_hash::ContentKey::from_hash(&file, hash, slug_options);
+            links_cache.contents().store_content(key, &slugs);
+        }
+
+        if modification_time(&file).as_deref() == Some(modified) {
+            links_cache.insert(file, slugs);
+        } else {
+            links_cache.record_eviction();
+
+            debug!(
+                "Ignoring outdated cache entry for file: {}",
+                safe_canonicalize(&file)
+            );
+        }
+    }
+
+    trace!(
+        "Loaded {} entries from cache file '{}'",
+        links_cache.len(),
+        canon
+    );
+
+    Ok(links_cache)
+}
+
+/// Write a links cache to a file, so it can be loaded again with [`load_links_cache`]
+///
+/// The modification time of each file is stored alongside its slugs to detect outdated entries when loading the cache,
+///  as well as the hash of its content with the `content-hash` feature.
+/// The slug options must be the ones the cache was filled with.
+pub fn save_links_cache(
+    links_cache: &FileLinksCache,
+    slug_options: &SlugOptions,
+    path: &Path,
+) -> Result<(), String> {
+    // Get the canonicalized path for display
+    let canon = safe_canonicalize(path);
+
+    debug!("Writing links cache to file: {}", canon);
+
+    let mut out = format!("{}\n", cache_file_header(slug_options));
+
+    // Entries are sorted to get a deterministic output
+    for (file, slugs) in links_cache.entries() {
+        // Entries whose path cannot be written as-is or whose modification time cannot be determined are skipped
+        let (file_str, modified) = match (file.to_str(), modification_time(&file)) {
+            (Some(file_str), Some(modified)) if !file_str.contains(&['\t', '\n', '\r'][..]) => {
+                (file_str, modified)
+            }
+            _ => {
+                trace!(
+                    "Not writing cache entry for file: {}",
+                    safe_canonicalize(&file)
+                );
+                continue;
+            }
+        };
+
+        out.push_str(&format!("{}\t{}", file_str, modified));
+
+        #[cfg(feature = "content-hash")]
+        if let Some(hash) = links_cache.content_hash_of(&file) {
+            out.push_str(&format!("\t{:016x}", hash));
+        }
+
+        out.push('\n');
+
+        for slug in slugs.iter() {
+            out.push_str(&format!("\t{}\n", slug));
+        }
+    }
+
+    std::fs::write(path, out)
+        .map_err(|err| format!("Failed to write cache file at '{}': {}", canon.green(), err))
+}
+
+/// Remove all HTML comments (`<!-- ... -->`) from Markdown content
+///
+/// Line breaks inside comments are kept so line numbers remain the same, and comments inside code are left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::strip_markdown_comments;
+///
+/// assert_eq!(strip_markdown_comments("Some <!-- hidden -->text"), "Some text");
+/// assert_eq!(strip_markdown_comments("A\n<!--\nmulti-line\n-->\nB"), "A\n\n\n\nB");
+/// assert_eq!(strip_markdown_comments("`<!-- code -->`<!-- comment -->"), "`<!-- code -->`");
+/// assert_eq!(strip_markdown_comments("Unterminated <!-- comment"), "Unterminated ");
+/// ```
+pub fn strip_markdown_comments(content: &str) -> String {
+    // Ranges of code spans and code blocks, where comments must not be removed
+    let code_ranges = Parser::new_ext(content, markdown_options())
+        .into_offset_iter()
+        .filter(|(event, _)| matches!(event, Event::Code(_) | Event::Start(Tag::CodeBlock(_))))
+        .map(|(_, range)| range)
+        .collect::<Vec<_>>();
+
+    let mut out = String::with_capacity(content.len());
+    let mut pos = 0;
+
+    while let Some(start) = content[pos..].find("<!--").map(|index| pos + index) {
+        // Comment markers inside code are kept as they are
+        if let Some(code) = code_ranges.iter().find(|code| code.contains(&start)) {
+            out.push_str(&content[pos..code.end]);
+            pos = code.end;
+            continue;
+        }
+
+        out.push_str(&content[pos..start]);
+
+        let end = content[start..]
+            .find("-->")
+            .map(|index| start + index + "-->".len())
+            .unwrap_or(content.len());
+
+        // Keep line breaks to preserve line numbers
+        out.extend(content[start..end].chars().filter(|c| *c == '\n'));
+
+        pos = end;
+    }
+
+    out.push_str(&content[pos..]);
+    out
+}
+
+/// Count all links (inline, reference, autolinks) and images of a Markdown file, without checking them
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::count_links_in_file;
+///
+/// println!("{} link(s)", count_links_in_file(Path::new("doc.md")).unwrap());
+/// ```
+pub fn count_links_in_file(path: &Path) -> Result<usize, String> {
+    // Get the canonicalized path for display
+    let canon = safe_canonicalize(path);
+
+    let (content, _) = read_text_file(path)
+        .map_err(|err| format!("Failed to read file at '{}': {}", canon.green(), err))?;
+
+    let (content, _, blocks) = markdown_blocks(&content, None);
+    let events = markdown_events(content, &blocks, &CheckerOptions::default()).events;
+
+    Ok(events
+        .iter()
+        .filter(|(event, _, origin)| {
+            *origin == EventOrigin::Parser
+                && matches!(event, Event::Start(Tag::Link { .. } | Tag::Image { .. }))
+        })
+        .count())
+}
+
+/// Extract all links (inline, reference, autolinks) and images of a file, without checking them
+///
+/// Links located in raw HTML blocks are included unless [`CheckerOptions::ignore_html_blocks`] is set,
+///  and links whose destination contains spaces if [`CheckerOptions::lenient_space_destinations`] is set.
+/// Fails if the file's embedded Markdown can't be extracted (e.g. for an invalid notebook).
+fn extract_links(path: &Path, options: &CheckerOptions) -> Result<Vec<ExtractedLink>, String> {
+    let (content, _) = read_text_file(path).map_err(|err| {
+        format!(
+            "Failed to read file at '{}': {}",
+            safe_canonicalize(path).green(),
+            err
+        )
+    })?;
+
+    let embedded = embedded_markdown(path, &content, options.rust_doc_comments)
+        .map_err(|err| format!("File at '{}' {}", safe_canonicalize(path).green(), err))?;
+
+    let (content, line_index, blocks) = markdown_blocks(&content, embedded.as_ref());
+
+    let mut links = vec![];
+
+    for (event, range, origin) in markdown_events(content, &blocks, options).events {
+        // Links whose destination contains spaces are only links for the checker if they are accepted
+        if origin == EventOrigin::SpacedDestination && !options.lenient_space_destinations {
+            continue;
+        }
+
+        let (link_type, dest_url, id, is_image) = match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                id,
+                ..
+            }) => (link_type, dest_url, id, false),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                id,
+                ..
+            }) => (link_type, dest_url, id, true),
+            _ => continue,
+        };
+
+        links.push(ExtractedLink {
+            line: line_index.line(range.start),
+            cell: embedded
+                .as_ref()
+                .and_then(|embedded| embedded.cell(range.start)),
+            destination: dest_url.to_string(),
+            reference: match link_type {
+                LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut => {
+                    Some(id.to_string())
+                }
+                _ => None,
+            },
+            is_image,
+            in_html_block: origin == EventOrigin::HtmlBlock,
+        });
+    }
+
+    Ok(links)
+}
+
+/// Check that the entries of a navigation file (e.g. `SUMMARY.md`) are ordered like the names of their targets,
+///  for the targets whose name has a numeric prefix (e.g. `01-intro.md`, `02-setup.md`)
+///
+/// Entries are compared with the previous entries pointing to the same directory only. Entries whose target has the same numeric prefix
+///  as another entry's target in the same directory are reported as well. Links to URLs and to locations of the file itself are ignored.
+/// Returns the line of each problematic entry, with a description of the problem.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_navigation_order, CheckerOptions};
+///
+/// for (line, problem) in check_navigation_order(Path::new("SUMMARY.md"), &CheckerOptions::default()).unwrap() {
+///     println!("line {}: {}", line, problem);
+/// }
+/// ```
+pub fn check_navigation_order(
+    nav_file: &Path,
+    options: &CheckerOptions,
+) -> Result<Vec<(usize, String)>, String> {
+    // Entries seen so far in each directory, in order, with their name and numeric prefix
+    let mut entries = HashMap::<String, Vec<(String, u64)>>::new();
+    let mut problems = vec![];
+
+    for link in extract_links(nav_file, options)? {
+        let target = link.path();
+
+        if target.is_empty() || URL_SCHEME_REGEX.is_match(target) || EMAIL_REGEX.is_match(target) {
+            continue;
+        }
+
+        let target = absolute_lexical_path(&nav_file.parent().unwrap().join(target));
+
+        let (dir, name) = match target.rsplit_once(['/', '\\']) {
+            Some((dir, name)) => (dir.to_owned(), name.to_owned()),
+            None => (String::new(), target.clone()),
+        };
+
+        let prefix = match NUMERIC_PREFIX_REGEX
+            .captures(&name)
+            .and_then(|captures| captures[1].parse::<u64>().ok())
+        {
+            Some(prefix) => prefix,
+            None => continue,
+        };
+
+        let seen = entries.entry(dir).or_default();
+
+        // Entries listed several times (e.g. for different headers) are only checked once
+        if seen.iter().any(|(seen_name, _)| *seen_name == name) {
+            continue;
+        }
+
+        if let Some((last, _)) = seen.iter().max_by(|(a, _), (b, _)| a.cmp(b)) {
+            if name < *last {
+                problems.push((
+                    link.line,
+                    format!("entry '{}' is listed after '{}'", name, last),
+                ));
+            }
+        }
+
+        if let Some((other, _)) = seen.iter().find(|(_, seen_prefix)| *seen_prefix == prefix) {
+            problems.push((
+                link.line,
+                format!("numeric prefix of '{}' is also used by '{}'", name, other),
+            ));
+        }
+
+        seen.push((name, prefix));
+    }
+
+    Ok(problems)
+}
+
+/// Build the map of all links of the files of a directory (or of a single file), without validating them
+///
+/// Files are found the same way as when checking a directory (see [`check_broken_links_simple`]), and links are extracted the same way,
+///  but their targets are not looked up on the filesystem. This is the first step of custom validation pipelines.
+/// Only failures to read files or directories, and files whose embedded Markdown can't be extracted (e.g. invalid notebooks), are errors.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{generate_link_map, CheckerOptions};
+///
+/// let map = generate_link_map(Path::new("docs"), &CheckerOptions::default()).unwrap();
+///
+/// for (file, links) in &map {
+///     for link in links {
+///         println!("{}:{}: {}", file.display(), link.line, link.destination);
+///     }
+/// }
+/// ```
+pub fn generate_link_map(
+    root: &Path,
+    options: &CheckerOptions,
+) -> Result<HashMap<PathBuf, Vec<ExtractedLink>>, String> {
+    let files = if root.is_file() {
+        vec![root.to_owned()]
+    } else {
+        let (files, failures, _) = walk_markdown_files(root, options);
+
+        if let Some((path, error)) = failures.into_iter().next() {
+            return Err(format!(
+                "In '{}': {}",
+                safe_canonicalize(&path).green(),
+                error
+            ));
+        }
+
+        files
+    };
+
+    files
+        .into_iter()
+        .map(|file| extract_links(&file, options).map(|links| (file, links)))
+        .collect()
+}
+
+/// Check if a fragment (the part of a link after `#`) points to an existing location of a file, without checking a whole file
+///
+/// This is useful to validate a single link's fragment, e.g. in an editor when a link is being typed.
+/// Fragments which can't be checked (see [`Fragment::Unknown`]) are considered as valid.
+/// The target's anchors are got from the provided cache, which is filled if the target is not already in it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_link_fragment, FileLinksCache, Fragment};
+///
+/// let mut cache = FileLinksCache::new();
+///
+/// check_link_fragment(Path::new("doc.md"), &Fragment::parse("installation"), &mut cache).unwrap();
+/// check_link_fragment(Path::new("doc.md"), &Fragment::LineNumber(3), &mut cache).unwrap();
+/// ```
+pub fn check_link_fragment(
+    target_path: &Path,
+    fragment: &Fragment,
+    cache: &mut FileLinksCache,
+) -> Result<(), String> {
+    match validate_fragment_link(target_path, fragment, &SlugOptions::default(), cache) {
+        ValidationResult::Valid | ValidationResult::Warning(_) => Ok(()),
+        ValidationResult::Broken(error) => Err(error),
+    }
+}
+
+/// Check broken links in a Markdown file or directory, which is checked recursively
+///
+/// By default, when a header points to a specific header (e.g. `other_file.md#some-header`), the target file will be opened and
+///  the function will check if it contains the said header. As this feature may slow down the whole process, it's possible to disable it by
+///  settings the `ignore_header_links` option to `true`.
+///
+/// The checker's behaviour can be customized through the `options` argument (see [`CheckerOptions`] and [`CheckerOptionsBuilder`]).
+/// To keep the caches of the anchors and targets between several checks, use [`check_broken_links_with`] instead.
+///
+/// The function returns an error is something goes wrong, or else the list of broken and invalid (without target) links.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_simple, CheckerOptions};
+///
+/// let options = CheckerOptions::default();
+///
+/// // Single file
+/// assert_eq!(check_broken_links_simple(Path::new("file.md"), &options), Ok(vec![]));
+///
+/// // Directory
+/// assert_eq!(check_broken_links_simple(Path::new("dir/"), &options), Ok(vec![]));
+/// ```
+///
+/// All kinds of links are checked: inline and reference-style links (as well as unused reference definitions), images
+///  (unless the `ignore_images` option is set), and links located in footnote definitions, headers, tables, block quotes and
+///  raw HTML blocks (unless the `ignore_html_blocks` option is set). Fragments are checked against both the headers of the
+///  target file and the `id` attributes of its HTML elements.
+///
+/// Directories are walked in parallel, skipping the files ignored by `.gitignore` and `.ignore` files unless [`CheckerOptions::no_ignore`]
+///  is set. Results don't depend on the order in which files are discovered: they are always sorted by file, then by line.
+///
+/// Files and directories which can't be read are reported as unreadable paths, and the other files are still checked
+///  (unless [`CheckerOptions::halt_on_io_error`] is set). Files larger than [`CheckerOptions::max_file_size`] are skipped,
+///  and the files remaining once [`CheckerOptions::timeout`] expires are counted in a finding.
+///
+/// The other checks (path style, extension case, Tables of Contents, changelogs, image dimensions, notebooks, ...) are opt-in,
+///  see [`CheckerOptions`].
+pub fn check_broken_links_simple(
+    path: &Path,
+    options: &CheckerOptions,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    check_broken_links_with(
+        path,
+        path.is_dir(),
+        options,
+        &mut FileLinksCache::new(),
+        &mut TargetsCache::new(),
+    )
+}
+
+/// Check broken links in a Markdown file or directory, with a cache of the anchors of the files
+///
+/// The input `path` will be checked recursively as a directory if `dir` is set to `true`, else as a single file.
+/// The other options of the checker keep their default value (see [`CheckerOptions`]).
+///
+/// To migrate to the new API:
+///
+/// * `check_broken_links(path, dir, ignore_header_links, only_files, no_errors, &mut HashMap::new())` becomes
+///   `check_broken_links_simple(path, &options)`, as directories are detected automatically
+/// * `check_broken_links(path, dir, ignore_header_links, only_files, no_errors, &mut links_cache)` becomes
+///   `check_broken_links_with(path, dir, &options, &mut links_cache, &mut TargetsCache::new())`, with a [`FileLinksCache`]
+/// * The options are built with [`CheckerOptions::builder`], e.g. `CheckerOptions::builder().no_errors(no_errors).build()`
+#[deprecated(
+    since = "1.3.0",
+    note = "use `check_broken_links_simple` (with options from `CheckerOptions::builder`), or `check_broken_links_with` to keep the caches between checks"
+)]
+pub fn check_broken_links(
+    path: &Path,
+    dir: bool,
+    ignore_header_links: bool,
+    only_files: bool,
+    no_errors: bool,
+    links_cache: &mut HashMap<PathBuf, Vec<String>>,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    let options = CheckerOptions {
+        ignore_header_links,
+        only_files,
+        no_errors,
+        ..CheckerOptions::default()
+    };
+
+    let mut cache = FileLinksCache::new();
+
+    for (file, anchors) in links_cache.iter() {
+        cache.insert(file.clone(), anchors.clone());
+    }
+
+    let result = check_broken_links_with(path, dir, &options, &mut cache, &mut TargetsCache::new());
+
+    links_cache.extend(
+        cache
+            .entries()
+            .into_iter()
+            .map(|(file, anchors)| (file, anchors.to_vec())),
+    );
+
+    result
+}
+
+/// Check broken links in a Markdown file or directory, with a cache of the resolved targets
+///
+/// The cache can be shared between several checks to avoid resolving the same targets again,
+///  as long as no file is created, moved or removed in the meantime.
+/// Links with the same destination in a file are only validated once.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_with, CheckerOptions, FileLinksCache, TargetsCache};
+///
+/// let options = CheckerOptions::default();
+/// let mut links_cache = FileLinksCache::new();
+/// let mut targets_cache = TargetsCache::new();
+///
+/// // The caches are kept between the checks
+/// for dir in &["docs", "guides"] {
+///     let errors = check_broken_links_with(Path::new(dir), true, &options, &mut links_cache, &mut targets_cache).unwrap();
+///     println!("{}: {} broken link(s)", dir, errors.len());
+/// }
+/// ```
+pub fn check_broken_links_with(
+    path: &Path,
+    dir: bool,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    check_path(path, dir, false, options, links_cache, targets_cache)
+}
+
+/// Check broken links in a Markdown file or directory like [`check_broken_links_with`], getting the number of files
+///  and links checked alongside the findings
+///
+/// Files and links checked with the targets cache since its statistics were last taken are counted (see [`TargetsCache::take_check_stats`]).
+/// The result can be serialized, and is valid even without findings.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_report, CheckerOptions, FileLinksCache, TargetsCache};
+///
+/// let result = check_broken_links_report(Path::new("docs"), true, &CheckerOptions::default(), &mut FileLinksCache::new(), &mut TargetsCache::new()).unwrap();
+///
+/// println!("{} broken link(s) out of {} in {} file(s)", result.broken_count(), result.stats.links, result.stats.files);
+/// ```
+pub fn check_broken_links_report(
+    path: &Path,
+    dir: bool,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> Result<CheckResult, String> {
+    let findings = check_broken_links_with(path, dir, options, links_cache, targets_cache)?;
+
+    Ok(CheckResult {
+        findings,
+        stats: targets_cache.take_check_stats(),
+    })
+}
+
+/// Check broken links in a Markdown file found while walking a directory
+///
+/// If it can't be read, it's reported as an unreadable path instead of failing (unless [`CheckerOptions::halt_on_io_error`] is set).
+pub(crate) fn check_walked_file(
+    path: &Path,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    // Messages are written once the file is checked, so they don't interleave with the ones of files checked at the same time
+    output::buffered(|| check_path(path, false, true, options, links_cache, targets_cache))
+}
+
+/// Check the links of a Markdown file after some files changed (e.g. the files which are about to be committed)
+///
+/// All links are validated as usual, including the ones to files which don't exist anymore (e.g. removed ones).
+/// The anchors of the changed files are generated again, while the cache is used for the other files,
+///  so the same cache can be used for several checks, e.g. of each changed file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_links_between_files, CheckerOptions, FileLinksCache};
+///
+/// let changed = [Path::new("docs/guide.md"), Path::new("docs/removed.md")];
+/// let errors = check_links_between_files(Path::new("docs/README.md"), &changed, &CheckerOptions::default(), &mut FileLinksCache::new()).unwrap();
+/// ```
+pub fn check_links_between_files(
+    source: &Path,
+    targets: &[&Path],
+    options: &CheckerOptions,
+    cache: &mut FileLinksCache,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    for target in targets {
+        if let Ok(canonical) = target.canonicalize() {
+            cache.remove(&canonical);
+        }
+    }
+
+    check_broken_links_with(source, false, options, cache, &mut TargetsCache::new())
+}
+
+/// Make a path absolute and canonicalize it lexically, without accessing the filesystem (the path may not exist)
+pub(crate) fn absolute_lexical_path(path: &Path) -> String {
+    safe_canonicalize(&std::path::absolute(path).unwrap_or_else(|_| path.to_owned()))
+}
+
+/// Check broken links in a Markdown file or directory (see [`check_broken_links_with`] and [`check_walked_file`])
+fn check_path(
+    path: &Path,
+    dir: bool,
+    walked: bool,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    /// Display a broken/invalid link error
+    macro_rules! err_or_warn {
+        ($($arg: expr),*) => {
+            if options.no_errors {
+                warn!($($arg),*);
+            } else {
+                error!($($arg),*);
+            }
+        }
+    }
+
+    // Get the canonicalized path for display
+    let canon = safe_canonicalize(path);
+
+    // List of broken/invalid links
+    let mut errors = vec![];
+
+    if dir {
+        check_dir_with_callback(
+            path,
+            options,
+            links_cache,
+            targets_cache,
+            &mut |error| errors.push(error),
+            &|| false,
+        )?;
+    } else {
+        // Treat input as a file
+        info!("Analyzing: {}", canon);
+
+        // Options of the file, once overridden by the configuration files which apply to it
+        let options = &*options.resolve(path);
+        let slugs = options.slugs_for(path);
+
+        if let Some(warning) = file_too_large(path, options) {
+            errors.push(file_warning(path, warning, options));
+            return Ok(errors);
+        }
+
+        // Time at which the file started to be checked, and time spent generating slugs before (which is not part of its check)
+        let started = Instant::now();
+        profile::take_slug_generation_time();
+
+        let (source, encoding_problem) = match FileContent::read(path) {
+            Ok(read) => read,
+
+            // Files which can't be read (e.g. because they were removed since the directory was walked)
+            //  don't prevent the other files from being checked
+            Err(err) if walked && !options.halt_on_io_error => {
+                return report_unreadable_paths(
+                    vec![(path.to_owned(), format!("Failed to read file: {}", err))],
+                    options,
+                )
+            }
+
+            Err(err) => {
+                return Err(format!(
+                    "Failed to read file at '{}': {}",
+                    canon.green(),
+                    err
+                ))
+            }
+        };
+
+        // Mapped contents are validated each time they are accessed
+        let content: &str = &source;
+
+        if let Some(problem) = encoding_problem {
+            match options.invalid_utf8 {
+                InvalidUtf8Handling::Skip => {
+                    errors.push(file_warning(
+                        path,
+                        format!("file {}, skipping it", problem),
+                        options,
+                    ));
+                    return Ok(errors);
+                }
+                InvalidUtf8Handling::Lossy => errors.push(file_warning(
+                    path,
+                    format!("file {} (checking it anyway)", problem),
+                    options,
+                )),
+                InvalidUtf8Handling::Error => {
+                    return Err(format!("File at '{}' {}", canon.green(), problem))
+                }
+            }
+        }
+
+        let read_time = started.elapsed();
+
+        trace!(
+            "In '{}': just read file, which is {} bytes long.",
+            canon,
+            content.len()
+        );
+
+        // Only the Markdown embedded in other kinds of files (e.g. the doc comments of Rust source files) is checked
+        let embedded = match embedded_markdown(path, content, options.rust_doc_comments) {
+            Ok(embedded) => embedded,
+            Err(err) => {
+                errors.push(file_warning(
+                    path,
+                    format!("file {}, skipping it", err),
+                    options,
+                ));
+                return Ok(errors);
+            }
+        };
+
+        // Identity of the file's content, to share its headers with identical files
+        #[cfg(feature = "content-hash")]
+        let content_key = match embedded {
+            None => Some(content_hash::ContentKey::new(
+                path,
+                content.as_bytes(),
+                &slugs,
+            )),
+            Some(_) => None,
+        };
+
+        // Ensure the front matter has the required fields
+        if embedded.is_none() && !options.frontmatter_required_fields.is_empty() {
+            match validate_frontmatter_schema(content, &options.frontmatter_required_fields) {
+                Ok(missing) => {
+                    for field in missing {
+                        let error = format!("missing required front matter field '{}'", field);
+
+                        err_or_warn!("In '{}': {}", canon.green(), error);
+
+                        errors.push(DetectedBrokenLink {
+                            file: path.to_owned(),
+                            line: 0,
+                            cell: None,
+                            kind: BrokenLinkKind::MissingFrontmatterField,
+                            severity: Severity::Error,
+                            error,
+                            suggestion: None,
+                            target: None,
+                            column: None,
+                            link_text: None,
+                        });
+                    }
+                }
+                Err(err) => errors.push(file_warning(path, err, options)),
+            }
+        }
+
+        // Number of lines of the file, for the links to one of its lines (e.g. `#L42`)
+        let line_count = content.lines().count();
+
+        // Content to parse, with the index of its lines and the blocks to parse independently
+        let (content, line_index, blocks) = markdown_blocks(content, embedded.as_ref());
+
+        // Are links located in the doc comments of a Rust source file?
+        let in_doc_comments = options.rust_doc_comments && is_rust_source_file(path);
+
+        // Directory containing the local issues, if this file is a changelog which must be checked against them
+        let issues_dir = changelog_issues_dir(path, options);
+
+        // Target and alternative text of the image being visited, if any
+        let mut image: Option<(String, String)> = None;
+
+        // Label of the footnote definition being visited, if any
+        let mut footnote: Option<String> = None;
+
+        // Resolved targets and validated fragments of the links, indexed by their written destination,
+        //  as generated files often contain the same link many times
+        let mut resolutions = HashMap::<String, (PathBuf, String, Option<ResolvedTarget>)>::new();
+        let mut fragment_results = HashMap::<String, ValidationResult>::new();
+
+        // Findings which were already displayed, to only display the first occurrence of each one if asked to
+        let mut displayed_findings = HashSet::<(Option<usize>, BrokenLinkKind, String)>::new();
+
+        // Parse the whole file first, so its headers are known before links pointing to them (e.g. `#some-header`) are checked
+        let MarkdownEvents {
+            events,
+            definitions,
+            unresolved_references,
+        } = markdown_events(content, &blocks, options);
+
+        // Reference definitions which no link uses are checked like links, so a definition isn't silently broken
+        //  until a link using it is added
+        let events = unused_definitions_as_links(events, definitions);
+
+        // Count links without a target (like `[link name]`) as an error (reported with the other findings afterwards),
+        //  except in doc comments where they are intra-doc links (like ``[`Vec`]``)
+        let unresolved_references = unresolved_references
+            .into_iter()
+            .filter(|(_, reference)| {
+                if in_doc_comments {
+                    trace!("In '{}': found intra-doc link: {}", canon, reference);
+                    false
+                } else if options.ignore_unresolved_references {
+                    trace!("In '{}': found link without a target: {}", canon, reference);
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let slugs_started = Instant::now();
+
+        let canonical_path = path.canonicalize().ok();
+
+        // Store the file's headers in the cache, so it doesn't need to be parsed again if it's linked to
+        if let Some(unified_path) = canonical_path.clone() {
+            targets_cache.record_file(&unified_path);
+
+            if !links_cache.contains(&unified_path) {
+                let mut headers = HeadersCollector::new(&slugs);
+
+                for (event, _, _) in &events {
+                    headers.feed(event);
+                }
+
+                let anchors = headers.into_anchors();
+
+                #[cfg(feature = "content-hash")]
+                if let Some(key) = content_key {
+                    links_cache.contents().store(&unified_path, key, &anchors);
+                }
+
+                links_cache.insert(unified_path, anchors);
+            }
+        }
+
+        // Ensure the entries of the navigation file are ordered like their targets' names
+        if let Some(ref navigation_file) = options.navigation_file {
+            if canonical_path.is_some() && canonical_path == navigation_file.canonicalize().ok() {
+                let severity = match options.navigation_order_severity {
+                    Severity::Warning => warning_severity(options),
+                    severity => severity,
+                };
+
+                for (line, error) in check_navigation_order(path, options)? {
+                    let message = format!(
+                        "In {}{} {}",
+                        canon.green(),
+                        format_location(line, None).yellow(),
+                        error
+                    );
+
+                    match severity {
+                        Severity::Error => err_or_warn!("{}", message),
+                        Severity::Warning => warn!("{}", message),
+                        Severity::Info => info!("{}", message),
+                    }
+
+                    errors.push(DetectedBrokenLink {
+                        file: path.to_owned(),
+                        line,
+                        cell: None,
+                        kind: BrokenLinkKind::NavigationOrder,
+                        severity,
+                        error,
+                        suggestion: None,
+                        target: None,
+                        column: None,
+                        link_text: None,
+                    });
+                }
+            }
+        }
+
+        // Ensure the files included by mdBook's directives exist, as well as the included anchors
+        if options.check_mdbook_includes && embedded.is_none() {
+            for (offset, kind, error) in mdbook_includes::check_includes(path, content) {
+                let line = line_index.line(offset);
+
+                err_or_warn!(
+                    "In {}{} {}",
+                    canon.green(),
+                    format_location(line, None).yellow(),
+                    error
+                );
+
+                errors.push(DetectedBrokenLink {
+                    file: path.to_owned(),
+                    line,
+                    cell: None,
+                    kind,
+                    severity: Severity::Error,
+                    error,
+                    suggestion: None,
+                    target: None,
+                    column: None,
+                    link_text: None,
+                });
+            }
+        }
+
+        // Warnings about headers, indexed by the event they end at
+        let mut header_warnings = header_warnings(&events, &slugs, options);
+
+        let own_slugs_time = slugs_started.elapsed();
+
+        // Text of the links and images, indexed by the event they start at
+        let link_texts = link_texts(&events);
+
+        // Number of links checked in the file
+        let mut link_count = 0;
+
+        let mut unresolved_references = unresolved_references.into_iter().peekable();
+
+        for (index, (event, range, origin)) in events.into_iter().enumerate() {
+            let line = || line_index.line(range.start);
+            let cell = || {
+                embedded
+                    .as_ref()
+                    .and_then(|embedded| embedded.cell(range.start))
+            };
+
+            // Destination of the link being checked (after placeholders substitution), for the findings about it
+            let mut link_target: Option<String> = None;
+
+            // Label of the reference definition being checked, if it's an unused one
+            let definition = match (&event, origin) {
+                (Event::Start(Tag::Link { id, .. }), EventOrigin::ReferenceDefinition) => {
+                    Some(id.to_string())
+                }
+                _ => None,
+            };
+
+            macro_rules! format_msg {
+                ($($param: expr),*) => {{
+                    let location = match footnote {
+                        Some(ref label) => format!("{} (in footnote '{}')", format_location(line(), cell()), label),
+                        None => format_location(line(), cell()),
+                    };
+
+                    format!("In {}{} {}", canon.green(), location.yellow(), format!($($param),*))
+                }}
+            }
+
+            /// Report a problem found by one of the checks of the link
+            macro_rules! report {
+                ($finding: expr) => {{
+                    let LinkFinding { mut kind, severity, mut error, suggestion } = $finding;
+
+                    // Unused reference definitions are mentioned in messages, as they are checked where they are written
+                    if let Some(ref label) = definition {
+                        error = format!("{} (in the definition of reference '{}')", error, label);
+
+                        if kind == BrokenLinkKind::MissingTarget {
+                            kind = BrokenLinkKind::DefinitionTargetMissing;
+                        }
+                    }
+
+                    // Links to missing targets are displayed afterwards if they must be grouped by target
+                    let grouped = options.group_by_target && kind == BrokenLinkKind::MissingTarget;
+
+                    if !grouped && (!options.dedupe_findings
+                        || displayed_findings.insert((cell(), kind, error.clone())))
+                    {
+                        match severity {
+                            Severity::Error => err_or_warn!("{}", format_msg!("{}", highlight_quoted(&error))),
+                            Severity::Warning => warn!("{}", format_msg!("{}", highlight_quoted(&error))),
+                            Severity::Info => info!("{}", format_msg!("{}", highlight_quoted(&error))),
+                        }
+                    }
+
+                    errors.push(DetectedBrokenLink {
+                        file: path.to_owned(),
+                        line: line(),
+                        cell: cell(),
+                        kind,
+                        severity,
+                        error,
+                        suggestion,
+                        target: link_target.clone(),
+                        column: Some(line_index.line_col(content, range.start).1),
+                        link_text: link_texts.get(&index).cloned(),
+                    });
+                }};
+            }
+
+            /// Report a broken/invalid link
+            macro_rules! broken_link {
+                ($kind: ident, $($param: expr),*) => {
+                    report!(LinkFinding::error(BrokenLinkKind::$kind, format!($($param),*)))
+                };
+            }
+
+            /// Report a warning, as an error if warnings are treated as errors
+            macro_rules! warning {
+                ($($param: expr),*) => {
+                    report!(LinkFinding::warning(format!($($param),*), options))
+                };
+            }
+
+            for warning in header_warnings.remove(&index).unwrap_or_default() {
+                warning!("{}", warning);
+            }
+
+            // Report the links without a target located at this event
+            while let Some((_, reference)) =
+                unresolved_references.next_if(|(offset, _)| *offset <= range.start)
+            {
+                broken_link!(
+                    UnresolvedReference,
+                    "missing target for link '{}': no reference definition found",
+                    reference
+                );
+            }
+
+            // Links inside footnote definitions are checked like any other link,
+            //  but the footnote is mentioned in messages as definitions are usually located far from their reference
+            match event {
+                Event::Start(Tag::FootnoteDefinition(ref label)) => {
+                    footnote = Some(label.to_string())
+                }
+                Event::End(TagEnd::FootnoteDefinition) => footnote = None,
+                _ => {}
+            }
+
+            // Collect the alternative text of images if it's required
+            if options.require_alt_text {
+                match event {
+                    Event::Start(Tag::Image { ref dest_url, .. }) => {
+                        image = Some((dest_url.to_string(), String::new()))
+                    }
+
+                    Event::Text(ref text) | Event::Code(ref text) => {
+                        if let Some((_, ref mut alt)) = image {
+                            alt.push_str(text);
+                        }
+                    }
+
+                    Event::End(TagEnd::Image) => {
+                        if let Some((target, alt)) = image.take() {
+                            if alt.trim().is_empty() {
+                                warning!(
+                                    "image '{}' has no alternative text [{}]",
+                                    target,
+                                    BrokenLinkKind::MissingAltText.id()
+                                );
+                            }
+                        }
+                    }
+
+                    _ => {}
+                }
+            }
+
+            // Substitute the placeholders of the destination of images before checking them
+            let image_target = match event {
+                Event::Start(Tag::Image { ref dest_url, .. }) => {
+                    match substitute_placeholders(dest_url, &options.placeholders) {
+                        Ok(substituted) => Some(substituted.into_owned()),
+                        Err(names) => {
+                            if !options.ignore_images {
+                                link_target = Some(dest_url.to_string());
+                                report!(link_checks::unresolved_placeholders(
+                                    &names, dest_url, options
+                                ));
+                            }
+
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+
+            // Check that local images can be decoded
+            #[cfg(feature = "image-check")]
+            if options.check_image_dimensions {
+                if let Some(ref dest_url) = image_target {
+                    if let Some(finding) = link_checks::check_image_content(path, dest_url, options)
+                    {
+                        report!(finding);
+                    }
+                }
+            }
+
+            // Ensure local images exist
+            if !options.ignore_images {
+                if let Some(ref dest_url) = image_target {
+                    let written_target = dest_url.split('#').next().unwrap();
+
+                    if !written_target.is_empty()
+                        && !URL_SCHEME_REGEX.is_match(written_target)
+                        && !WINDOWS_ABSOLUTE_PATH_REGEX.is_match(written_target)
+                    {
+                        link_count += 1;
+                        link_target = Some(dest_url.clone());
+
+                        if let ValidationResult::Broken(error) = validate::validate_image_link(
+                            &path.parent().unwrap().join(written_target),
+                        ) {
+                            broken_link!(MissingImage, "{}", error);
+                        }
+                    }
+                }
+            }
+
+            // Report the links whose text is their destination (autolinks are the way to display a bare destination)
+            if let Some(severity) = options.link_text_is_url {
+                if let Event::Start(Tag::Link {
+                    link_type:
+                        LinkType::Inline
+                        | LinkType::Reference
+                        | LinkType::Collapsed
+                        | LinkType::Shortcut,
+                    ref dest_url,
+                    ..
+                }) = event
+                {
+                    let text = link_texts.get(&index).map_or("", String::as_str);
+
+                    if let Some(finding) =
+                        link_checks::check_link_text(text, dest_url, severity, options)
+                    {
+                        link_target = Some(dest_url.to_string());
+                        report!(finding);
+                    }
+                }
+            }
+
+            // Check inline and reference-style links only (not URLs or e-mail addresses in autolinks for instance)
+            if let Event::Start(Tag::Link {
+                link_type:
+                    LinkType::Inline | LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut,
+                dest_url: unsplit_target,
+                ..
+            }) = event
+            {
+                link_count += 1;
+
+                // Substitute the placeholders of the destination before resolving it
+                let unsplit_target =
+                    match substitute_placeholders(&unsplit_target, &options.placeholders) {
+                        Ok(Cow::Borrowed(_)) => unsplit_target,
+                        Ok(Cow::Owned(substituted)) => CowStr::from(substituted),
+                        Err(names) => {
+                            link_target = Some(unsplit_target.to_string());
+                            report!(link_checks::unresolved_placeholders(
+                                &names,
+                                &unsplit_target,
+                                options
+                            ));
+                            continue;
+                        }
+                    };
+
+                link_target = Some(unsplit_target.to_string());
+
+                // Links whose destination contains spaces without angle brackets are not links for CommonMark,
+                //  but many renderers accept them so they are likely intended to be
+                if origin == EventOrigin::SpacedDestination {
+                    let written_target = unsplit_target.split('#').next().unwrap();
+
+                    if options.lenient_space_destinations
+                        && path.parent().unwrap().join(written_target).exists()
+                    {
+                        warning!(
+                            "destination '{}' contains spaces, write it '<{}>' or '{}' instead",
+                            unsplit_target,
+                            unsplit_target,
+                            unsplit_target.replace(' ', "%20")
+                        );
+                    } else {
+                        warning!(
+                            "'{}' is not a link as its destination '{}' contains spaces, write it '<{}>' or '{}' instead",
+                            &content[range.clone()],
+                            unsplit_target,
+                            unsplit_target,
+                            unsplit_target.replace(' ', "%20")
+                        );
+
+                        continue;
+                    }
+                }
+
+                // Ensure links named after an issue number reference an existing local issue in changelogs
+                if let Some(ref issues_dir) = issues_dir {
+                    if let Some(finding) =
+                        link_checks::check_changelog_issue(&content[range.clone()], issues_dir)
+                    {
+                        report!(finding);
+                    }
+                }
+
+                let destination = unsplit_target.to_string();
+
+                // Get the link's target file and optionally its fragment
+                let (target, fragment): (String, Option<Fragment>) =
+                    match unsplit_target.split_once('#') {
+                        Some((target, fragment)) => {
+                            (target.to_owned(), Some(Fragment::parse(fragment)))
+                        }
+                        None => (unsplit_target.into_string(), None),
+                    };
+
+                // Absolute Windows paths only work on a single machine
+                // This is checked before URLs as drive letters look like URL schemes
+                if let Some(finding) = link_checks::check_machine_specific_path(path, &target) {
+                    report!(finding);
+                    continue;
+                }
+
+                // Don't care about URLs, but validate the addresses of "mailto:" links if asked to
+                if let Some(scheme) = URL_SCHEME_REGEX.captures(&target) {
+                    // Links with a custom scheme are validated by their handler first
+                    let verdict = options
+                        .scheme_handlers
+                        .get(&scheme[1])
+                        .map(|handler| handler(&destination));
+
+                    if let Some(verdict) = verdict {
+                        match verdict {
+                            SchemeVerdict::Valid => {
+                                trace!(
+                                    "{}",
+                                    format_msg!(
+                                        "valid link with a custom scheme found: {}",
+                                        destination
+                                    )
+                                );
+                                continue;
+                            }
+                            SchemeVerdict::Invalid(reason) => {
+                                broken_link!(
+                                    InvalidSchemeLink,
+                                    "invalid link found: '{}': {}",
+                                    destination,
+                                    reason
+                                );
+                                continue;
+                            }
+                            SchemeVerdict::Skip => {}
+                        }
+                    }
+
+                    // Ensure HTTP and HTTPS links are reachable if asked to
+                    #[cfg(feature = "check-http")]
+                    if options.check_http_links
+                        && matches!(scheme[1].to_ascii_lowercase().as_str(), "http" | "https")
+                    {
+                        match targets_cache.check_url(
+                            &target,
+                            options.follow_redirects,
+                            options
+                                .http_timeout
+                                .unwrap_or(validate::DEFAULT_HTTP_TIMEOUT),
+                        ) {
+                            ValidationResult::Valid => {
+                                trace!("{}", format_msg!("valid URL found: {}", target))
+                            }
+                            ValidationResult::Broken(error) => broken_link!(BrokenUrl, "{}", error),
+                            ValidationResult::Warning(warning) => warning!("{}", warning),
+                        }
+
+                        continue;
+                    }
+
+                    if is_mailto_link(&target) {
+                        trace!("{}", format_msg!("found mailto link: {}", target));
+
+                        if options.check_mailto {
+                            for address in malformed_mailto_addresses(&target) {
+                                warning!(
+                                    "malformed e-mail address '{}' in link '{}'",
+                                    address,
+                                    target
+                                );
+                            }
+                        }
+                    } else {
+                        trace!("{}", format_msg!("found link to URL: {}", target));
+                    }
+
+                    continue;
+                }
+
+                if EMAIL_REGEX.is_match(&target) {
+                    trace!("{}", format_msg!("found link to e-mail addres: {}", target));
+                    continue;
+                }
+
+                let written_target = target;
+
+                // Warn about suspiciously long targets before trying to resolve them
+                if let Some(finding) = link_checks::check_link_length(&written_target, options) {
+                    report!(finding);
+                }
+
+                let (target, target_canon, resolved) = resolutions
+                    .entry(written_target.clone())
+                    .or_insert_with(|| {
+                        let target = if !written_target.is_empty() {
+                            path.parent().unwrap().join(Path::new(&written_target))
+                        } else {
+                            path.to_owned()
+                        };
+
+                        let target_canon = safe_canonicalize(&target);
+                        let resolved = targets_cache.resolve(&target);
+
+                        (target, target_canon, resolved)
+                    })
+                    .clone();
+
+                // Ensure the target's extension has the same casing as on disk if asked to
+                if options.check_extension_case {
+                    if let Some(finding) = link_checks::check_extension_case(
+                        &target,
+                        &written_target,
+                        fragment.as_ref(),
+                    ) {
+                        report!(finding);
+
+                        // On case-sensitive filesystems the target does not exist, which has just been reported more precisely
+                        if resolved.is_none() {
+                            continue;
+                        }
+                    }
+                }
+
+                let resolved = match resolved {
+                    Some(resolved) => resolved,
+                    None => {
+                        broken_link!(
+                            MissingTarget,
+                            "broken link found: {}",
+                            missing_target_reason(&target)
+                        );
+                        continue;
+                    }
+                };
+
+                if options.only_files && !resolved.is_file {
+                    broken_link!(DirectoryLink, "invalid link found: path '{}' is a directory but only file links are allowed", target_canon);
+                    continue;
+                }
+
+                trace!("{}", format_msg!("valid link found: {}", target_canon));
+
+                // Register the link in the links graph, if it's between two Markdown files
+                if let Some(ref source) = canonical_path {
+                    if resolved.is_file
+                        && resolved.canonical != *source
+                        && resolved.canonical.extension().and_then(|ext| ext.to_str()) == Some("md")
+                    {
+                        targets_cache.record_link(source, &resolved.canonical);
+                    }
+                }
+
+                // Warn about links leading to redirects
+                if let Some(finding) =
+                    link_checks::check_redirect(&written_target, &resolved, options, targets_cache)
+                {
+                    report!(finding);
+                }
+
+                // Report links to files which won't be published
+                if let Some(finding) =
+                    link_checks::check_draft(&written_target, &resolved, options, targets_cache)
+                {
+                    report!(finding);
+                }
+
+                // Ensure the target is written in its shortest relative form if asked to
+                if options.check_path_style {
+                    if let Some(finding) = link_checks::check_path_style(
+                        path,
+                        &written_target,
+                        fragment.as_ref(),
+                        options,
+                    ) {
+                        report!(finding);
+                    }
+                }
+
+                // If header links must be checked...
+                if !options.ignore_header_links {
+                    // If the link points to a specific location (header, HTML anchor, line)...
+                    if let Some(fragment) = fragment {
+                        debug!(
+                            "{}",
+                            format_msg!(
+                                "now checking link '{}' from file '{}'",
+                                fragment,
+                                target_canon
+                            )
+                        );
+
+                        let result = fragment_results.entry(destination).or_insert_with(|| {
+                            // Links to the file itself are validated against its already known anchors
+                            let own_anchors = match written_target.is_empty() {
+                                true => canonical_path
+                                    .as_ref()
+                                    .and_then(|canonical| links_cache.get(canonical)),
+                                false => None,
+                            };
+
+                            let result = match own_anchors {
+                                Some(anchors) => {
+                                    validate_same_file_fragment(&fragment, &anchors, line_count)
+                                }
+                                None => link_checks::validate_target_fragment(
+                                    &target,
+                                    &resolved,
+                                    &fragment,
+                                    options,
+                                    links_cache,
+                                    targets_cache,
+                                ),
+                            };
+
+                            link_checks::validate_anchor_alias(
+                                result,
+                                &resolved,
+                                &fragment,
+                                options,
+                                links_cache,
+                                targets_cache,
+                            )
+                        });
+
+                        match result.clone() {
+                            ValidationResult::Valid => {
+                                trace!("{}", format_msg!("valid fragment link found: {}", fragment))
+                            }
+                            ValidationResult::Broken(error) => {
+                                report!(link_checks::broken_fragment(
+                                    error,
+                                    &fragment,
+                                    &written_target,
+                                    &resolved,
+                                    options,
+                                    links_cache,
+                                ))
+                            }
+                            ValidationResult::Warning(warning) => warning!("{}", warning),
+                        }
+                    }
+                }
+            }
+        }
+
+        targets_cache.record_checked_file(link_count);
+
+        if options.profile {
+            targets_cache.record_profile(profile::FileProfile::new(
+                path,
+                started,
+                read_time,
+                own_slugs_time,
+                link_count,
+                &errors,
+            ));
+        }
+
+        // Findings of the whole file (e.g. about its table of contents) are sorted with the ones of its links
+        errors.sort_by_key(|error| (error.cell, error.line));
+    }
+
+    // Everything went fine :D
+    Ok(errors)
+}
+
+/// Check broken links in all Markdown files of a directory, providing them to a callback as soon as each file is checked
+///  (or once all files are checked, when they are checked in parallel)
+///
+/// No more files are checked once `stopped` returns `true`, e.g. because the results are not needed anymore.
+pub(crate) fn check_dir_with_callback(
+    path: &Path,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+    on_error: &mut dyn FnMut(DetectedBrokenLink),
+    stopped: &(dyn Fn() -> bool + Sync),
+) -> Result<(), String> {
+    debug!("Analyzing directory: {}", safe_canonicalize(path));
+
+    let deadline = check_deadline(options);
+    let (files, failures, walk_warnings) = walk_markdown_files(path, options);
+
+    report_unreadable_paths(failures, options)?
+        .into_iter()
+        .chain(walk_warnings)
+        .for_each(&mut *on_error);
+
+    let (files, size_warnings) = skip_large_files(files, options);
+    size_warnings.into_iter().for_each(&mut *on_error);
+
+    if options.parallel || options.max_concurrent_dirs > 1 {
+        // Files are numbered in the order they were walked in, so the results can be provided in this order
+        let files = files.into_iter().enumerate();
+
+        let groups = match options.parallel {
+            true => vec![files.collect()],
+            false => {
+                let mut dirs = BTreeMap::<PathBuf, Vec<(usize, PathBuf)>>::new();
+
+                for (index, file) in files {
+                    let dir = file.parent().map(Path::to_owned).unwrap_or_default();
+                    dirs.entry(dir).or_default().push((index, file));
+                }
+
+                dirs.into_values().collect()
+            }
+        };
+
+        let unchecked = check_in_parallel(
+            groups,
+            deadline,
+            options,
+            links_cache,
+            targets_cache,
+            on_error,
+            stopped,
+        )?;
+
+        if unchecked > 0 {
+            on_error(timeout_finding(path, unchecked, options));
+        }
+
+        return Ok(());
+    }
+
+    for (index, file) in files.iter().enumerate() {
+        if stopped() {
+            break;
+        }
+
+        if deadline_expired(deadline) {
+            on_error(timeout_finding(path, files.len() - index, options));
+            break;
+        }
+
+        check_walked_file(file, options, links_cache, targets_cache)?
+            .into_iter()
+            .for_each(&mut *on_error);
+    }
+
+    Ok(())
+}
+
+/// Check groups of files (e.g. the files of each directory) on several threads, along with the index of each file in the walk
+///
+/// Up to [`CheckerOptions::max_concurrent_dirs`] groups are checked at the same time (a single one if [`CheckerOptions::parallel`] is set),
+///  the files of each group being checked on their share of the available threads.
+/// The results are provided to the callback once all files are checked, in the order of the files' indexes.
+/// Returns the number of files left unchecked because the deadline expired.
+fn check_in_parallel(
+    groups: Vec<Vec<(usize, PathBuf)>>,
+    deadline: Option<Instant>,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+    on_error: &mut dyn FnMut(DetectedBrokenLink),
+    stopped: &(dyn Fn() -> bool + Sync),
+) -> Result<usize, String> {
+    let group_threads = match options.parallel {
+        true => 1,
+        false => options.max_concurrent_dirs.min(groups.len()),
+    };
+
+    let available_threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let file_threads = (available_threads / group_threads.max(1)).max(1);
+
+    let pending = Mutex::new(groups.into_iter());
+    let shared_links_cache = &*links_cache;
+
+    // Number of files left unchecked because the deadline expired
+    let unchecked = AtomicUsize::new(0);
+
+    let outcomes = std::thread::scope(|scope| {
+        let workers = (0..group_threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut outcomes = vec![];
+
+                    loop {
+                        let next = pending.lock().unwrap().next();
+
+                        let files = match next {
+                            Some(files) => files,
+                            None => break,
+                        };
+
+                        outcomes.extend(check_files_in_parallel(
+                            files,
+                            file_threads,
+                            deadline,
+                            &unchecked,
+                            options,
+                            shared_links_cache,
+                            stopped,
+                        ));
+                    }
+
+                    outcomes
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    let mut results = vec![];
+
+    for (worker_results, worker_targets_cache) in outcomes {
+        targets_cache.merge(worker_targets_cache);
+        results.extend(worker_results);
+    }
+
+    // Put back the results in the order the files were walked in
+    results.sort_by_key(|(index, _)| *index);
+
+    for (_, result) in results {
+        result?.into_iter().for_each(&mut *on_error);
+    }
+
+    Ok(unchecked.into_inner())
+}
+
+/// Result of the check of a file, along with the index of the file in the walk
+type IndexedResult = (usize, Result<Vec<DetectedBrokenLink>, String>);
+
+/// Check files on several threads, along with the index of each file in the walk
+///
+/// Files are not checked anymore once the deadline expired, in which case they are counted in `unchecked`,
+///  or once `stopped` returns `true`.
+/// Returns the results of each thread, along with the targets cache it used.
+fn check_files_in_parallel(
+    files: Vec<(usize, PathBuf)>,
+    threads: usize,
+    deadline: Option<Instant>,
+    unchecked: &AtomicUsize,
+    options: &CheckerOptions,
+    links_cache: &FileLinksCache,
+    stopped: &(dyn Fn() -> bool + Sync),
+) -> Vec<(Vec<IndexedResult>, TargetsCache)> {
+    let threads = threads.min(files.len());
+    let pending = Mutex::new(files.into_iter());
+
+    std::thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    // Clones of the cache share its entries, so each target is only parsed once for all threads
+                    let mut links_cache = links_cache.clone();
+                    let mut targets_cache = TargetsCache::new();
+                    let mut results = vec![];
+
+                    loop {
+                        let next = pending.lock().unwrap().next();
+
+                        let (index, file) = match next {
+                            Some(next) if !stopped() => next,
+                            _ => break,
+                        };
+
+                        if deadline_expired(deadline) {
+                            unchecked.fetch_add(1, Ordering::Relaxed);
+                            continue;
+                        }
+
+                        let result =
+                            check_walked_file(&file, options, &mut links_cache, &mut targets_cache);
+
+                        results.push((index, result));
+                    }
+
+                    (results, targets_cache)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+/// Check broken links in a Markdown file or directory, providing each of them to a callback instead of collecting them
+///
+/// Broken links are provided as soon as the file containing them is checked, so they don't accumulate
+///  when checking large directories (unless files are checked in parallel, in which case they are provided once all files are checked).
+/// The callback is called from the current thread.
+/// Returns the number of broken links.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_with_callback, CheckerOptions, FileLinksCache};
+///
+/// let count = check_broken_links_with_callback(Path::new("docs"), true, &CheckerOptions::default(), &mut FileLinksCache::new(), |error| {
+///     eprintln!("{}:{}: {}", error.file.display(), error.line, error.error)
+/// }).unwrap();
+/// ```
+pub fn check_broken_links_with_callback(
+    path: &Path,
+    dir: bool,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    mut on_error: impl FnMut(DetectedBrokenLink),
+) -> Result<usize, String> {
+    let mut count = 0;
+
+    check_path_with_callback(
+        path,
+        dir,
+        options,
+        links_cache,
+        &mut TargetsCache::new(),
+        &mut |error| {
+            count += 1;
+            on_error(error);
+        },
+    )?;
+
+    Ok(count)
+}
+
+/// Check broken links in a Markdown file or directory like [`check_broken_links_with_callback`], with the provided targets cache
+pub(crate) fn check_path_with_callback(
+    path: &Path,
+    dir: bool,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+    on_error: &mut dyn FnMut(DetectedBrokenLink),
+) -> Result<(), String> {
+    if dir {
+        check_dir_with_callback(path, options, links_cache, targets_cache, on_error, &|| {
+            false
+        })
+    } else {
+        check_broken_links_with(path, false, options, links_cache, targets_cache)?
+            .into_iter()
+            .for_each(on_error);
+
+        Ok(())
+    }
+}
+
+/// Get the path an error of the directory walker is related to, if any
+fn walk_error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::Loop { child, .. } => Some(child),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            walk_error_path(err)
+        }
+        _ => None,
+    }
+}
+
+/// Get the symbolic link and the parent directory it points to if an error of the directory walker is a loop
+fn walk_loop(err: &ignore::Error) -> Option<(&Path, &Path)> {
+    match err {
+        ignore::Error::Loop { ancestor, child } => Some((child, ancestor)),
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => walk_loop(err),
+        _ => None,
+    }
+}
+
+/// Get the warnings about the headers of a file (e.g. empty ones), indexed by the event they end at
+fn header_warnings(
+    events: &[(Event, Range<usize>, EventOrigin)],
+    slug_options: &SlugOptions,
+    options: &CheckerOptions,
+) -> HashMap<usize, Vec<String>> {
+    let mut warnings = HashMap::<usize, Vec<String>>::new();
+    let mut headers = HeadersCollector::new(slug_options);
+
+    for (index, (event, _, _)) in events.iter().enumerate() {
+        if let Some((title, _)) = headers.feed(event) {
+            if title.trim().is_empty() {
+                warnings
+                    .entry(index)
+                    .or_default()
+                    .push("heading was not directly followed by a title".to_owned());
+            }
+        }
+    }
+
+    if options.check_toc {
+        for (index, title) in check_table_of_contents(events, slug_options) {
+            warnings.entry(index).or_default().push(format!(
+                "header '{}' is missing from the table of contents",
+                title
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Get the text of the links and images of a file, indexed by the event they start at
+///
+/// The text of nested links is part of the enclosing one as well.
+fn link_texts(events: &[(Event, Range<usize>, EventOrigin)]) -> HashMap<usize, String> {
+    let mut texts = HashMap::<usize, String>::new();
+    let mut open_links = Vec::<usize>::new();
+
+    for (index, (event, _, _)) in events.iter().enumerate() {
+        match event {
+            Event::Start(Tag::Link { .. } | Tag::Image { .. }) => open_links.push(index),
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                if let Some(start) = open_links.pop() {
+                    let text = texts.remove(&start).unwrap_or_default();
+
+                    if let Some(&parent) = open_links.last() {
+                        texts.entry(parent).or_default().push_str(&text);
+                    }
+
+                    texts.insert(start, text);
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(&start) = open_links.last() {
+                    texts.entry(start).or_default().push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    texts
+}
+
+/// Find the headers which are missing from a file's Table of Contents (see [`CheckerOptions::check_toc`])
+/// Returns the index of the event each of them ends at, with its title.
+///
+/// Entries pointing to headers which don't exist are reported by the checker like any other link.
+fn check_table_of_contents(
+    events: &[(Event, Range<usize>, EventOrigin)],
+    slug_options: &SlugOptions,
+) -> Vec<(usize, String)> {
+    let mut headers_collector = HeadersCollector::new(slug_options);
+
+    // Headers as (level, title, slug) with the index of the event they end at
+    let mut headers = vec![];
+    let mut header_level = None;
+
+    // Entries of the Table of Contents and index of the event it ends at, once found
+    let mut toc = None;
+    let mut list_depth = 0;
+    let mut list_links: Option<Vec<String>> = None;
+
+    for (index, (event, _, _)) in events.iter().enumerate() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => header_level = Some(*level),
+            Event::Start(Tag::List(_)) => {
+                if list_depth == 0 && toc.is_none() {
+                    list_links = Some(vec![]);
+                }
+
+                list_depth += 1;
+            }
+            Event::End(TagEnd::List(_)) => {
+                list_depth -= 1;
+
+                if list_depth == 0 {
+                    if let Some(links) = list_links.take() {
+                        if links.len() >= 2 && links.iter().all(|link| link.starts_with('#')) {
+                            toc = Some((links, index));
+                        }
+                    }
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                if let Some(ref mut links) = list_links {
+                    links.push(dest_url.to_string());
+                }
+            }
+            _ => {}
+        }
+
+        if let Some((title, slug)) = headers_collector.feed(event) {
+            if let Some(level) = header_level.take() {
+                headers.push((level, title, slug, index));
+            }
+        }
+    }
+
+    let (entries, toc_end) = match toc {
+        Some(toc) => toc,
+        None => return vec![],
+    };
+
+    let slugs = headers
+        .iter()
+        .map(|(_, _, slug, _)| slug.clone())
+        .collect::<Vec<_>>();
+
+    let missing = check_header_coverage(&entries, &slugs)
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    // Levels of the headers listed in the Table of Contents
+    let levels = headers
+        .iter()
+        .filter(|(_, _, slug, _)| !missing.contains(slug))
+        .map(|(level, _, _, _)| *level)
+        .collect::<HashSet<_>>();
+
+    headers
+        .into_iter()
+        .filter(|(level, _, slug, index)| {
+            *index > toc_end && levels.contains(level) && missing.contains(slug)
+        })
+        .map(|(_, title, _, index)| (index, title))
+        .collect()
+}
+
+/// Check if a file is larger than the maximum size allowed by the options, returning the related warning if so
+///
+/// The parser requires the whole content of a file at once, so large files can't be parsed in chunks
+///  and are skipped instead.
+fn file_too_large(path: &Path, options: &CheckerOptions) -> Option<String> {
+    let max_file_size = options.max_file_size?;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.len() > max_file_size => Some(format!(
+            "file is {} bytes long, which is more than the limit of {} bytes, skipping it",
+            metadata.len(),
+            max_file_size
+        )),
+
+        // Unreadable files are reported when reading them
+        _ => None,
+    }
+}
+
+/// Remove the files larger than the maximum size allowed by the options, listing them
+/// Returns the remaining files, and the warnings about the skipped ones.
+fn skip_large_files(
+    files: Vec<PathBuf>,
+    options: &CheckerOptions,
+) -> (Vec<PathBuf>, Vec<DetectedBrokenLink>) {
+    let mut warnings = vec![];
+
+    let (skipped, files): (Vec<_>, Vec<_>) =
+        files
+            .into_iter()
+            .partition(|file| match file_too_large(file, options) {
+                Some(warning) => {
+                    warnings.push(file_warning(file, warning, options));
+                    true
+                }
+                None => false,
+            });
+
+    if !skipped.is_empty() {
+        info!(
+            "Skipped {} file{} because of their size: {}",
+            skipped.len(),
+            if skipped.len() > 1 { "s" } else { "" },
+            skipped
+                .iter()
+                .map(|file| safe_canonicalize(file))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    (files, warnings)
+}
+
+/// Get the severity of the warnings, which are errors if [`CheckerOptions::error_on_warnings`] is set
+fn warning_severity(options: &CheckerOptions) -> Severity {
+    if options.error_on_warnings {
+        Severity::Error
+    } else {
+        Severity::Warning
+    }
+}
+
+/// Report a warning which is not located in a file's content (e.g. about the file itself), returning it as a finding
+///
+/// If [`CheckerOptions::error_on_warnings`] is set, it's reported as an error.
+fn file_warning(path: &Path, warning: String, options: &CheckerOptions) -> DetectedBrokenLink {
+    let message = format!(
+        "In '{}': {}",
+        safe_canonicalize(path).green(),
+        highlight_quoted(&warning)
+    );
+    let severity = warning_severity(options);
+
+    if severity == Severity::Warning || options.no_errors {
+        warn!("{}", message);
+    } else {
+        error!("{}", message);
+    }
+
+    DetectedBrokenLink {
+        file: path.to_owned(),
+        line: 0,
+        cell: None,
+        kind: BrokenLinkKind::Warning,
+        severity,
+        error: warning,
+        suggestion: None,
+        target: None,
+        column: None,
+        link_text: None,
+    }
+}
+
+/// Highlight the values quoted in a message for display, headers and anchors in yellow and paths in green
+///
+/// The message itself is kept uncolored in findings, as it's also written to reports.
+fn highlight_quoted(message: &str) -> String {
+    QUOTED_VALUE_REGEX
+        .replace_all(message, |captures: &regex::Captures| {
+            let value = &captures[3];
+
+            match captures.get(2) {
+                Some(prefix) => format!("{}{}'{}'", &captures[1], prefix.as_str(), value.yellow()),
+                None => format!("{}'{}'", &captures[1], value.green()),
+            }
+        })
+        .into_owned()
+}
+
+/// Get the time at which checking files must stop, if there is a timeout (see [`CheckerOptions::timeout`])
+pub(crate) fn check_deadline(options: &CheckerOptions) -> Option<Instant> {
+    options.timeout.map(|timeout| Instant::now() + timeout)
+}
+
+/// Check if the time to check files is over
+pub(crate) fn deadline_expired(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Report the files of a directory which were left unchecked because the timeout expired
+pub(crate) fn timeout_finding(
+    dir: &Path,
+    unchecked: usize,
+    options: &CheckerOptions,
+) -> DetectedBrokenLink {
+    let error = format!(
+        "timeout of {:?} expired, {} file{} left unchecked",
+        options.timeout.unwrap_or_default(),
+        unchecked,
+        if unchecked > 1 { "s were" } else { " was" }
+    );
+
+    let message = format!("In '{}': {}", safe_canonicalize(dir).green(), error);
+
+    if options.no_errors {
+        warn!("{}", message);
+    } else {
+        error!("{}", message);
+    }
+
+    DetectedBrokenLink {
+        file: dir.to_owned(),
+        line: 0,
+        cell: None,
+        kind: BrokenLinkKind::Timeout,
+        severity: Severity::Error,
+        error,
+        suggestion: None,
+        target: None,
+        column: None,
+        link_text: None,
+    }
+}
+
+/// Report the entries which could not be read while walking a directory
+///
+/// They don't prevent the other files from being checked, unless [`CheckerOptions::halt_on_io_error`] is set
+///  in which case the first one is returned as an error.
+fn report_unreadable_paths(
+    failures: Vec<(PathBuf, String)>,
+    options: &CheckerOptions,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    if options.halt_on_io_error {
+        if let Some((path, error)) = failures.into_iter().next() {
+            return Err(format!(
+                "In '{}': {}",
+                safe_canonicalize(&path).green(),
+                error
+            ));
+        }
+
+        return Ok(vec![]);
+    }
+
+    Ok(failures
+        .into_iter()
+        .map(|(path, error)| {
+            let message = format!("In '{}': {}", safe_canonicalize(&path).green(), error);
+
+            if options.no_errors {
+                warn!("{}", message);
+            } else {
+                error!("{}", message);
+            }
+
+            DetectedBrokenLink {
+                file: path,
+                line: 0,
+                cell: None,
+                kind: BrokenLinkKind::UnreadablePath,
+                severity: Severity::Error,
+                error,
+                suggestion: None,
+                target: None,
+                column: None,
+                link_text: None,
+            }
+        })
+        .collect())
+}
+
+/// Find all Markdown files located in a directory and its subdirectories, walking them in parallel
+///
+/// Files ignored by `.gitignore` and `.ignore` files are skipped, unless [`CheckerOptions::no_ignore`] is set.
+/// Returns the files sorted by path, the entries which could not be read with the related error message,
+///  and the warnings about the skipped entries.
+/// Results and warnings don't depend on the order the entries are discovered in.
+fn walk_markdown_files(
+    dir: &Path,
+    options: &CheckerOptions,
+) -> (
+    Vec<PathBuf>,
+    Vec<(PathBuf, String)>,
+    Vec<DetectedBrokenLink>,
+) {
+    let found = Mutex::new((vec![], vec![], vec![], vec![], vec![]));
+
+    WalkBuilder::new(dir)
+        .standard_filters(!options.no_ignore)
+        .follow_links(options.follow_symlinks)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build_parallel()
+        .run(|| {
+            Box::new(|entry| {
+                match entry {
+                    Ok(entry) => match entry.file_type() {
+                        Some(file_type) if file_type.is_dir() => {
+                            if entry.path_is_symlink() {
+                                found.lock().unwrap().4.push(entry.into_path());
+                            }
+                        }
+                        Some(file_type) if file_type.is_file() => {
+                            if is_checked_file(entry.path(), options) {
+                                found.lock().unwrap().0.push(entry.into_path());
+                            }
+                        }
+                        _ => found.lock().unwrap().2.push(entry.into_path()),
+                    },
+
+                    // Symbolic links to a parent directory are skipped (the walker doesn't enter them)
+                    Err(err) if walk_loop(&err).is_some() => {
+                        let (child, ancestor) = walk_loop(&err).unwrap();
+                        found
+                            .lock()
+                            .unwrap()
+                            .3
+                            .push((child.to_owned(), ancestor.to_owned()))
+                    }
+
+                    Err(err) => found.lock().unwrap().1.push((
+                        walk_error_path(&err).unwrap_or(dir).to_owned(),
+                        format!("Failed to read directory entry: {}", err),
+                    )),
+                }
+
+                WalkState::Continue
+            })
+        });
+
+    let (mut files, mut failures, mut ignored, mut loops, mut linked_dirs) =
+        found.into_inner().unwrap();
+
+    // The order of the walk is not deterministic
+    files.sort();
+    failures.sort();
+    ignored.sort();
+    loops.sort();
+    linked_dirs.sort();
+
+    let mut warnings = vec![];
+
+    // Files reached through several paths (e.g. two symbolic links to the same directory) are only checked once,
+    //  preferably through a path without symbolic links to directories
+    let through_link = |file: &Path| linked_dirs.iter().any(|dir| file.starts_with(dir));
+
+    files.sort_by_cached_key(|file| (through_link(file), file.clone()));
+
+    let mut seen = HashSet::new();
+    let mut duplicates = vec![];
+
+    files.retain(|file| {
+        let canonical = std::fs::canonicalize(file).unwrap_or_else(|_| file.clone());
+        let first = seen.insert(canonical);
+
+        if !first {
+            duplicates.push(file.clone());
+        }
+
+        first
+    });
+
+    files.sort();
+
+    let mut skipped_dirs: Vec<&PathBuf> = vec![];
+
+    for dir in &linked_dirs {
+        if skipped_dirs.iter().any(|skipped| dir.starts_with(skipped))
+            || !duplicates.iter().any(|file| file.starts_with(dir))
+        {
+            continue;
+        }
+
+        let warning = format!(
+            "directory is a symbolic link to '{}', which is already checked through another path, skipping it",
+            std::fs::canonicalize(dir).unwrap_or_else(|_| dir.clone()).display()
+        );
+
+        warnings.push(file_warning(dir, warning, options));
+        skipped_dirs.push(dir);
+    }
+
+    for (child, ancestor) in loops {
+        let warning = format!(
+            "directory is a symbolic link to its parent directory '{}', skipping it",
+            safe_canonicalize(&ancestor)
+        );
+
+        warnings.push(file_warning(&child, warning, options));
+    }
+
+    for path in ignored {
+        let warning = match std::fs::read_link(&path) {
+            Ok(target) if !path.exists() => format!(
+                "item is a broken symbolic link to '{}', ignoring it",
+                safe_canonicalize(&target)
+            ),
+            Ok(_) => "item is a symbolic link, which is not followed, ignoring it".to_owned(),
+            Err(_) => "item is neither a file nor a directory, ignoring it".to_owned(),
+        };
+
+        warnings.push(file_warning(&path, warning, options));
+    }
+
+    (files, failures, warnings)
+}
+
+/// Check if a file found while walking a directory must be checked
+///  (Markdown files, Rust source files if asked to and notebooks if they are supported, unless a configuration file excludes them)
+fn is_checked_file(path: &Path, options: &CheckerOptions) -> bool {
+    let checked = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("md") => true,
+        Some("rs") => options.rust_doc_comments,
+        Some("ipynb") => cfg!(feature = "notebooks"),
+        _ => false,
+    };
+
+    checked && !options.directory_configs.is_excluded(path) && !options.is_target_only(path)
+}
+
+/// Check if a file is a Rust source file
+fn is_rust_source_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("rs")
+}
+
+/// Extract the Markdown embedded in a file which isn't a Markdown file, if it's one of the supported kinds
+///  (the doc comments of Rust source files if asked to, and the Markdown cells of notebooks if they are supported)
+///
+/// Fails if the file's content doesn't match its kind (e.g. a notebook which isn't valid JSON).
+pub(crate) fn embedded_markdown(
+    path: &Path,
+    content: &str,
+    rust_doc_comments: bool,
+) -> Result<Option<EmbeddedMarkdown>, String> {
+    if rust_doc_comments && is_rust_source_file(path) {
+        return Ok(Some(extract_doc_comments(content)));
+    }
+
+    #[cfg(feature = "notebooks")]
+    if path.extension().and_then(|ext| ext.to_str()) == Some("ipynb") {
+        return notebooks::extract_markdown_cells(content).map(Some);
+    }
+
+    Ok(None)
+}
+
+/// Get the content to parse, the index of its lines and the blocks to parse independently,
+///  from a file's content and the Markdown embedded in it (if it isn't a Markdown file)
+pub(crate) fn markdown_blocks<'a>(
+    content: &'a str,
+    embedded: Option<&'a EmbeddedMarkdown>,
+) -> (&'a str, LineIndex, Vec<Range<usize>>) {
+    match embedded {
+        Some(embedded) => (
+            &embedded.content,
+            LineIndex::with_source_lines(&embedded.content, embedded.source_lines.clone()),
+            embedded.blocks.clone(),
+        ),
+        None => (
+            content,
+            LineIndex::new(content),
+            std::iter::once(0..content.len()).collect(),
+        ),
+    }
+}
+
+/// Read a text file, decoding it with replacement characters if it isn't valid UTF-8
+///
+/// A leading UTF-8 byte order mark is stripped, as the parser would consider it part of the first line's content
+///  (which would prevent a header located on it from being recognized). Line numbers are not affected.
+///
+/// Files starting with a UTF-16 byte order mark are decoded as UTF-16.
+/// If the file isn't valid UTF-8, a description of the problem is returned alongside the decoded content.
+pub(crate) fn read_text_file(path: &Path) -> std::io::Result<(String, Option<String>)> {
+    Ok(decode_text(std::fs::read(path)?))
+}
+
+/// Decode the content of a text file (see [`read_text_file`])
+pub(crate) fn decode_text(mut bytes: Vec<u8>) -> (String, Option<String>) {
+    if bytes.starts_with(b"\xEF\xBB\xBF") {
+        bytes.drain(..3);
+    }
+
+    let utf16_units = |to_unit: fn([u8; 2]) -> u16| {
+        bytes[2..]
+            .chunks(2)
+            .map(|pair| to_unit([pair[0], *pair.get(1).unwrap_or(&0)]))
+            .collect::<Vec<_>>()
+    };
+
+    let (content, problem) = match bytes.get(..2) {
+        Some([0xFF, 0xFE]) => (
+            String::from_utf16_lossy(&utf16_units(u16::from_le_bytes)),
+            "is encoded in UTF-16, please convert it to UTF-8",
+        ),
+        Some([0xFE, 0xFF]) => (
+            String::from_utf16_lossy(&utf16_units(u16::from_be_bytes)),
+            "is encoded in UTF-16, please convert it to UTF-8",
+        ),
+        _ => match String::from_utf8(bytes) {
+            Ok(content) => return (content, None),
+            Err(err) => (
+                String::from_utf8_lossy(err.as_bytes()).into_owned(),
+                "is not valid UTF-8",
+            ),
+        },
+    };
+
+    (content, Some(problem.to_owned()))
+}
+
+/// Substitute the placeholders of a link's destination with their value (see [`PlaceholderOptions`])
+///
+/// Placeholder names are made of alphanumeric characters, `_`, `-` and `.`, and may be surrounded by spaces (e.g. `{{ version }}`).
+/// If some placeholders have no value, their names are returned instead.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::{substitute_placeholders, PlaceholderOptions};
+///
+/// let options = PlaceholderOptions {
+///     variables: vec![("version".to_owned(), "1.4".to_owned())].into_iter().collect(),
+///     delimiters: vec![("{{".to_owned(), "}}".to_owned()), ("${".to_owned(), "}".to_owned())],
+///     ..PlaceholderOptions::default()
+/// };
+///
+/// assert_eq!(substitute_placeholders("releases/{{ version }}/CHANGELOG.md#v${version}", &options).unwrap(), "releases/1.4/CHANGELOG.md#v1.4");
+/// assert_eq!(substitute_placeholders("{{lang}}/{{ region }}/{{version}}.md", &options), Err(vec!["lang".to_owned(), "region".to_owned()]));
+/// assert_eq!(substitute_placeholders("notes{{}}.md", &options).unwrap(), "notes{{}}.md");
+/// ```
+pub fn substitute_placeholders<'a>(
+    destination: &'a str,
+    options: &PlaceholderOptions,
+) -> Result<Cow<'a, str>, Vec<String>> {
+    let mut destination = Cow::Borrowed(destination);
+    let mut unresolved = vec![];
+
+    for (open, close) in &options.delimiters {
+        if open.is_empty() || close.is_empty() {
+            continue;
+        }
+
+        let mut substituted = String::new();
+        let mut rest = destination.as_ref();
+        let mut found = false;
+
+        while let Some(start) = rest.find(open.as_str()) {
+            let inner = &rest[start + open.len()..];
+
+            let end = match inner.find(close.as_str()) {
+                Some(end) => end,
+                None => break,
+            };
+
+            let name = inner[..end].trim();
+
+            // Text between delimiters which can't be a placeholder's name is left as-is
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            {
+                substituted.push_str(&rest[..start + open.len()]);
+                rest = inner;
+                continue;
+            }
+
+            substituted.push_str(&rest[..start]);
+
+            match options.variables.get(name) {
+                Some(value) => substituted.push_str(value),
+                None => {
+                    if !unresolved.iter().any(|unresolved| unresolved == name) {
+                        unresolved.push(name.to_owned());
+                    }
+
+                    substituted.push_str(&rest[start..start + open.len() + end + close.len()]);
+                }
+            }
+
+            rest = &inner[end + close.len()..];
+            found = true;
+        }
+
+        if found {
+            substituted.push_str(rest);
+            destination = Cow::Owned(substituted);
+        }
+    }
+
+    if unresolved.is_empty() {
+        Ok(destination)
+    } else {
+        Err(unresolved)
+    }
+}
+
+/// Get the anchor aliases declared in a Markdown file, from their old name to their new one
+///
+/// Aliases are declared with `<!-- broken-md-links:anchor-alias old-name=new-name -->` comments, e.g. when a header is renamed,
+///  so links to its old slug are still accepted as long as the new one exists (see [`CheckerOptions::anchor_aliases`]).
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::inline_anchor_aliases;
+///
+/// let aliases = inline_anchor_aliases("<!-- broken-md-links:anchor-alias setup=installation -->\n# Installation\n");
+///
+/// assert_eq!(aliases.get("setup").map(String::as_str), Some("installation"));
+/// assert_eq!(aliases.len(), 1);
+/// ```
+pub fn inline_anchor_aliases(content: &str) -> HashMap<String, String> {
+    ANCHOR_ALIAS_REGEX
+        .captures_iter(content)
+        .map(|cap| (cap[1].to_owned(), cap[2].to_owned()))
+        .collect()
+}
+
+/// Load anchor aliases (see [`CheckerOptions::anchor_aliases`]) from a TOML file mapping `path#old-anchor` to `path#new-anchor`
+///
+/// Paths are relative to the file's directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_simple, load_anchor_aliases, CheckerOptions};
+///
+/// let options = CheckerOptions {
+///     anchor_aliases: load_anchor_aliases(Path::new("aliases.toml")).unwrap(),
+///     ..CheckerOptions::default()
+/// };
+///
+/// let errors = check_broken_links_simple(Path::new("docs"), &options).unwrap();
+/// ```
+pub fn load_anchor_aliases(path: &Path) -> Result<AnchorAliases, String> {
+    let canon = safe_canonicalize(path);
+
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        format!(
+            "Failed to read anchor aliases file at '{}': {}",
+            canon.green(),
+            err
+        )
+    })?;
+
+    let entries = toml::from_str::<BTreeMap<String, String>>(&content).map_err(|err| {
+        format!(
+            "Failed to parse anchor aliases file at '{}': {}",
+            canon.green(),
+            err
+        )
+    })?;
+
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    // Split a `path#anchor` entry, with its path made canonical
+    let parse = |entry: &str| {
+        let (file, anchor) = entry.split_once('#').ok_or_else(|| {
+            format!(
+                "Invalid anchor alias '{}' in file at '{}': expected 'path#anchor'",
+                entry,
+                canon.green()
+            )
+        })?;
+
+        let file = dir.join(file);
+
+        Ok::<_, String>((file.canonicalize().unwrap_or(file), anchor.to_owned()))
+    };
+
+    entries
+        .iter()
+        .map(|(old, new)| Ok((parse(old)?, parse(new)?)))
+        .collect()
+}
+
+/// Load the value of placeholders (see [`PlaceholderOptions`]) from the `[variables]` table of a TOML file
+///
+/// Values which are not strings (e.g. `version = 1.4`) are converted to strings as they are written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::load_placeholder_variables;
+///
+/// let variables = load_placeholder_variables(Path::new("config.toml")).unwrap();
+///
+/// println!("Version: {:?}", variables.get("version"));
+/// ```
+pub fn load_placeholder_variables(path: &Path) -> Result<BTreeMap<String, String>, String> {
+    let canon = safe_canonicalize(path);
+
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        format!(
+            "Failed to read variables file at '{}': {}",
+            canon.green(),
+            err
+        )
+    })?;
+
+    let table = toml::from_str::<toml::Table>(&content).map_err(|err| {
+        format!(
+            "Failed to parse variables file at '{}': {}",
+            canon.green(),
+            err
+        )
+    })?;
+
+    let variables = match table.get("variables") {
+        Some(toml::Value::Table(variables)) => variables,
+        Some(_) => {
+            return Err(format!(
+                "In variables file at '{}': 'variables' is not a table",
+                canon.green()
+            ))
+        }
+        None => return Ok(BTreeMap::new()),
+    };
+
+    variables
+        .iter()
+        .map(|(name, value)| match value {
+            toml::Value::String(value) => Ok((name.clone(), value.clone())),
+            toml::Value::Array(_) | toml::Value::Table(_) => Err(format!(
+                "In variables file at '{}': value of variable '{}' is not a scalar",
+                canon.green(),
+                name
+            )),
+            value => Ok((name.clone(), value.to_string())),
+        })
+        .collect()
+}
+
+/// Get the value of the first of the provided keys found in a Markdown file's front matter (a YAML block at its beginning)
+///
+/// Only the fields of the top-level mapping are supported (see [`front_matter_fields`]).
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::front_matter_value;
+///
+/// let markers = ["redirect_to".to_owned()];
+///
+/// assert_eq!(front_matter_value("---\ntitle: Old\nredirect_to: \"/new/page.md\"\n---\n# Old\n", &markers), Some("/new/page.md".to_owned()));
+/// assert_eq!(front_matter_value("---\nlinks:\n  redirect_to: /new/page.md\n---\n# Old\n", &markers), None);
+/// assert_eq!(front_matter_value("# Old\n\nredirect_to: /new/page.md\n", &markers), None);
+/// ```
+pub fn front_matter_value(content: &str, keys: &[String]) -> Option<String> {
+    front_matter_fields(content)?
+        .ok()?
+        .into_iter()
+        .find(|(key, _)| keys.iter().any(|candidate| candidate == key))
+        .map(|(_, value)| value.to_owned())
+}
+
+/// Get the first of the provided markers found in a Markdown file's front matter, as `key: value`
+///
+/// Markers are front matter keys with the value marking a file as unpublished (e.g. `draft` and `true`, or `publish` and `false`),
+///  which is compared case-insensitively. Only simple `key: value` lines are supported (see [`front_matter_value`]).
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::draft_marker;
+///
+/// let markers = [("draft".to_owned(), "true".to_owned()), ("publish".to_owned(), "false".to_owned())];
+///
+/// assert_eq!(draft_marker("---\ntitle: WIP\ndraft: True\n---\n# WIP\n", &markers), Some("draft: True".to_owned()));
+/// assert_eq!(draft_marker("---\npublish: false\n---\n# WIP\n", &markers), Some("publish: false".to_owned()));
+/// assert_eq!(draft_marker("---\ndraft: false\npublish: true\n---\n# Done\n", &markers), None);
+/// ```
+pub fn draft_marker(content: &str, markers: &[(String, String)]) -> Option<String> {
+    markers.iter().find_map(|(key, unpublished)| {
+        front_matter_value(content, std::slice::from_ref(key))
+            .filter(|value| value.eq_ignore_ascii_case(unpublished))
+            .map(|value| format!("{}: {}", key, value))
+    })
+}
+
+/// Get the fields of a Markdown file's front matter (a YAML block at its beginning), as keys with their value
+///
+/// Only the top-level mapping is parsed, without enforcing the type of values: the value of a field is the rest of its line
+///  without quotes, and nested lines (e.g. the items of a list or the fields of a nested mapping) are skipped.
+/// Returns `None` if the file has no (closed) front matter, and an error if the front matter is not a mapping.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::front_matter_fields;
+///
+/// let content = "---\ntitle: 'Setup'\ntags:\n- install\n- linux\nauthor:\n  name: Jane\n---\n# Setup\n";
+///
+/// assert_eq!(front_matter_fields(content), Some(Ok(vec![("title", "Setup"), ("tags", ""), ("author", "")])));
+/// assert_eq!(front_matter_fields("---\n---\n# Setup\n"), Some(Ok(vec![])));
+/// assert_eq!(front_matter_fields("---\ntitle: Setup\n# Setup\n"), None);
+/// assert!(front_matter_fields("---\n- title\n---\n").unwrap().is_err());
+/// ```
+pub fn front_matter_fields(content: &str) -> Option<Result<Vec<(&str, &str)>, String>> {
+    let mut lines = content.lines();
+
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+
+    let mut fields: Vec<(&str, &str)> = vec![];
+    let mut invalid = None;
+
+    for line in lines {
+        let trimmed = line.trim();
+
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some(match invalid {
+                Some(err) => Err(err),
+                None => Ok(fields),
+            });
+        }
+
+        // Blank lines, comments and nested lines
+        if trimmed.is_empty() || trimmed.starts_with('#') || line.starts_with([' ', '\t']) {
+            continue;
+        }
+
+        // Items of a list can be at the same indentation as the field they are the value of
+        if trimmed == "-" || trimmed.starts_with("- ") {
+            if !matches!(fields.last(), Some((_, ""))) {
+                invalid.get_or_insert_with(|| "front matter is not a mapping".to_owned());
+            }
+
+            continue;
+        }
+
+        let (key, value) = match line.split_once(':') {
+            Some(field) => field,
+            None => {
+                invalid.get_or_insert_with(|| {
+                    format!(
+                        "front matter line '{}' is not a 'key: value' field",
+                        trimmed
+                    )
+                });
+
+                continue;
+            }
+        };
+
+        fields.push((unquote(key.trim()), unquote(value.trim())));
+    }
+
+    // The front matter is not closed
+    None
+}
+
+/// Remove the quotes around a YAML scalar, if any
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .or_else(|| {
+            value
+                .strip_prefix('\'')
+                .and_then(|value| value.strip_suffix('\''))
+        })
+        .unwrap_or(value)
+}
+
+/// Get the required fields missing from a Markdown file's front matter (see [`CheckerOptions::frontmatter_required_fields`])
+///
+/// Only the presence of the fields is checked, whatever their value is. Files without a front matter miss all the required fields.
+/// Returns an error if the front matter is not a mapping (see [`front_matter_fields`]).
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::validate_frontmatter_schema;
+///
+/// let required = ["title".to_owned(), "date".to_owned()];
+///
+/// assert_eq!(validate_frontmatter_schema("---\ntitle: Setup\ndate: 2021-04-01\n---\n# Setup\n", &required), Ok(vec![]));
+/// assert_eq!(validate_frontmatter_schema("---\ntitle: [Setup, Usage]\n---\n", &required), Ok(vec!["date".to_owned()]));
+/// assert_eq!(validate_frontmatter_schema("# Setup\n", &required), Ok(vec!["title".to_owned(), "date".to_owned()]));
+/// assert!(validate_frontmatter_schema("---\n- title\n---\n", &required).is_err());
+/// ```
+pub fn validate_frontmatter_schema(
+    content: &str,
+    required: &[String],
+) -> Result<Vec<String>, String> {
+    let fields = match front_matter_fields(content) {
+        None => return Ok(required.to_vec()),
+        Some(fields) => fields?,
+    };
+
+    Ok(required
+        .iter()
+        .filter(|field| !fields.iter().any(|(key, _)| key == field))
+        .cloned()
+        .collect())
+}
+
+/// Get the (canonicalized) existing Markdown files a Markdown file links to, without reporting anything
+fn linked_markdown_files(
+    path: &Path,
+    options: &CheckerOptions,
+) -> Result<Vec<PathBuf>, CheckerError> {
+    let (content, _) = read_text_file(path).map_err(|err| CheckerError::Unreadable {
+        path: path.to_owned(),
+        error: err.to_string(),
+    })?;
+
+    let embedded =
+        embedded_markdown(path, &content, options.rust_doc_comments).map_err(|error| {
+            CheckerError::Invalid {
+                path: path.to_owned(),
+                error,
+            }
+        })?;
+
+    let (content, _, blocks) = markdown_blocks(&content, embedded.as_ref());
+
+    let mut targets = vec![];
+
+    for (event, _, origin) in markdown_events(content, &blocks, options).events {
+        // Links whose destination contains spaces are only links for the checker if they are accepted
+        if origin == EventOrigin::SpacedDestination && !options.lenient_space_destinations {
+            continue;
+        }
+
+        if let Event::Start(Tag::Link {
+            link_type:
+                LinkType::Inline | LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut,
+            dest_url,
+            ..
+        }) = event
+        {
+            // Links with unresolved placeholders can't be followed
+            let dest_url = match substitute_placeholders(&dest_url, &options.placeholders) {
+                Ok(dest_url) => dest_url,
+                Err(_) => continue,
+            };
+
+            let target = dest_url.split('#').next().unwrap();
+
+            if target.is_empty()
+                || WINDOWS_ABSOLUTE_PATH_REGEX.is_match(target)
+                || URL_SCHEME_REGEX.is_match(target)
+                || EMAIL_REGEX.is_match(target)
+            {
+                continue;
+            }
+
+            if let Ok(target) = path.parent().unwrap().join(target).canonicalize() {
+                if target.is_file() && target.extension().and_then(|ext| ext.to_str()) == Some("md")
+                {
+                    targets.push(target);
+                }
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+/// Find the Markdown files of a directory which are not reachable from any of the provided entry points by following links
+///
+/// These files are not broken, but nobody can navigate to them, which may indicate forgotten documentation.
+/// Entry points are always considered reachable. Links are followed even when they point outside of the `root` directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::{Path, PathBuf};
+/// use broken_md_links::{find_unreachable_files, CheckerOptions};
+///
+/// let entry_points = [PathBuf::from("docs/README.md")];
+///
+/// for file in find_unreachable_files(Path::new("docs"), &entry_points, &CheckerOptions::default()).unwrap() {
+///     println!("{} can't be reached from the entry points", file.display());
+/// }
+/// ```
+pub fn find_unreachable_files(
+    root: &Path,
+    entry_points: &[PathBuf],
+    options: &CheckerOptions,
+) -> Result<Vec<PathBuf>, CheckerError> {
+    let (files, failures, _) = walk_markdown_files(root, options);
+
+    if let Some((path, error)) = failures.into_iter().next() {
+        return Err(CheckerError::Unreadable { path, error });
+    }
+
+    let mut unreachable = files
+        .iter()
+        .map(|file| {
+            file.canonicalize().map_err(|err| CheckerError::Unreadable {
+                path: file.clone(),
+                error: err.to_string(),
+            })
+        })
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    // Files remaining to visit
+    let mut queue = entry_points
+        .iter()
+        .map(|entry_point| {
+            entry_point
+                .canonicalize()
+                .map_err(|error| CheckerError::MissingEntryPoint {
+                    path: entry_point.clone(),
+                    error,
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut visited = HashSet::new();
+
+    while let Some(file) = queue.pop() {
+        if !visited.insert(file.clone()) {
+            continue;
+        }
+
+        debug!("Following links of file: {}", safe_canonicalize(&file));
+
+        unreachable.remove(&file);
+        queue.extend(linked_markdown_files(&file, options)?);
+    }
+
+    let mut unreachable = unreachable.into_iter().collect::<Vec<_>>();
+    unreachable.sort();
+
+    Ok(unreachable)
 }
@@ -0,0 +1,83 @@
+/// Index of the lines of a file's content, used to get the line and column an offset is located at
+///
+/// The index is built once per file, then each lookup is a binary search on the lines' start offsets.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::LineIndex;
+///
+/// let content = "# Title\n\nSome [link](file.md)\n";
+/// let index = LineIndex::new(content);
+///
+/// assert_eq!(index.line(0), 1);
+/// assert_eq!(index.line(content.find("[link]").unwrap()), 3);
+/// assert_eq!(index.line_col(content, content.find("[link]").unwrap()), (3, 6));
+///
+/// // Offsets are counted in bytes, columns in characters
+/// let content = "# Élève\n\n日本語 [link](file.md)\n";
+/// let index = LineIndex::new(content);
+///
+/// assert_eq!(index.line_col(content, content.find("[link]").unwrap()), (3, 5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// Byte offset of the beginning of each line
+    line_starts: Vec<usize>,
+
+    /// Line of the original file each line comes from, if the content was extracted from another file
+    source_lines: Option<Vec<usize>>,
+}
+
+impl LineIndex {
+    /// Build the index of a file's content
+    pub fn new(content: &str) -> Self {
+        Self {
+            line_starts: std::iter::once(0)
+                .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+                .collect(),
+            source_lines: None,
+        }
+    }
+
+    /// Build the index of a content extracted from another file, with the line of this file each line comes from
+    pub(crate) fn with_source_lines(content: &str, source_lines: Vec<usize>) -> Self {
+        Self {
+            source_lines: Some(source_lines),
+            ..Self::new(content)
+        }
+    }
+
+    /// Get the line (starting from 1) a byte offset is located at
+    pub fn line(&self, offset: usize) -> usize {
+        let position = self.position(offset);
+
+        match self.source_lines {
+            Some(ref source_lines) => source_lines
+                .get(position)
+                .or(source_lines.last())
+                .copied()
+                .unwrap_or(1),
+            None => position + 1,
+        }
+    }
+
+    /// Get the index of the line a byte offset is located at in the content
+    fn position(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        }
+    }
+
+    /// Get the line and column (both starting from 1) a byte offset is located at
+    /// The column is counted in characters, not in bytes. The provided content must be the indexed one.
+    pub fn line_col(&self, content: &str, offset: usize) -> (usize, usize) {
+        let line_start = self.line_starts[self.position(offset)];
+
+        (
+            self.line(offset),
+            content[line_start..offset].chars().count() + 1,
+        )
+    }
+}
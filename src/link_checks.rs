@@ -0,0 +1,456 @@
+//! Checks of a single link of a file, run by the checker for each link it finds
+//!
+//! Each check returns the problem it found, if any, which the checker then reports at the link's location.
+
+use crate::validate::{
+    anchor_near_miss, missing_target_reason, validate_resolved_fragment_link, AnchorNearMiss,
+    ValidationResult,
+};
+use crate::{
+    extension_case_mismatch, non_shortest_relative_target, safe_canonicalize,
+    suggest_for_machine_specific_path, warning_severity, BrokenLinkKind, CheckerOptions,
+    FileLinksCache, Fragment, ResolvedTarget, Severity, TargetsCache, ISSUE_LINK_REGEX,
+    WINDOWS_ABSOLUTE_PATH_REGEX,
+};
+use std::path::Path;
+
+/// Problem found by one of the checks of a link
+pub(crate) struct LinkFinding {
+    /// Kind of problem
+    pub(crate) kind: BrokenLinkKind,
+
+    /// Severity of the problem
+    pub(crate) severity: Severity,
+
+    /// Description of the problem
+    pub(crate) error: String,
+
+    /// Suggested replacement for the link's target, if any
+    pub(crate) suggestion: Option<String>,
+}
+
+impl LinkFinding {
+    /// Create an error of the provided kind
+    pub(crate) fn error(kind: BrokenLinkKind, error: String) -> Self {
+        Self {
+            kind,
+            severity: Severity::Error,
+            error,
+            suggestion: None,
+        }
+    }
+
+    /// Create a warning, which is an error if warnings are treated as errors
+    pub(crate) fn warning(error: String, options: &CheckerOptions) -> Self {
+        Self::with_severity(BrokenLinkKind::Warning, Severity::Warning, error, options)
+    }
+
+    /// Create a finding of the provided kind and severity, which is an error if it's a warning and warnings are treated as errors
+    pub(crate) fn with_severity(
+        kind: BrokenLinkKind,
+        severity: Severity,
+        error: String,
+        options: &CheckerOptions,
+    ) -> Self {
+        let severity = match severity {
+            Severity::Warning => warning_severity(options),
+            severity => severity,
+        };
+
+        Self {
+            kind,
+            severity,
+            error,
+            suggestion: None,
+        }
+    }
+
+    /// Set the suggested replacement for the link's target
+    pub(crate) fn suggest(self, suggestion: Option<String>) -> Self {
+        Self { suggestion, ..self }
+    }
+}
+
+/// Report the placeholders of a link's destination which have no value (see [`substitute_placeholders`](crate::substitute_placeholders))
+pub(crate) fn unresolved_placeholders(
+    names: &[String],
+    destination: &str,
+    options: &CheckerOptions,
+) -> LinkFinding {
+    let error = format!(
+        "unresolved placeholder{} {} in link '{}'",
+        if names.len() > 1 { "s" } else { "" },
+        names
+            .iter()
+            .map(|name| format!("'{}'", name))
+            .collect::<Vec<_>>()
+            .join(", "),
+        destination
+    );
+
+    LinkFinding::with_severity(
+        BrokenLinkKind::UnresolvedPlaceholder,
+        options.placeholders.unresolved_severity,
+        error,
+        options,
+    )
+}
+
+/// Check that a local image can be decoded (see [`CheckerOptions::check_image_dimensions`])
+#[cfg(feature = "image-check")]
+pub(crate) fn check_image_content(
+    path: &Path,
+    destination: &str,
+    options: &CheckerOptions,
+) -> Option<LinkFinding> {
+    let target = destination.split('#').next().unwrap();
+
+    if target.is_empty()
+        || crate::URL_SCHEME_REGEX.is_match(target)
+        || WINDOWS_ABSOLUTE_PATH_REGEX.is_match(target)
+    {
+        return None;
+    }
+
+    let target = path.parent().unwrap().join(target);
+
+    if !target.is_file() {
+        return None;
+    }
+
+    match crate::validate::validate_image_content(&target) {
+        ValidationResult::Warning(warning) => Some(LinkFinding::warning(warning, options)),
+        _ => None,
+    }
+}
+
+/// Check that the text of a link isn't its destination (see [`CheckerOptions::link_text_is_url`]),
+///  as autolinks are the way to display a bare destination
+pub(crate) fn check_link_text(
+    text: &str,
+    destination: &str,
+    severity: Severity,
+    options: &CheckerOptions,
+) -> Option<LinkFinding> {
+    let text = text.trim();
+
+    if text.is_empty() || text.trim_end_matches('/') != destination.trim().trim_end_matches('/') {
+        return None;
+    }
+
+    Some(LinkFinding::with_severity(
+        BrokenLinkKind::LinkTextIsUrl,
+        severity,
+        format!(
+            "link text '{}' is the link's destination, it should describe the target instead",
+            text
+        ),
+        options,
+    ))
+}
+
+/// Check that a link named after an issue number in a changelog (e.g. `[#42]`) references an existing local issue
+///  (see [`CheckerOptions::changelog_mode`])
+pub(crate) fn check_changelog_issue(link: &str, issues_dir: &Path) -> Option<LinkFinding> {
+    let issue = ISSUE_LINK_REGEX.captures(link)?;
+    let issue_file = issues_dir.join(format!("{}.md", &issue[1]));
+
+    if issue_file.is_file() {
+        return None;
+    }
+
+    Some(LinkFinding::error(
+        BrokenLinkKind::MissingIssue,
+        format!(
+            "issue #{} not found: path '{}' does not exist",
+            &issue[1],
+            safe_canonicalize(&issue_file)
+        ),
+    ))
+}
+
+/// Check that a link's target isn't an absolute Windows path, which only works on a single machine
+///
+/// UNC paths start with a single backslash here, as the Markdown parser unescapes the leading double one.
+pub(crate) fn check_machine_specific_path(path: &Path, target: &str) -> Option<LinkFinding> {
+    if !WINDOWS_ABSOLUTE_PATH_REGEX.is_match(target) {
+        return None;
+    }
+
+    let suggestion = suggest_for_machine_specific_path(path, target);
+
+    let error = format!(
+        "machine-specific link found: '{}' is an absolute Windows path{}",
+        target,
+        match suggestion {
+            Some(ref suggestion) => format!(", use a relative path like '{}' instead", suggestion),
+            None => String::new(),
+        }
+    );
+
+    Some(LinkFinding::error(BrokenLinkKind::MachineSpecificPath, error).suggest(suggestion))
+}
+
+/// Warn about suspiciously long link targets, which may be paste errors (see [`CheckerOptions::max_link_length`])
+pub(crate) fn check_link_length(
+    written_target: &str,
+    options: &CheckerOptions,
+) -> Option<LinkFinding> {
+    let max_link_length = options.max_link_length?;
+    let length = written_target.chars().count();
+
+    if length <= max_link_length {
+        return None;
+    }
+
+    Some(LinkFinding::warning(
+        format!(
+            "link target is {} characters long (maximum is {}), this may be a paste error: {}",
+            length, max_link_length, written_target
+        ),
+        options,
+    ))
+}
+
+/// Check that a link's target has an extension with the same casing as on disk (see [`CheckerOptions::check_extension_case`])
+pub(crate) fn check_extension_case(
+    target: &Path,
+    written_target: &str,
+    fragment: Option<&Fragment>,
+) -> Option<LinkFinding> {
+    let on_disk = extension_case_mismatch(target)?;
+    let name = target.file_name().unwrap().to_string_lossy();
+
+    // Replace the file's name in the written target
+    let suggestion = written_target
+        .strip_suffix(name.as_ref())
+        .map(|parent| match fragment {
+            Some(fragment) => format!("{}{}#{}", parent, on_disk, fragment),
+            None => format!("{}{}", parent, on_disk),
+        });
+
+    let error = format!(
+        "extension case mismatch: '{}' is named '{}' on disk",
+        safe_canonicalize(target),
+        on_disk
+    );
+
+    Some(LinkFinding::error(BrokenLinkKind::ExtensionCase, error).suggest(suggestion))
+}
+
+/// Warn about links leading to a redirect (see [`CheckerOptions::redirect_markers`])
+pub(crate) fn check_redirect(
+    written_target: &str,
+    resolved: &ResolvedTarget,
+    options: &CheckerOptions,
+    targets_cache: &mut TargetsCache,
+) -> Option<LinkFinding> {
+    if !resolved.is_file || options.redirect_markers.is_empty() {
+        return None;
+    }
+
+    let redirect = targets_cache.redirect_target(&resolved.canonical, &options.redirect_markers)?;
+
+    Some(LinkFinding::warning(
+        format!(
+            "link to '{}' leads to a redirect to '{}', consider linking to it directly",
+            written_target, redirect
+        ),
+        options,
+    ))
+}
+
+/// Report links to files which won't be published (see [`CheckerOptions::draft_markers`])
+pub(crate) fn check_draft(
+    written_target: &str,
+    resolved: &ResolvedTarget,
+    options: &CheckerOptions,
+    targets_cache: &mut TargetsCache,
+) -> Option<LinkFinding> {
+    if !resolved.is_file || options.draft_markers.is_empty() {
+        return None;
+    }
+
+    let marker = targets_cache.draft_marker(&resolved.canonical, &options.draft_markers)?;
+
+    Some(LinkFinding::with_severity(
+        BrokenLinkKind::LinksToDraft,
+        Severity::Warning,
+        format!(
+            "link to '{}' leads to a draft which won't be published ('{}' in its front matter)",
+            written_target, marker
+        ),
+        options,
+    ))
+}
+
+/// Check that a link's target is written in its shortest relative form (see [`CheckerOptions::check_path_style`])
+pub(crate) fn check_path_style(
+    path: &Path,
+    written_target: &str,
+    fragment: Option<&Fragment>,
+    options: &CheckerOptions,
+) -> Option<LinkFinding> {
+    let shortest = non_shortest_relative_target(path, written_target)?;
+
+    let suggestion = match fragment {
+        Some(fragment) => format!("{}#{}", shortest, fragment),
+        None => shortest,
+    };
+
+    let error = format!(
+        "non-canonical link found: '{}' should be written '{}'",
+        written_target, suggestion
+    );
+
+    Some(
+        LinkFinding::with_severity(BrokenLinkKind::PathStyle, Severity::Warning, error, options)
+            .suggest(Some(suggestion)),
+    )
+}
+
+/// Validate the fragment of a link against the anchors of its target
+///
+/// The anchors of files which are only link targets (see [`CheckerOptions::targets_only`]) are counted in the cache's statistics
+///  when they are generated.
+pub(crate) fn validate_target_fragment(
+    target: &Path,
+    resolved: &ResolvedTarget,
+    fragment: &Fragment,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> ValidationResult {
+    let cached = links_cache.contains(&resolved.canonical);
+
+    let result = validate_resolved_fragment_link(
+        target,
+        resolved,
+        fragment,
+        &options.slugs_for(target),
+        links_cache,
+        targets_cache,
+    );
+
+    if !cached
+        && links_cache.contains(&resolved.canonical)
+        && options.is_target_only(&resolved.canonical)
+    {
+        links_cache.record_target_only_generation();
+    }
+
+    result
+}
+
+/// Validate the alias of a link's anchor if it wasn't found in the target (see [`CheckerOptions::anchor_aliases`])
+///
+/// The provided result is returned as-is if it isn't broken or if the anchor has no alias.
+pub(crate) fn validate_anchor_alias(
+    result: ValidationResult,
+    resolved: &ResolvedTarget,
+    fragment: &Fragment,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> ValidationResult {
+    let (error, anchor) = match (result, fragment) {
+        (ValidationResult::Broken(error), Fragment::Slug(anchor) | Fragment::HtmlId(anchor)) => {
+            (error, anchor)
+        }
+        (result, _) => return result,
+    };
+
+    let key = (resolved.canonical.clone(), anchor.clone());
+
+    let (alias_target, alias) = match options.anchor_aliases.get(&key) {
+        Some(alias) => alias.clone(),
+        None => match targets_cache.inline_anchor_alias(&resolved.canonical, anchor) {
+            Some(alias) => (resolved.canonical.clone(), alias),
+            None => return ValidationResult::Broken(error),
+        },
+    };
+
+    let alias_result = match targets_cache.resolve(&alias_target) {
+        Some(alias_resolved) => validate_resolved_fragment_link(
+            &alias_target,
+            &alias_resolved,
+            &Fragment::parse(&alias),
+            &options.slugs_for(&alias_target),
+            links_cache,
+            targets_cache,
+        ),
+        None => ValidationResult::Broken(missing_target_reason(&alias_target)),
+    };
+
+    match alias_result {
+        ValidationResult::Valid if options.warn_anchor_aliases => {
+            ValidationResult::Warning(format!(
+                "anchor '{}' of '{}' resolves via alias '{}', consider updating the link",
+                anchor,
+                safe_canonicalize(&resolved.canonical),
+                alias
+            ))
+        }
+        ValidationResult::Valid => ValidationResult::Valid,
+        _ => ValidationResult::Broken(format!(
+            "{} (its alias '{}' of '{}' doesn't exist either)",
+            error,
+            alias,
+            safe_canonicalize(&alias_target)
+        )),
+    }
+}
+
+/// Report a link whose fragment wasn't found in its target
+///
+/// If the target has an anchor the link is very likely meant to point to (see [`anchor_near_miss`]), the link is reported
+///  as a near miss with the severity configured for it, and the anchor is suggested.
+pub(crate) fn broken_fragment(
+    error: String,
+    fragment: &Fragment,
+    written_target: &str,
+    resolved: &ResolvedTarget,
+    options: &CheckerOptions,
+    links_cache: &FileLinksCache,
+) -> LinkFinding {
+    let near_miss = match fragment {
+        Fragment::Slug(anchor) | Fragment::HtmlId(anchor) => {
+            links_cache.get(&resolved.canonical).and_then(|anchors| {
+                anchor_near_miss(anchor, &anchors)
+                    .map(|(near_miss, anchor)| (near_miss, anchor.to_owned()))
+            })
+        }
+        _ => None,
+    };
+
+    let (near_miss, anchor) = match (near_miss, fragment) {
+        (Some(near_miss), _) => near_miss,
+        (None, Fragment::Slug(_)) => {
+            return LinkFinding::error(BrokenLinkKind::MissingHeader, error)
+        }
+        (None, _) => return LinkFinding::error(BrokenLinkKind::MissingAnchor, error),
+    };
+
+    let (kind, severity, difference) = match near_miss {
+        AnchorNearMiss::Case => (
+            BrokenLinkKind::AnchorCase,
+            options.anchor_case_severity,
+            "only differs by its casing",
+        ),
+        AnchorNearMiss::Punctuation => (
+            BrokenLinkKind::AnchorPunctuation,
+            options.anchor_punctuation_severity,
+            "only differs by its dashes",
+        ),
+        AnchorNearMiss::Plural => (
+            BrokenLinkKind::AnchorPlural,
+            options.anchor_plural_severity,
+            "is its plural or singular form",
+        ),
+    };
+
+    let error = format!("{}, but anchor '{}' {}", error, anchor, difference);
+
+    LinkFinding::with_severity(kind, severity, error, options)
+        .suggest(Some(format!("{}#{}", written_target, anchor)))
+}
@@ -0,0 +1,319 @@
+//! Analysis of the graph of links between the Markdown files of a directory
+//!
+//! The graph is collected while links are checked (see [`TargetsCache::link_graph`](crate::TargetsCache::link_graph)),
+//!  so analyzing it doesn't require to parse the files again.
+
+use crate::{safe_canonicalize, BrokenLinkKind, DetectedBrokenLink, Severity};
+use colored::Colorize;
+use log::{error, info, warn};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Markdown files linked to by each checked file, by canonical path
+pub type LinkGraph = BTreeMap<PathBuf, BTreeSet<PathBuf>>;
+
+/// Minimum number of files of the dead-end chains which are reported
+static DEAD_END_CHAIN_MIN_LENGTH: usize = 3;
+
+/// Analyze the graph of links between the files of a directory, reporting:
+///
+/// * Clusters of files (strongly-connected components) which are not reachable from any entry point ([`BrokenLinkKind::UnreachableCluster`])
+/// * Circular references between reachable files, which make "previous/next" navigation confusing ([`BrokenLinkKind::LinkCycle`]).
+///   Links to entry points are not considered, as pages commonly link back to them.
+/// * Chains of files which only link to the next one, ending with a file which doesn't link to any other file ([`BrokenLinkKind::DeadEndChain`])
+///
+/// Only the files of the graph are considered, links to other files are ignored. Entry points which are not part of it are ignored as well.
+/// Paths are displayed relatively to the provided root in messages. All findings get the provided severity.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_with, CheckerOptions, FileLinksCache, Severity, TargetsCache};
+/// use broken_md_links::link_graph::analyze_link_graph;
+///
+/// let mut targets_cache = TargetsCache::new();
+/// check_broken_links_with(Path::new("docs"), true, &CheckerOptions::default(), &mut FileLinksCache::new(), &mut targets_cache).unwrap();
+///
+/// let root = Path::new("docs").canonicalize().unwrap();
+///
+/// for finding in analyze_link_graph(targets_cache.link_graph(), &root, &[root.join("README.md")], Severity::Warning) {
+///     println!("{}", finding.error);
+/// }
+/// ```
+pub fn analyze_link_graph(
+    graph: &LinkGraph,
+    root: &Path,
+    entry_points: &[PathBuf],
+    severity: Severity,
+) -> Vec<DetectedBrokenLink> {
+    let display = |file: &Path| match file.strip_prefix(root) {
+        Ok(relative) => relative.to_string_lossy().into_owned(),
+        Err(_) => safe_canonicalize(file),
+    };
+
+    let display_path = |files: &[&PathBuf]| {
+        files
+            .iter()
+            .map(|file| display(file))
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    };
+
+    let entry_points = entry_points
+        .iter()
+        .filter(|entry_point| graph.contains_key(*entry_point))
+        .collect::<HashSet<_>>();
+
+    // Files reachable from the entry points
+    let mut reachable = HashSet::new();
+    let mut queue = entry_points.iter().copied().collect::<VecDeque<_>>();
+
+    while let Some(file) = queue.pop_front() {
+        if reachable.insert(file) {
+            queue.extend(graph_links(graph, file));
+        }
+    }
+
+    let mut findings = vec![];
+
+    let mut report = |file: &PathBuf, kind: BrokenLinkKind, message: String| {
+        let displayed = format!("In '{}': {}", safe_canonicalize(file).green(), message);
+
+        match severity {
+            Severity::Error => error!("{}", displayed),
+            Severity::Warning => warn!("{}", displayed),
+            Severity::Info => info!("{}", displayed),
+        }
+
+        findings.push(DetectedBrokenLink {
+            file: file.clone(),
+            line: 0,
+            cell: None,
+            kind,
+            severity,
+            error: message,
+            suggestion: None,
+            target: None,
+            column: None,
+            link_text: None,
+        });
+    };
+
+    // Unreachable clusters
+    let unreachable = graph
+        .keys()
+        .filter(|file| !reachable.contains(file))
+        .collect::<Vec<_>>();
+
+    for cluster in strongly_connected_components(&unreachable, |file| {
+        graph_links(graph, file).filter(|target| !reachable.contains(target))
+    }) {
+        let message = match cluster.len() {
+            1 => "file unreachable from the entry points".to_owned(),
+            count => format!(
+                "cluster of {} files unreachable from the entry points: {}",
+                count,
+                cluster
+                    .iter()
+                    .map(|file| display(file))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        report(cluster[0], BrokenLinkKind::UnreachableCluster, message);
+    }
+
+    // Circular references between reachable files, without the entry points
+    let navigable = graph
+        .keys()
+        .filter(|file| reachable.contains(file) && !entry_points.contains(file))
+        .collect::<Vec<_>>();
+
+    for component in strongly_connected_components(&navigable, |file| {
+        graph_links(graph, file)
+            .filter(|target| reachable.contains(target) && !entry_points.contains(target))
+    }) {
+        if component.len() < 2 {
+            continue;
+        }
+
+        let component_files = component.iter().copied().collect::<HashSet<_>>();
+
+        // Find the shortest cycle going through the component's first file
+        let start = component[0];
+        let mut previous = HashMap::<&PathBuf, &PathBuf>::new();
+        let mut queue = VecDeque::from(vec![start]);
+        let mut cycle_end = None;
+
+        'search: while let Some(file) = queue.pop_front() {
+            for target in graph_links(graph, file).filter(|target| component_files.contains(target))
+            {
+                if target == start {
+                    cycle_end = Some(file);
+                    break 'search;
+                }
+
+                if !previous.contains_key(target) {
+                    previous.insert(target, file);
+                    queue.push_back(target);
+                }
+            }
+        }
+
+        let mut cycle = vec![start];
+        let mut file = cycle_end.unwrap();
+
+        while file != start {
+            cycle.push(file);
+            file = previous[file];
+        }
+
+        cycle.push(start);
+        cycle.reverse();
+
+        report(
+            start,
+            BrokenLinkKind::LinkCycle,
+            format!("circular references: {}", display_path(&cycle)),
+        );
+    }
+
+    // Dead-end chains among the reachable files
+    let mut linked_from = HashMap::<&PathBuf, Vec<&PathBuf>>::new();
+
+    for file in graph.keys().filter(|file| reachable.contains(file)) {
+        for target in graph_links(graph, file) {
+            linked_from.entry(target).or_default().push(file);
+        }
+    }
+
+    for dead_end in graph
+        .keys()
+        .filter(|file| reachable.contains(file) && graph_links(graph, file).next().is_none())
+    {
+        let mut chain = vec![dead_end];
+
+        // Go back while the file is only linked to by a file which only links to it
+        while let Some([previous]) = linked_from.get(chain.last().unwrap()).map(Vec::as_slice) {
+            if graph_links(graph, previous).count() != 1
+                || entry_points.contains(previous)
+                || chain.contains(previous)
+            {
+                break;
+            }
+
+            chain.push(previous);
+        }
+
+        if chain.len() >= DEAD_END_CHAIN_MIN_LENGTH {
+            chain.reverse();
+
+            report(
+                chain[0],
+                BrokenLinkKind::DeadEndChain,
+                format!(
+                    "dead-end chain: {}, which doesn't link to any other file",
+                    display_path(&chain)
+                ),
+            );
+        }
+    }
+
+    findings
+}
+
+/// Get the files a file links to, among the files of the graph and without the file itself
+fn graph_links<'a>(graph: &'a LinkGraph, file: &'a PathBuf) -> impl Iterator<Item = &'a PathBuf> {
+    graph[file]
+        .iter()
+        .filter(move |target| *target != file && graph.contains_key(*target))
+}
+
+/// Get the strongly-connected components of a graph (with Kosaraju's algorithm), each one sorted, in the order of their first file
+fn strongly_connected_components<'a, I: Iterator<Item = &'a PathBuf>>(
+    files: &[&'a PathBuf],
+    links: impl Fn(&'a PathBuf) -> I,
+) -> Vec<Vec<&'a PathBuf>> {
+    let indexes = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| (*file, index))
+        .collect::<HashMap<_, _>>();
+
+    let successors = files
+        .iter()
+        .map(|file| {
+            links(file)
+                .filter_map(|target| indexes.get(target).copied())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut predecessors = vec![vec![]; files.len()];
+
+    for (index, targets) in successors.iter().enumerate() {
+        for target in targets {
+            predecessors[*target].push(index);
+        }
+    }
+
+    // Order the files by the time their exploration ended (iteratively, to not overflow the stack on long chains)
+    let mut visited = vec![false; files.len()];
+    let mut order = Vec::with_capacity(files.len());
+
+    for start in 0..files.len() {
+        if visited[start] {
+            continue;
+        }
+
+        visited[start] = true;
+        let mut stack = vec![(start, 0)];
+
+        while let Some((index, next)) = stack.pop() {
+            match successors[index].get(next) {
+                Some(&target) => {
+                    stack.push((index, next + 1));
+
+                    if !visited[target] {
+                        visited[target] = true;
+                        stack.push((target, 0));
+                    }
+                }
+                None => order.push(index),
+            }
+        }
+    }
+
+    // Collect the components by exploring the reversed graph in the reverse order
+    let mut component_of = vec![None; files.len()];
+    let mut components = vec![];
+
+    for start in order.into_iter().rev() {
+        if component_of[start].is_some() {
+            continue;
+        }
+
+        let mut component = vec![];
+        let mut stack = vec![start];
+        component_of[start] = Some(components.len());
+
+        while let Some(index) = stack.pop() {
+            component.push(files[index]);
+
+            for &source in &predecessors[index] {
+                if component_of[source].is_none() {
+                    component_of[source] = Some(components.len());
+                    stack.push(source);
+                }
+            }
+        }
+
+        component.sort();
+        components.push(component);
+    }
+
+    components.sort();
+    components
+}
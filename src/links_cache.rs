@@ -0,0 +1,280 @@
+//! Anchors cache (see [`FileLinksCache`]), with its pre-warming and statistics
+
+#[cfg(feature = "content-hash")]
+use crate::content_hash::{content_hash, ContentAnchors};
+use crate::{generate_shared_anchors, walk_markdown_files, CheckerOptions};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "content-hash")]
+use std::sync::{Mutex, MutexGuard};
+
+/// Cache of the anchors of each Markdown file (see [`generate_anchors_with`](crate::generate_anchors_with)), indexed by canonical path
+///
+/// Clones of a cache share its entries and statistics, so a single cache can be filled by several threads at once.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::{Path, PathBuf};
+/// use broken_md_links::FileLinksCache;
+///
+/// let cache = FileLinksCache::new();
+/// let shared = cache.clone();
+///
+/// shared.insert(PathBuf::from("/docs/README.md"), vec!["docs".to_owned()]);
+///
+/// assert_eq!(cache.len(), 1);
+/// assert_eq!(cache.get(Path::new("/docs/README.md")).as_deref(), Some(&vec!["docs".to_owned()]));
+/// assert!(cache.remove(Path::new("/docs/README.md")));
+/// assert!(shared.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FileLinksCache {
+    anchors: Arc<RwLock<HashMap<PathBuf, Arc<Vec<String>>>>>,
+    counters: Arc<Counters>,
+    #[cfg(feature = "content-hash")]
+    contents: Arc<Mutex<ContentAnchors>>,
+}
+
+/// Counters of the lookups and evictions of a cache (see [`LinksCacheStats`])
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    evictions: AtomicUsize,
+    targets_only: AtomicUsize,
+}
+
+impl FileLinksCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the anchors of a file
+    pub fn get(&self, path: &Path) -> Option<Arc<Vec<String>>> {
+        self.anchors.read().unwrap().get(path).cloned()
+    }
+
+    /// Check if the anchors of a file are in the cache
+    pub fn contains(&self, path: &Path) -> bool {
+        self.anchors.read().unwrap().contains_key(path)
+    }
+
+    /// Set the anchors of a file
+    pub fn insert(&self, path: PathBuf, anchors: Vec<String>) {
+        self.anchors
+            .write()
+            .unwrap()
+            .insert(path, Arc::new(anchors));
+    }
+
+    /// Remove the anchors of a file, returning `true` if they were in the cache
+    pub fn remove(&self, path: &Path) -> bool {
+        #[cfg(feature = "content-hash")]
+        self.contents.lock().unwrap().forget(path);
+
+        self.anchors.write().unwrap().remove(path).is_some()
+    }
+
+    /// Get the number of files in the cache
+    pub fn len(&self) -> usize {
+        self.anchors.read().unwrap().len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the anchors of all files in the cache, sorted by path
+    pub fn entries(&self) -> Vec<(PathBuf, Arc<Vec<String>>)> {
+        let mut entries = self
+            .anchors
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(path, anchors)| (path.clone(), Arc::clone(anchors)))
+            .collect::<Vec<_>>();
+
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries
+    }
+
+    /// Get the anchors of a link's target, generating them if they are not in the cache yet
+    ///
+    /// The lookup is counted as a hit or a miss in the statistics of the cache.
+    /// The anchors are generated without holding the cache's lock, so other threads are not blocked in the meantime.
+    pub(crate) fn get_or_try_insert_with<E>(
+        &self,
+        path: &Path,
+        generate: impl FnOnce() -> Result<Vec<String>, E>,
+    ) -> Result<Arc<Vec<String>>, E> {
+        if let Some(anchors) = self.get(path) {
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(anchors);
+        }
+
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+
+        let anchors = Arc::new(generate()?);
+
+        // Another thread may have generated the same anchors in the meantime
+        Ok(Arc::clone(
+            self.anchors
+                .write()
+                .unwrap()
+                .entry(path.to_owned())
+                .or_insert(anchors),
+        ))
+    }
+
+    /// Get the content-addressed layer of the cache (see [`content_hash`](crate::content_hash))
+    #[cfg(feature = "content-hash")]
+    pub(crate) fn contents(&self) -> MutexGuard<'_, ContentAnchors> {
+        self.contents.lock().unwrap()
+    }
+
+    /// Get the content hash of a file, reading it if its anchors were not stored by content
+    #[cfg(feature = "content-hash")]
+    pub(crate) fn content_hash_of(&self, path: &Path) -> Option<u64> {
+        if let Some(hash) = self.contents().file_hash(path) {
+            return Some(hash);
+        }
+
+        std::fs::read(path)
+            .ok()
+            .map(|content| content_hash(&content))
+    }
+
+    /// Get the number of files whose anchors were reused from another file with the same content, instead of being parsed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{CheckerOptions, FileLinksCache, LinksCache};
+    ///
+    /// let mut cache = FileLinksCache::new();
+    /// cache.warm(Path::new("docs"), &CheckerOptions::default());
+    ///
+    /// println!("{} file(s) had the same content as another one", cache.dedupe_hits());
+    /// ```
+    #[cfg(feature = "content-hash")]
+    pub fn dedupe_hits(&self) -> usize {
+        self.contents().dedupe_hits()
+    }
+
+    /// Get the number of distinct file contents whose anchors are stored
+    #[cfg(feature = "content-hash")]
+    pub fn stored_contents(&self) -> usize {
+        self.contents().stored_contents()
+    }
+
+    /// Record an entry of a persistent cache file dropped because its file changed
+    pub(crate) fn record_eviction(&self) {
+        self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the generation of the anchors of a file which is only a link target (see [`CheckerOptions::targets_only`])
+    pub(crate) fn record_target_only_generation(&self) {
+        self.counters.targets_only.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Statistics of an anchors cache (see [`LinksCache::stats`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LinksCacheStats {
+    /// Number of files in the cache
+    pub entries: usize,
+
+    /// Total number of anchors of these files
+    pub slugs: usize,
+
+    /// Number of lookups of a link target's anchors answered by a cache
+    pub hits: usize,
+
+    /// Number of lookups of a link target's anchors which required to parse the target
+    pub misses: usize,
+
+    /// Number of entries of persistent cache files which were dropped because their file changed since they were written
+    pub evictions: usize,
+
+    /// Number of files which are only link targets (see [`CheckerOptions::targets_only`]) whose anchors were generated when linked to
+    pub targets_only: usize,
+}
+
+/// Pre-warming and statistics of an anchors cache
+pub trait LinksCache {
+    /// Generate the anchors of all Markdown files of a directory (or of a single file) which are not in the cache yet, without checking any link
+    ///
+    /// Files are found the same way as when checking a directory, and files which can't be read are skipped.
+    /// Returns the number of files added to the cache.
+    fn warm(&mut self, root: &Path, options: &CheckerOptions) -> usize;
+
+    /// Get the statistics of the cache
+    ///
+    /// Lookups and evictions are counted since the cache was created, including by its clones.
+    fn stats(&self) -> LinksCacheStats;
+}
+
+impl LinksCache for FileLinksCache {
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{check_broken_links_with, CheckerOptions, FileLinksCache, LinksCache, TargetsCache};
+    ///
+    /// let options = CheckerOptions::default();
+    /// let mut cache = FileLinksCache::new();
+    ///
+    /// cache.warm(Path::new("docs"), &options);
+    /// check_broken_links_with(Path::new("docs"), true, &options, &mut cache, &mut TargetsCache::new()).unwrap();
+    ///
+    /// let stats = cache.stats();
+    /// println!("{} hit(s), {} miss(es)", stats.hits, stats.misses);
+    /// ```
+    fn warm(&mut self, root: &Path, options: &CheckerOptions) -> usize {
+        let files = match root.is_dir() {
+            true => walk_markdown_files(root, options).0,
+            false => vec![root.to_owned()],
+        };
+
+        let mut warmed = 0;
+
+        for file in files {
+            let canonical = match file.canonicalize() {
+                Ok(canonical) => canonical,
+                Err(_) => continue,
+            };
+
+            if self.contains(&canonical) {
+                continue;
+            }
+
+            if let Ok(anchors) =
+                generate_shared_anchors(&canonical, &options.slugs_for(&canonical), self)
+            {
+                self.insert(canonical, anchors);
+                warmed += 1;
+            }
+        }
+
+        warmed
+    }
+
+    fn stats(&self) -> LinksCacheStats {
+        let anchors = self.anchors.read().unwrap();
+
+        LinksCacheStats {
+            entries: anchors.len(),
+            slugs: anchors.values().map(|anchors| anchors.len()).sum(),
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+            targets_only: self.counters.targets_only.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Directives of mdBook including other files in a chapter (e.g. `{{#include listing.rs:main}}`)
+
+use crate::BrokenLinkKind;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+lazy_static! {
+    /// Directives including a file, with their escaping backslash if any and the included path followed by its lines or anchor
+    static ref INCLUDE_DIRECTIVE_REGEX: Regex =
+        Regex::new(r"(\\?)\{\{\s*#(?:include|rustdoc_include|playground)\s+([^\s}]+)[^}]*\}\}").unwrap();
+
+    /// Anchor delimiting a part of a file which can be included on its own (e.g. `// ANCHOR: main`)
+    static ref ANCHOR_REGEX: Regex = Regex::new(r"ANCHOR:\s*([\w-]+)").unwrap();
+}
+
+/// A file included by a directive
+pub(crate) struct Include<'a> {
+    /// Offset of the directive in the chapter
+    pub offset: usize,
+
+    /// Included path, as written
+    pub written: &'a str,
+
+    /// Included path, relative to the current directory
+    pub path: PathBuf,
+
+    /// Anchor of the included part of the file, if any (lines ranges like `:2:10` are not anchors)
+    pub anchor: Option<&'a str>,
+}
+
+/// Get the files included by the (non-escaped) directives of a chapter
+pub(crate) fn includes<'a>(path: &Path, content: &'a str) -> Vec<Include<'a>> {
+    INCLUDE_DIRECTIVE_REGEX
+        .captures_iter(content)
+        .filter(|captures| captures[1].is_empty())
+        .map(|captures| {
+            let spec = captures.get(2).unwrap().as_str();
+
+            let (written, anchor) = match spec.split_once(':') {
+                Some((written, rest)) if rest.chars().all(|c| c.is_ascii_digit() || c == ':') => {
+                    (written, None)
+                }
+                Some((written, rest)) => (written, Some(rest)),
+                None => (spec, None),
+            };
+
+            Include {
+                offset: captures.get(0).unwrap().start(),
+                written,
+                path: path.parent().unwrap().join(written),
+                anchor,
+            }
+        })
+        .collect()
+}
+
+/// Find the directives of a chapter including a missing file, or a missing anchor of a file
+///
+/// Each invalid directive is returned with its offset, the kind of the finding and the reason why it's invalid.
+pub(crate) fn check_includes(path: &Path, content: &str) -> Vec<(usize, BrokenLinkKind, String)> {
+    let mut invalid = vec![];
+
+    for include in includes(path, content) {
+        if !include.path.is_file() {
+            invalid.push((
+                include.offset,
+                BrokenLinkKind::MissingTarget,
+                format!("included file '{}' does not exist", include.written),
+            ));
+
+            continue;
+        }
+
+        let anchor = match include.anchor {
+            Some(anchor) => anchor,
+            None => continue,
+        };
+
+        let included = std::fs::read(&include.path).unwrap_or_default();
+
+        let found = ANCHOR_REGEX
+            .captures_iter(&String::from_utf8_lossy(&included))
+            .any(|captures| &captures[1] == anchor);
+
+        if !found {
+            invalid.push((
+                include.offset,
+                BrokenLinkKind::MissingAnchor,
+                format!(
+                    "anchor '{}' does not exist in included file '{}'",
+                    anchor, include.written
+                ),
+            ));
+        }
+    }
+
+    invalid
+}
@@ -0,0 +1,56 @@
+use crate::embedded_markdown::EmbeddedMarkdown;
+use serde::Deserialize;
+
+/// Content of a Jupyter notebook (only the parts which are needed)
+#[derive(Deserialize)]
+struct Notebook {
+    cells: Vec<Cell>,
+}
+
+/// A cell of a Jupyter notebook
+#[derive(Deserialize)]
+struct Cell {
+    cell_type: String,
+    source: Source,
+}
+
+/// Source of a notebook cell, which is either a single string or a list of lines (each one ending with its line break)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Source {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+/// Extract the Markdown cells of a Jupyter notebook (`.ipynb`)
+///
+/// Each cell is parsed on its own, and its lines are counted from the beginning of the cell.
+/// Cells are numbered from 1, including the code cells.
+pub(crate) fn extract_markdown_cells(json: &str) -> Result<EmbeddedMarkdown, String> {
+    let notebook: Notebook = serde_json::from_str(json)
+        .map_err(|err| format!("is not a valid Jupyter notebook: {}", err))?;
+
+    let mut cells = EmbeddedMarkdown::default();
+
+    for (index, cell) in notebook.cells.into_iter().enumerate() {
+        if cell.cell_type != "markdown" {
+            continue;
+        }
+
+        let source = match cell.source {
+            Source::Text(text) => text,
+            Source::Lines(lines) => lines.concat(),
+        };
+
+        cells.push_block(
+            source
+                .lines()
+                .enumerate()
+                .map(|(line, text)| (line + 1, text.to_owned()))
+                .collect(),
+            Some(index + 1),
+        );
+    }
+
+    Ok(cells)
+}
@@ -0,0 +1,269 @@
+//! Builder of the checker's options (see [`CheckerOptionsBuilder`])
+
+use crate::directory_config::{DirectoryConfigs, GitignorePatterns};
+use crate::{
+    front_matter_fields, safe_canonicalize, AnchorAliases, CheckerOptions, EmojiHandling,
+    InvalidUtf8Handling, MathSlugHandling, PlaceholderOptions, SchemeHandlers, Severity,
+    SlugAlgorithm, SlugOptions,
+};
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Builder of [`CheckerOptions`], starting from the default options (or from a preset, e.g. [`CheckerOptions::for_github`])
+///
+/// Each option of [`CheckerOptions`] has a method of the same name to set it.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::{CheckerOptions, CheckerOptionsBuilder, Severity};
+///
+/// let options = CheckerOptions::builder()
+///     .ignore_header_links(true)
+///     .anchor_case_severity(Severity::Error)
+///     .build();
+///
+/// assert_eq!(options, CheckerOptions { ignore_header_links: true, anchor_case_severity: Severity::Error, ..Default::default() });
+///
+/// // Presets can be customized as well
+/// let options = CheckerOptions::for_github().no_errors(true).build();
+/// assert_eq!(options, CheckerOptions { no_errors: true, ..CheckerOptions::for_github().build() });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CheckerOptionsBuilder(CheckerOptions);
+
+impl CheckerOptionsBuilder {
+    /// Create a builder starting from the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the built options
+    pub fn build(self) -> CheckerOptions {
+        self.0
+    }
+}
+
+impl From<CheckerOptions> for CheckerOptionsBuilder {
+    fn from(options: CheckerOptions) -> Self {
+        Self(options)
+    }
+}
+
+impl CheckerOptions {
+    /// Create a builder starting from the default options (see [`CheckerOptionsBuilder`])
+    pub fn builder() -> CheckerOptionsBuilder {
+        CheckerOptionsBuilder::new()
+    }
+
+    /// Builder of the options matching how GitHub renders Markdown files
+    ///
+    /// Slugs are generated like GitHub does (see [`SlugAlgorithm::GitHub`]), so non-ASCII letters are kept
+    ///  and emojis are removed. Files ignored by `.gitignore` files are not checked, images are checked
+    ///  and links to directories are allowed, as GitHub displays their content.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use broken_md_links::{slugify_with, CheckerOptions, EmojiHandling, SlugAlgorithm};
+    ///
+    /// let options = CheckerOptions::for_github().build();
+    ///
+    /// assert!(!options.only_files && !options.no_ignore && !options.ignore_images);
+    /// assert_eq!(options.slugs.algorithm, SlugAlgorithm::GitHub);
+    /// assert_eq!(options.slugs.emoji_handling, EmojiHandling::Strip);
+    ///
+    /// assert_eq!(slugify_with("Café & Co", &options.slugs), "café--co");
+    /// assert_eq!(slugify_with("Café & Co", &CheckerOptions::default().slugs), "caf--co");
+    /// ```
+    pub fn for_github() -> CheckerOptionsBuilder {
+        Self::builder()
+            .no_ignore(false)
+            .only_files(false)
+            .ignore_images(false)
+            .slugs(SlugOptions {
+                emoji_handling: EmojiHandling::Strip,
+                math_in_slug: MathSlugHandling::Strip,
+                algorithm: SlugAlgorithm::GitHub,
+            })
+    }
+
+    /// Builder of the options matching how MkDocs builds a documentation site
+    ///
+    /// Slugs are generated like MkDocs does (see [`SlugAlgorithm::MkDocs`]), so accents are stripped and
+    ///  consecutive dashes are collapsed. MkDocs only generates pages for files, so links to directories are refused.
+    ///
+    /// The checked directory should be MkDocs' `docs_dir`, which links are resolved from (see [`CheckerOptions::mkdocs_docs_dir`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use broken_md_links::{slugify_with, CheckerOptions, SlugAlgorithm};
+    ///
+    /// let options = CheckerOptions::for_mkdocs().build();
+    ///
+    /// assert!(options.only_files);
+    /// assert_eq!(options.slugs.algorithm, SlugAlgorithm::MkDocs);
+    ///
+    /// assert_eq!(slugify_with("Café & Co", &options.slugs), "cafe-co");
+    /// assert_eq!(slugify_with("Café & Co", &CheckerOptions::for_github().build().slugs), "café--co");
+    /// ```
+    pub fn for_mkdocs() -> CheckerOptionsBuilder {
+        Self::for_github().only_files(true).slugs(SlugOptions {
+            algorithm: SlugAlgorithm::MkDocs,
+            ..Self::for_github().build().slugs
+        })
+    }
+
+    /// Builder of the options matching how mdBook builds a book
+    ///
+    /// Slugs are generated like mdBook does (see [`SlugAlgorithm::MdBook`]), so every whitespace character is a dash.
+    /// mdBook only generates pages for files, so links to directories are refused, and the files and anchors included
+    ///  with its directives (e.g. `{{#include listing.rs:main}}`) are checked (see [`CheckerOptions::check_mdbook_includes`]).
+    ///
+    /// The checked directory should be the book's `src/` directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use broken_md_links::{slugify_with, CheckerOptions};
+    ///
+    /// let options = CheckerOptions::for_mdbook().build();
+    ///
+    /// assert!(options.only_files && options.check_mdbook_includes);
+    /// assert_eq!(slugify_with("Café\t& Co", &options.slugs), "café--co");
+    /// ```
+    pub fn for_mdbook() -> CheckerOptionsBuilder {
+        Self::for_github()
+            .only_files(true)
+            .check_mdbook_includes(true)
+            .slugs(SlugOptions {
+                algorithm: SlugAlgorithm::MdBook,
+                ..Self::for_github().build().slugs
+            })
+    }
+
+    /// Find the directory of the Markdown files of a MkDocs project (its `docs_dir`), which is the one to check (see [`CheckerOptions::for_mkdocs`])
+    ///
+    /// The directory is read from the `docs_dir` field of the project's `mkdocs.yml` (or `mkdocs.yaml`) file, relative to the project's directory,
+    ///  and defaults to `docs` like MkDocs does. Only the top-level fields of the configuration file are read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{check_broken_links_simple, CheckerOptions};
+    ///
+    /// let docs_dir = CheckerOptions::mkdocs_docs_dir(Path::new(".")).unwrap();
+    /// let errors = check_broken_links_simple(&docs_dir, &CheckerOptions::for_mkdocs().build()).unwrap();
+    /// ```
+    pub fn mkdocs_docs_dir(project_dir: &Path) -> Result<PathBuf, String> {
+        let config = ["mkdocs.yml", "mkdocs.yaml"]
+            .iter()
+            .map(|name| project_dir.join(name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| {
+                format!(
+                    "No MkDocs configuration file found in '{}'",
+                    safe_canonicalize(project_dir).green()
+                )
+            })?;
+
+        let canon = safe_canonicalize(&config);
+
+        let content = fs::read_to_string(&config).map_err(|err| {
+            format!(
+                "Failed to read MkDocs configuration file at '{}': {}",
+                canon.green(),
+                err
+            )
+        })?;
+
+        // The top-level fields of the configuration are read like the ones of a front matter
+        let docs_dir = match front_matter_fields(&format!("---\n{}\n---\n", content)) {
+            Some(Ok(fields)) => fields
+                .into_iter()
+                .find(|(key, value)| *key == "docs_dir" && !value.is_empty())
+                .map(|(_, value)| value.to_owned()),
+            _ => {
+                return Err(format!(
+                    "MkDocs configuration file at '{}' is not a YAML mapping",
+                    canon.green()
+                ))
+            }
+        };
+
+        Ok(project_dir.join(docs_dir.as_deref().unwrap_or("docs")))
+    }
+}
+
+/// Generate the methods of the builder which set each option
+macro_rules! setters {
+    ($($option: ident: $type: ty),* $(,)?) => {
+        impl CheckerOptionsBuilder {
+            $(
+                #[doc = concat!("Set [`CheckerOptions::", stringify!($option), "`]")]
+                pub fn $option(mut self, $option: $type) -> Self {
+                    self.0.$option = $option;
+                    self
+                }
+            )*
+        }
+    };
+}
+
+setters! {
+    ignore_header_links: bool,
+    only_files: bool,
+    no_errors: bool,
+    check_mailto: bool,
+    check_path_style: bool,
+    check_extension_case: bool,
+    max_link_length: Option<usize>,
+    changelog_mode: bool,
+    issues_dir: Option<PathBuf>,
+    require_alt_text: bool,
+    check_image_dimensions: bool,
+    ignore_images: bool,
+    ignore_unresolved_references: bool,
+    ignore_html_blocks: bool,
+    check_toc: bool,
+    check_mdbook_includes: bool,
+    navigation_file: Option<PathBuf>,
+    navigation_order_severity: Severity,
+    lenient_space_destinations: bool,
+    dedupe_findings: bool,
+    group_by_target: bool,
+    no_ignore: bool,
+    halt_on_io_error: bool,
+    follow_symlinks: bool,
+    max_file_size: Option<u64>,
+    timeout: Option<Duration>,
+    check_http_links: bool,
+    follow_redirects: bool,
+    http_timeout: Option<Duration>,
+    invalid_utf8: InvalidUtf8Handling,
+    max_concurrent_dirs: usize,
+    parallel: bool,
+    redirect_markers: Vec<String>,
+    draft_markers: Vec<(String, String)>,
+    frontmatter_required_fields: Vec<String>,
+    partial_dirs: Vec<String>,
+    partial_context_severity: Severity,
+    anchor_aliases: AnchorAliases,
+    warn_anchor_aliases: bool,
+    anchor_case_severity: Severity,
+    anchor_punctuation_severity: Severity,
+    anchor_plural_severity: Severity,
+    link_text_is_url: Option<Severity>,
+    placeholders: PlaceholderOptions,
+    scheme_handlers: SchemeHandlers,
+    profile: bool,
+    rust_doc_comments: bool,
+    error_on_warnings: bool,
+    slugs: SlugOptions,
+    directory_configs: DirectoryConfigs,
+    targets_only: GitignorePatterns,
+}
@@ -0,0 +1,105 @@
+//! Output of the messages, buffered by file so the messages of files checked at the same time don't interleave
+
+use lazy_static::lazy_static;
+use std::cell::RefCell;
+use std::io::Write;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// Lock held while writing to the standard output or error, so blocks of lines are written at once
+    static ref OUTPUT_LOCK: Mutex<()> = Mutex::new(());
+}
+
+thread_local! {
+    /// Lines written while a file is checked on this thread, with whether they go to the standard error
+    static BUFFER: RefCell<Option<Vec<(String, bool)>>> = const { RefCell::new(None) };
+}
+
+/// Write a line to the standard output (or error), or to the current thread's buffer if a file is being checked on it
+#[cfg(feature = "serde")]
+pub(crate) fn write_line(line: String, to_stderr: bool) {
+    let line = BUFFER.with(|buffer| match *buffer.borrow_mut() {
+        Some(ref mut lines) => {
+            lines.push((line, to_stderr));
+            None
+        }
+        None => Some(line),
+    });
+
+    if let Some(line) = line {
+        flush(vec![(line, to_stderr)]);
+    }
+}
+
+/// Run a function while buffering the lines written on the current thread, then write them at once
+///
+/// Nested calls are buffered in the outermost one.
+pub(crate) fn buffered<T>(run: impl FnOnce() -> T) -> T {
+    let outermost = BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        let outermost = buffer.is_none();
+
+        if outermost {
+            *buffer = Some(vec![]);
+        }
+
+        outermost
+    });
+
+    if !outermost {
+        return run();
+    }
+
+    // Ensure the lines are written and buffering stops even if the function panics
+    struct Flush;
+
+    impl Drop for Flush {
+        fn drop(&mut self) {
+            if let Some(lines) = BUFFER.with(|buffer| buffer.borrow_mut().take()) {
+                flush(lines);
+            }
+        }
+    }
+
+    let _flush = Flush;
+    run()
+}
+
+/// Write lines to the standard output and error, with a single write for each consecutive lines going to the same one
+fn flush(lines: Vec<(String, bool)>) {
+    if lines.is_empty() {
+        return;
+    }
+
+    let _lock = OUTPUT_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut block = String::new();
+    let mut block_to_stderr = lines[0].1;
+
+    for (line, to_stderr) in lines {
+        if to_stderr != block_to_stderr {
+            write_block(&block, block_to_stderr);
+            block.clear();
+            block_to_stderr = to_stderr;
+        }
+
+        block.push_str(&line);
+        block.push('\n');
+    }
+
+    write_block(&block, block_to_stderr);
+}
+
+/// Write a block of lines to the standard output or error (failures, e.g. due to a closed pipe, are ignored)
+fn write_block(block: &str, to_stderr: bool) {
+    let _ = if to_stderr {
+        std::io::stderr().lock().write_all(block.as_bytes())
+    } else {
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(block.as_bytes())
+            .and_then(|()| stdout.flush())
+    };
+}
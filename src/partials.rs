@@ -0,0 +1,235 @@
+//! Checking of the links of partials (files inlined into other pages when the site is published) from the pages including them
+//!
+//! Static site generators like Jekyll and Hugo inline the content of partials (e.g. files of `_includes/`) into the pages
+//!  including them, so their relative links are resolved from these pages' location once published.
+
+use crate::{
+    extract_links, format_location, read_text_file, report_unreadable_paths, safe_canonicalize,
+    walk_markdown_files, warning_severity, BrokenLinkKind, CheckerOptions, DetectedBrokenLink,
+    Severity, EMAIL_REGEX, URL_SCHEME_REGEX,
+};
+use colored::Colorize;
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+lazy_static! {
+    /// Inclusion of a partial: Jekyll's `{% include path %}` and `{% include_relative path %}` tags,
+    ///  or Hugo's `include` and `readfile` shortcodes (e.g. `{{< include "path" >}}` or `{{% readfile file="path" %}}`)
+    static ref INCLUSION_REGEX: Regex = Regex::new(
+        r#"\{%-?\s*include(?:_relative)?\s+"?([^\s"%]+)"?[^%]*-?%\}|\{\{[<%]\s*(?:include|readfile)\s+(?:file=)?"([^"]+)"[^}]*[>%]\}\}"#
+    )
+    .unwrap();
+}
+
+/// Get the partials included by a file, as written (see [`INCLUSION_REGEX`])
+fn inclusions(content: &str) -> Vec<&str> {
+    INCLUSION_REGEX
+        .captures_iter(content)
+        .filter_map(|captures| captures.get(1).or_else(|| captures.get(2)))
+        .map(|inclusion| inclusion.as_str())
+        .collect()
+}
+
+/// Check that the relative links of the partials of a directory resolve from the pages including them
+///
+/// Partials are the Markdown files located in a directory named after one of [`CheckerOptions::partial_dirs`] (at any depth).
+/// An inclusion (see below) refers to a partial if the partial's path relatively to the inclusion's file or to its partials directory
+///  is the included path, so both Jekyll's `include` (from the `_includes` directory) and `include_relative` tags are supported.
+///
+/// Links which resolve from the partial's directory but not from the directory of a page including it are reported
+///  as [`BrokenLinkKind::PartialContextLink`] findings located in the partial, whose message names the including page.
+/// Links which don't resolve from the partial's directory are not reported, as they are when the partial itself is checked.
+/// Only the inclusions located in pages which are not partials are considered.
+///
+/// Findings have the [`CheckerOptions::partial_context_severity`] severity. Paths which can't be read are reported
+///  like when checking a directory, unless [`CheckerOptions::halt_on_io_error`] is set.
+///
+/// Supported inclusions are Jekyll's `{% include path %}` and `{% include_relative path %}` tags,
+///  and Hugo's `include` and `readfile` shortcodes (e.g. `{{< include "path" >}}` or `{{% readfile file="path" %}}`).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::CheckerOptions;
+/// use broken_md_links::partials::check_partial_links;
+///
+/// let options = CheckerOptions { partial_dirs: vec!["_includes".to_owned()], ..CheckerOptions::default() };
+///
+/// for finding in check_partial_links(Path::new("docs"), &options).unwrap() {
+///     println!("{}:{}: {}", finding.file.display(), finding.line, finding.error);
+/// }
+/// ```
+pub fn check_partial_links(
+    root: &Path,
+    options: &CheckerOptions,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    let (files, mut failures, _) = walk_markdown_files(root, options);
+    let findings = partial_links(root, &files, &mut failures, options)?;
+
+    let mut unreadable = report_unreadable_paths(failures, options)?;
+    unreadable.extend(findings);
+
+    Ok(unreadable)
+}
+
+/// Check the links of the partials of a directory like [`check_partial_links`], once the directory itself was checked
+///
+/// Paths which can't be read are not reported, as the check of the directory already reported them.
+#[cfg(feature = "serde")]
+pub(crate) fn check_checked_partial_links(
+    root: &Path,
+    options: &CheckerOptions,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    let (files, mut failures, _) = walk_markdown_files(root, options);
+    partial_links(root, &files, &mut failures, options)
+}
+
+/// Check the links of the partials among the provided files
+///
+/// Files which can't be read are added to the failures (see [`report_unreadable_paths`]) and skipped,
+///  unless [`CheckerOptions::halt_on_io_error`] is set.
+fn partial_links(
+    root: &Path,
+    files: &[PathBuf],
+    failures: &mut Vec<(PathBuf, String)>,
+    options: &CheckerOptions,
+) -> Result<Vec<DetectedBrokenLink>, String> {
+    let unreadable = |file: &Path, err: String| {
+        let error = format!("failed to read file: {}", err);
+
+        match options.halt_on_io_error {
+            true => Err(format!(
+                "In '{}': {}",
+                safe_canonicalize(file).green(),
+                error
+            )),
+            false => Ok((file.to_owned(), error)),
+        }
+    };
+
+    // Partials, with their path relatively to their partials directory
+    let partials = files
+        .iter()
+        .filter_map(|file| {
+            let relative = file.strip_prefix(root).ok()?;
+
+            let partials_dir = relative.ancestors().skip(1).find(|ancestor| {
+                ancestor
+                    .file_name()
+                    .is_some_and(|name| options.partial_dirs.iter().any(|dir| name == dir.as_str()))
+            })?;
+
+            Some((file, relative.strip_prefix(partials_dir).unwrap()))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    if partials.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Pages including each partial
+    let mut included_by = BTreeMap::<&PathBuf, BTreeSet<&PathBuf>>::new();
+
+    for file in files.iter().filter(|file| !partials.contains_key(file)) {
+        let content = match read_text_file(file) {
+            Ok((content, _)) => content,
+            Err(err) => {
+                failures.push(unreadable(file, err.to_string())?);
+                continue;
+            }
+        };
+
+        for inclusion in inclusions(&content) {
+            let relative_to_file = file.parent().unwrap().join(inclusion);
+
+            for (partial, path) in &partials {
+                if *path == Path::new(inclusion) || **partial == relative_to_file {
+                    included_by.entry(partial).or_default().insert(file);
+                }
+            }
+        }
+    }
+
+    let display = |file: &Path| match file.strip_prefix(root) {
+        Ok(relative) => relative.to_string_lossy().into_owned(),
+        Err(_) => safe_canonicalize(file),
+    };
+
+    // Findings which are warnings are errors if warnings are treated as errors
+    let severity = match options.partial_context_severity {
+        Severity::Warning => warning_severity(options),
+        severity => severity,
+    };
+
+    let mut findings = vec![];
+
+    for (partial, pages) in included_by {
+        let partial_dir = partial.parent().unwrap();
+
+        let links = match extract_links(partial, options) {
+            Ok(links) => links,
+            Err(err) => {
+                failures.push(unreadable(partial, err)?);
+                continue;
+            }
+        };
+
+        for link in links {
+            let target = link.path();
+
+            if target.is_empty()
+                || target.starts_with('/')
+                || URL_SCHEME_REGEX.is_match(target)
+                || EMAIL_REGEX.is_match(target)
+                || !partial_dir.join(target).exists()
+            {
+                continue;
+            }
+
+            for page in &pages {
+                if page.parent().unwrap().join(target).exists() {
+                    continue;
+                }
+
+                let message = format!(
+                    "link to '{}' only resolves from the partial's directory, not once included in '{}'",
+                    link.destination,
+                    display(page)
+                );
+
+                let displayed = format!(
+                    "In {}{} {}",
+                    safe_canonicalize(partial).green(),
+                    format_location(link.line, link.cell).yellow(),
+                    message
+                );
+
+                match severity {
+                    Severity::Error if options.no_errors => warn!("{}", displayed),
+                    Severity::Error => error!("{}", displayed),
+                    Severity::Warning => warn!("{}", displayed),
+                    Severity::Info => info!("{}", displayed),
+                }
+
+                findings.push(DetectedBrokenLink {
+                    file: partial.clone(),
+                    line: link.line,
+                    cell: link.cell,
+                    kind: BrokenLinkKind::PartialContextLink,
+                    severity,
+                    error: message,
+                    suggestion: None,
+                    target: Some(link.destination.clone()),
+                    column: None,
+                    link_text: None,
+                });
+            }
+        }
+    }
+
+    Ok(findings)
+}
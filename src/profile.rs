@@ -0,0 +1,90 @@
+//! Timing of the check of each file (see [`CheckerOptions::profile`](crate::CheckerOptions::profile))
+//!
+//! The profiles of a check are recorded in its targets cache (see [`TargetsCache::take_file_profiles`](crate::TargetsCache::take_file_profiles)).
+
+use crate::{BrokenLinkKind, DetectedBrokenLink, Severity};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Time spent on each phase of a file's check, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileProfile {
+    /// Path of the checked file
+    pub file: PathBuf,
+
+    /// Time spent reading the file
+    pub read_ms: f64,
+
+    /// Time spent generating slugs, of the file's headers and of the headers of the files it links to
+    pub slug_gen_ms: f64,
+
+    /// Time spent parsing the file and checking its links, excluding slugs generation
+    pub link_check_ms: f64,
+
+    /// Number of links checked in the file
+    pub link_count: usize,
+
+    /// Number of broken links found in the file (warnings excluded)
+    pub broken_count: usize,
+}
+
+impl FileProfile {
+    /// Get the profile of a file's check which started at `started`, from the time spent on each phase of it
+    ///
+    /// The time spent generating slugs on the current thread since it was last taken is added to `slugs_time`.
+    pub(crate) fn new(
+        file: &Path,
+        started: Instant,
+        read_time: Duration,
+        slugs_time: Duration,
+        link_count: usize,
+        findings: &[DetectedBrokenLink],
+    ) -> Self {
+        let slugs_time = slugs_time + take_slug_generation_time();
+
+        Self {
+            file: file.to_owned(),
+            read_ms: millis(read_time),
+            slug_gen_ms: millis(slugs_time),
+            link_check_ms: millis(
+                started
+                    .elapsed()
+                    .saturating_sub(read_time)
+                    .saturating_sub(slugs_time),
+            ),
+            link_count,
+            broken_count: findings
+                .iter()
+                .filter(|finding| {
+                    finding.severity == Severity::Error && finding.kind != BrokenLinkKind::Warning
+                })
+                .count(),
+        }
+    }
+}
+
+thread_local! {
+    /// Time spent generating slugs on this thread since it was last taken
+    static SLUG_GENERATION: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Run a slugs generation, adding the time it took to the current thread's total
+pub(crate) fn time_slug_generation<T>(generate: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = generate();
+
+    SLUG_GENERATION.with(|total| total.set(total.get() + started.elapsed()));
+    result
+}
+
+/// Get the time spent generating slugs on the current thread, and reset it
+pub(crate) fn take_slug_generation_time() -> Duration {
+    SLUG_GENERATION.with(|total| total.replace(Duration::ZERO))
+}
+
+/// Convert a duration to milliseconds, with a microsecond precision
+pub(crate) fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
@@ -0,0 +1,454 @@
+//! Reports written with `--format json`, `--format sarif` or `--format github`, and their comparison
+//!
+//! Findings are identified by their file, kind and message (which includes the link's target), regardless of their line,
+//!  so moving a broken link around in its file doesn't make it a new finding.
+
+use crate::{
+    broken_targets, format_error_summary, safe_canonicalize, BrokenTarget, CheckStats,
+    DetectedBrokenLink, Severity, TargetGroup,
+};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+/// Differences between an old and a new report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportDiff {
+    /// Findings of the new report which were not in the old one
+    pub new: Vec<DetectedBrokenLink>,
+
+    /// Findings of the old report which are not in the new one anymore
+    pub fixed: Vec<DetectedBrokenLink>,
+
+    /// Findings present in both reports (as located in the new one)
+    pub persisting: Vec<DetectedBrokenLink>,
+}
+
+impl ReportDiff {
+    /// Format the differences as a text report, like the checker displays broken links
+    pub fn format_text(&self) -> String {
+        [
+            ("New findings", &self.new),
+            ("Fixed findings", &self.fixed),
+            ("Persisting findings", &self.persisting),
+        ]
+        .iter()
+        .map(|(title, findings)| match findings.len() {
+            0 => format!("{}: none", title),
+            count => format!("{} ({}):\n{}", title, count, format_error_summary(findings)),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+    }
+
+    /// Format the differences as Markdown, e.g. to post them as a pull request comment
+    ///
+    /// Persisting findings are collapsed as they are already known.
+    pub fn format_markdown(&self) -> String {
+        let list = |findings: &[DetectedBrokenLink]| {
+            findings
+                .iter()
+                .map(|finding| {
+                    format!(
+                        "- `{}{}` {} (`{}`)",
+                        safe_canonicalize(&finding.file),
+                        finding.location(),
+                        finding.error,
+                        finding.kind.id()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let mut out = format!(
+            "### Broken links: {} new, {} fixed, {} persisting\n",
+            self.new.len(),
+            self.fixed.len(),
+            self.persisting.len()
+        );
+
+        if !self.new.is_empty() {
+            out.push_str(&format!("\n#### :x: New\n\n{}\n", list(&self.new)));
+        }
+
+        if !self.fixed.is_empty() {
+            out.push_str(&format!(
+                "\n#### :white_check_mark: Fixed\n\n{}\n",
+                list(&self.fixed)
+            ));
+        }
+
+        if !self.persisting.is_empty() {
+            out.push_str(&format!(
+                "\n<details>\n<summary>Persisting ({})</summary>\n\n{}\n\n</details>\n",
+                self.persisting.len(),
+                list(&self.persisting)
+            ));
+        }
+
+        out
+    }
+}
+
+/// Compare an old and a new report
+///
+/// Identical findings of a file are matched in order, so a link broken twice in the new report but once in the old one
+///  results in a persisting finding and a new one.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use broken_md_links::{BrokenLinkKind, DetectedBrokenLink, Severity};
+/// use broken_md_links::report_diff::diff_reports;
+///
+/// let link = |file: &str, line, error: &str| DetectedBrokenLink {
+///     file: PathBuf::from(file),
+///     line,
+///     cell: None,
+///     kind: BrokenLinkKind::MissingTarget,
+///     severity: Severity::Error,
+///     error: error.to_owned(),
+///     suggestion: None,
+///     target: None,
+///     column: None,
+///     link_text: None,
+/// };
+///
+/// let old = vec![link("a.md", 3, "path 'b.md' does not exist"), link("a.md", 8, "path 'c.md' does not exist")];
+/// let new = vec![link("a.md", 5, "path 'b.md' does not exist"), link("d.md", 1, "path 'c.md' does not exist")];
+///
+/// let diff = diff_reports(&old, &new);
+///
+/// assert_eq!(diff.new, vec![link("d.md", 1, "path 'c.md' does not exist")]);
+/// assert_eq!(diff.fixed, vec![link("a.md", 8, "path 'c.md' does not exist")]);
+/// assert_eq!(diff.persisting, vec![link("a.md", 5, "path 'b.md' does not exist")]);
+/// ```
+pub fn diff_reports(old: &[DetectedBrokenLink], new: &[DetectedBrokenLink]) -> ReportDiff {
+    // Indexes of the old findings which were not matched yet, by identity
+    let mut unmatched = HashMap::<_, VecDeque<usize>>::new();
+
+    for (index, finding) in old.iter().enumerate() {
+        unmatched
+            .entry((&finding.file, finding.cell, finding.kind, &finding.error))
+            .or_default()
+            .push_back(index);
+    }
+
+    let mut fixed = vec![true; old.len()];
+    let mut diff = ReportDiff::default();
+
+    for finding in new {
+        let matched = unmatched
+            .get_mut(&(&finding.file, finding.cell, finding.kind, &finding.error))
+            .and_then(VecDeque::pop_front);
+
+        match matched {
+            Some(index) => {
+                fixed[index] = false;
+                diff.persisting.push(finding.clone());
+            }
+            None => diff.new.push(finding.clone()),
+        }
+    }
+
+    diff.fixed = old
+        .iter()
+        .zip(fixed)
+        .filter(|(_, fixed)| *fixed)
+        .map(|(finding, _)| finding.clone())
+        .collect();
+
+    diff
+}
+
+/// Version of the shape of the reports written with `--format json` (see [`JsonReport`])
+///
+/// It's increased when the shape changes in a way which may break their parsers (e.g. a field is removed or renamed).
+pub static REPORT_FORMAT_VERSION: u32 = 1;
+
+/// Report written with `--format json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct JsonReport {
+    /// Version of the report's shape (see [`REPORT_FORMAT_VERSION`])
+    pub format_version: u32,
+
+    /// Version of the tool which wrote the report
+    pub tool_version: String,
+
+    /// All findings
+    pub findings: Vec<DetectedBrokenLink>,
+
+    /// Links to missing targets, grouped by target (see [`group_by_target`](crate::group_by_target)), with `--group-by-target`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<TargetGroup>>,
+
+    /// Number of links to each missing target (see [`broken_targets`](crate::broken_targets))
+    #[serde(default)]
+    pub broken_targets: Vec<BrokenTarget>,
+
+    /// Number of files and links checked, and of broken links found
+    #[serde(default)]
+    pub summary: ReportSummary,
+}
+
+impl JsonReport {
+    /// Create a report with the current format and tool versions, counting the links to each missing target
+    ///
+    /// The number of checked files and links is provided by the caller (see [`TargetsCache::take_check_stats`](crate::TargetsCache::take_check_stats)).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{check_broken_links_with, CheckerOptions, FileLinksCache, TargetsCache};
+    /// use broken_md_links::report_diff::JsonReport;
+    ///
+    /// let mut targets_cache = TargetsCache::new();
+    /// let findings = check_broken_links_with(Path::new("docs"), true, &CheckerOptions::default(), &mut FileLinksCache::new(), &mut targets_cache).unwrap();
+    /// let report = JsonReport::new(findings, None, targets_cache.take_check_stats());
+    ///
+    /// println!("{} broken link(s) in {} file(s)", report.summary.broken_count, report.summary.files_checked);
+    /// ```
+    pub fn new(
+        findings: Vec<DetectedBrokenLink>,
+        groups: Option<Vec<TargetGroup>>,
+        stats: CheckStats,
+    ) -> Self {
+        Self {
+            format_version: REPORT_FORMAT_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+            broken_targets: broken_targets(&findings),
+            summary: ReportSummary {
+                files_checked: stats.files,
+                links_checked: stats.links,
+                broken_count: findings
+                    .iter()
+                    .filter(|finding| finding.severity == Severity::Error)
+                    .count(),
+            },
+            findings,
+            groups,
+        }
+    }
+}
+
+/// Summary of a check, in the reports written with `--format json` (see [`JsonReport`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReportSummary {
+    /// Number of checked files
+    pub files_checked: usize,
+
+    /// Number of checked links
+    pub links_checked: usize,
+
+    /// Number of findings with an error severity
+    pub broken_count: usize,
+}
+
+/// Get the [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log written with `--format sarif`,
+///  which code scanning tools (e.g. GitHub's) can display on the lines of the findings
+///
+/// Each finding is a result whose rule is its kind (see [`BrokenLinkKind::id`](crate::BrokenLinkKind::id)).
+/// Findings which are not located in a file's content (e.g. unreadable directories) have no region.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_simple, CheckerOptions};
+/// use broken_md_links::report_diff::sarif_report;
+///
+/// let findings = check_broken_links_simple(Path::new("docs"), &CheckerOptions::default()).unwrap();
+///
+/// std::fs::write("broken-links.sarif", sarif_report(&findings).to_string()).unwrap();
+/// ```
+pub fn sarif_report(findings: &[DetectedBrokenLink]) -> serde_json::Value {
+    let mut rules = findings
+        .iter()
+        .map(|finding| finding.kind.id())
+        .collect::<Vec<_>>();
+
+    rules.sort_unstable();
+    rules.dedup();
+
+    let results = findings
+        .iter()
+        .map(|finding| {
+            let path = finding.file.to_string_lossy().replace('\\', "/");
+
+            // Relative paths are relative to the directory the tool was run from, which is usually the repository's root
+            let uri = match finding.file.is_absolute() {
+                true => format!("file://{}", path),
+                false => path.trim_start_matches("./").to_owned(),
+            };
+
+            let mut location = serde_json::json!({ "artifactLocation": { "uri": uri } });
+
+            if finding.line > 0 {
+                location["region"] = match finding.column {
+                    Some(column) => {
+                        serde_json::json!({ "startLine": finding.line, "startColumn": column })
+                    }
+                    None => serde_json::json!({ "startLine": finding.line }),
+                };
+            }
+
+            serde_json::json!({
+                "ruleId": finding.kind.id(),
+                "level": match finding.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "note",
+                },
+                "message": { "text": finding.error },
+                "locations": [{ "physicalLocation": location }],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules.iter().map(|id| serde_json::json!({ "id": id })).collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Get the [workflow commands](https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions) written with `--format github`,
+///  which make GitHub Actions display each finding as an annotation on its line (one command per line)
+///
+/// Errors are written as `::error` commands, warnings as `::warning` commands and informational findings as `::notice` commands.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_simple, CheckerOptions};
+/// use broken_md_links::report_diff::github_annotations;
+///
+/// let findings = check_broken_links_simple(Path::new("docs"), &CheckerOptions::default()).unwrap();
+///
+/// print!("{}", github_annotations(&findings));
+/// ```
+pub fn github_annotations(findings: &[DetectedBrokenLink]) -> String {
+    // Data and properties of workflow commands can't contain some characters
+    let escape_data = |data: &str| {
+        data.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+
+    let escape_property = |property: &str| {
+        escape_data(property)
+            .replace(':', "%3A")
+            .replace(',', "%2C")
+    };
+
+    findings
+        .iter()
+        .map(|finding| {
+            let command = match finding.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Info => "notice",
+            };
+
+            let path = finding.file.to_string_lossy().replace('\\', "/");
+            let mut properties = vec![format!(
+                "file={}",
+                escape_property(path.trim_start_matches("./"))
+            )];
+
+            if finding.line > 0 {
+                properties.push(format!("line={}", finding.line));
+
+                if let Some(column) = finding.column {
+                    properties.push(format!("col={}", column));
+                }
+            }
+
+            properties.push(format!("title={}", finding.kind.id()));
+
+            format!(
+                "::{} {}::{}\n",
+                command,
+                properties.join(","),
+                escape_data(&finding.error)
+            )
+        })
+        .collect()
+}
+
+/// Get the JSON Schema of the reports written with `--format json` (see [`JsonReport`])
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::report_diff::report_schema;
+///
+/// let schema: serde_json::Value = serde_json::from_str(&report_schema()).unwrap();
+///
+/// assert_eq!(schema["title"], "JsonReport");
+/// assert_eq!(schema["required"], serde_json::json!(["format_version", "tool_version", "findings"]));
+/// ```
+#[cfg(feature = "schema")]
+pub fn report_schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(JsonReport)).unwrap()
+}
+
+/// Content of a report, written by the current or a previous version of the tool
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Report {
+    Versioned(JsonReport),
+    // Reports written before they had a format version
+    Flat(Vec<DetectedBrokenLink>),
+    Grouped { findings: Vec<DetectedBrokenLink> },
+}
+
+/// Load a report written with `--format json` (with or without `--group-by-target`)
+///
+/// Reports written by previous versions of the tool are supported, but not the ones with a newer format version.
+pub fn load_report(path: &Path) -> Result<Vec<DetectedBrokenLink>, String> {
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        format!(
+            "Failed to read the report at '{}': {}",
+            safe_canonicalize(path).green(),
+            err
+        )
+    })?;
+
+    let report = serde_json::from_str(&content).map_err(|err| {
+        format!(
+            "Failed to parse the report at '{}': {}",
+            safe_canonicalize(path).green(),
+            err
+        )
+    })?;
+
+    match report {
+        Report::Versioned(report) if report.format_version > REPORT_FORMAT_VERSION => Err(format!(
+            "Report at '{}' has format version {} (written by version {} of the tool), but only versions up to {} are supported",
+            safe_canonicalize(path).green(),
+            report.format_version,
+            report.tool_version,
+            REPORT_FORMAT_VERSION
+        )),
+        Report::Versioned(report) => Ok(report.findings),
+        Report::Flat(findings) | Report::Grouped { findings } => Ok(findings),
+    }
+}
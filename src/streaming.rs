@@ -0,0 +1,84 @@
+use crate::{
+    check_broken_links_with, check_dir_with_callback, CheckerOptions, DetectedBrokenLink,
+    FileLinksCache, TargetsCache,
+};
+use std::path::Path;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::Stream;
+
+/// Check broken links in a Markdown file or directory, providing them as a stream
+///
+/// Directories are checked recursively, like with [`check_broken_links_with_callback`](crate::check_broken_links_with_callback):
+///  the broken links of each file are yielded as soon as it's checked (or once all files are checked, when they are checked in parallel),
+///  and the stream ends once all files are checked. If the check fails, the error is yielded as the stream's last item.
+///
+/// Files are checked on Tokio's blocking threads, so this function must be called from within a Tokio runtime.
+/// Dropping the stream stops the check once the file being checked is done.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{check_broken_links_streaming, CheckerOptions};
+/// use tokio_stream::StreamExt;
+///
+/// # async fn check() {
+/// let mut stream = check_broken_links_streaming(Path::new("docs"), CheckerOptions::default());
+///
+/// while let Some(result) = stream.next().await {
+///     match result {
+///         Ok(error) => println!("{}:{}: {}", error.file.display(), error.line, error.error),
+///         Err(err) => eprintln!("Check failed: {}", err),
+///     }
+/// }
+/// # }
+/// ```
+pub fn check_broken_links_streaming(
+    path: &Path,
+    options: CheckerOptions,
+) -> impl Stream<Item = Result<DetectedBrokenLink, String>> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let path = path.to_owned();
+
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = send_broken_links(&path, &options, &sender) {
+            // The stream may have been dropped in the meantime
+            let _ = sender.send(Err(err));
+        }
+    });
+
+    UnboundedReceiverStream::new(receiver)
+}
+
+/// Check broken links in a Markdown file or directory, sending them to a stream until it's dropped
+fn send_broken_links(
+    path: &Path,
+    options: &CheckerOptions,
+    sender: &UnboundedSender<Result<DetectedBrokenLink, String>>,
+) -> Result<(), String> {
+    let mut links_cache = FileLinksCache::new();
+    let mut targets_cache = TargetsCache::new();
+
+    // The stream may have been dropped in the meantime
+    let mut send = |error| {
+        let _ = sender.send(Ok(error));
+    };
+
+    if !path.is_dir() {
+        check_broken_links_with(path, false, options, &mut links_cache, &mut targets_cache)?
+            .into_iter()
+            .for_each(send);
+
+        return Ok(());
+    }
+
+    check_dir_with_callback(
+        path,
+        options,
+        &mut links_cache,
+        &mut targets_cache,
+        &mut send,
+        &|| sender.is_closed(),
+    )
+}
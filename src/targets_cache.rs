@@ -0,0 +1,454 @@
+use crate::check_stats::CheckStats;
+use crate::link_graph::LinkGraph;
+use crate::profile::FileProfile;
+use crate::{
+    absolute_lexical_path, build_slug_lookup, draft_marker, front_matter_value,
+    inline_anchor_aliases, read_text_file, safe_canonicalize,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A link's target resolved on the filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedTarget {
+    /// Canonical path of the target
+    pub canonical: PathBuf,
+
+    /// Is the target a file (else it's a directory)?
+    pub is_file: bool,
+}
+
+impl ResolvedTarget {
+    /// Resolve a target on the filesystem, returning `None` if it does not exist
+    pub fn resolve(target: &Path) -> Option<Self> {
+        let canonical = std::fs::canonicalize(safe_canonicalize(target)).ok()?;
+        let is_file = canonical.is_file();
+
+        Some(Self { canonical, is_file })
+    }
+}
+
+/// Number of anchors from which a target's anchors are looked up in a map instead of being searched linearly
+static SLUG_LOOKUP_THRESHOLD: usize = 20;
+
+/// Lookup map of the anchors of a target, with the anchors it was built from
+#[derive(Debug, Clone)]
+struct SlugLookup {
+    /// Anchors the lookup map was built from, to detect when they change
+    anchors: Arc<Vec<String>>,
+
+    /// Index of each anchor (see [`build_slug_lookup`])
+    slugs: HashMap<String, usize>,
+}
+
+impl SlugLookup {
+    fn new(anchors: &Arc<Vec<String>>) -> Self {
+        Self {
+            anchors: Arc::clone(anchors),
+            slugs: build_slug_lookup(anchors),
+        }
+    }
+}
+
+/// Cache of the resolution of links' targets on the filesystem, including the targets which do not exist
+///
+/// It avoids canonicalizing and checking the type of targets which are linked to many times during a run.
+/// Targets are indexed by their absolute and lexically canonicalized path (see [`safe_canonicalize`]), so all the ways
+///  to write a target's path (e.g. `./a.md`, `dir/../a.md` or an absolute path) share the same entry.
+///
+/// It also stores lookup maps of the anchors of targets which have many of them (see [`build_slug_lookup`]),
+///  the targets of the files which are redirects (see [`front_matter_value`]) and the files which are drafts (see [`draft_marker`]).
+///
+/// Checks also record in it what they found about the checked files, like the links between them (see [`TargetsCache::link_graph`])
+///  their number (see [`TargetsCache::take_check_stats`]) and their profiles (see [`TargetsCache::take_file_profiles`]).
+///
+/// The cache is never invalidated automatically: it must be cleared if files are created, moved or removed between two checks.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use broken_md_links::TargetsCache;
+///
+/// let mut cache = TargetsCache::new();
+///
+/// assert!(cache.resolve(Path::new("src/lib.rs")).unwrap().is_file);
+/// assert!(cache.resolve(Path::new("src/../src/lib.rs")).is_some());
+/// assert!(cache.resolve(&std::env::current_dir().unwrap().join("src/lib.rs")).is_some());
+/// assert!(cache.resolve(Path::new("missing.md")).is_none());
+/// assert!(cache.resolve(Path::new("./missing.md")).is_none());
+///
+/// assert_eq!((cache.hits(), cache.misses()), (3, 2));
+/// assert_eq!(cache.hit_rate(), 0.6);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TargetsCache {
+    /// Resolved targets (`None` if they don't exist)
+    targets: HashMap<PathBuf, Option<ResolvedTarget>>,
+
+    /// Number of resolutions got from the cache
+    hits: usize,
+
+    /// Number of resolutions which required to access the filesystem
+    misses: usize,
+
+    /// Lookup maps of the anchors of targets with many anchors, indexed by canonical path
+    slug_lookups: HashMap<PathBuf, SlugLookup>,
+
+    /// Targets of the files which are redirects (`None` if they aren't), indexed by canonical path
+    redirects: HashMap<PathBuf, Option<String>>,
+
+    /// Markers of the files which are drafts (`None` if they aren't), indexed by canonical path
+    drafts: HashMap<PathBuf, Option<String>>,
+
+    /// Number of lines of the files targeted by links to one of their lines, indexed by canonical path
+    line_counts: HashMap<PathBuf, usize>,
+
+    /// Results of the checks of HTTP and HTTPS links
+    #[cfg(feature = "check-http")]
+    urls: crate::UrlCheckCache,
+
+    /// Agent sending the requests of the checks of HTTP and HTTPS links, built on the first check
+    #[cfg(feature = "check-http")]
+    http_agent: Option<ureq::Agent>,
+
+    /// Anchor aliases declared in files, indexed by canonical path
+    anchor_aliases: HashMap<PathBuf, HashMap<String, String>>,
+
+    /// Markdown files linked to by each checked file
+    link_graph: LinkGraph,
+
+    /// Profiles of the files checked with this cache (see [`CheckerOptions::profile`](crate::CheckerOptions::profile))
+    profiles: Vec<FileProfile>,
+
+    /// Number of files and links checked with this cache
+    check_stats: CheckStats,
+}
+
+impl TargetsCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve a target, from the cache if it was already resolved
+    pub fn resolve(&mut self, target: &Path) -> Option<ResolvedTarget> {
+        let key = PathBuf::from(absolute_lexical_path(target));
+
+        if let Some(resolved) = self.targets.get(&key) {
+            self.hits += 1;
+            return resolved.clone();
+        }
+
+        self.misses += 1;
+
+        let resolved = ResolvedTarget::resolve(target);
+        self.targets.insert(key, resolved.clone());
+        resolved
+    }
+
+    /// Check if an anchor is part of a target's anchors
+    ///
+    /// For targets with many anchors, a lookup map is built once and then used for all next checks.
+    /// The lookup map is built again when different anchors are provided for the target, e.g. because it changed
+    ///  or because it's linked to from directories with different slug options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use std::sync::Arc;
+    /// use broken_md_links::TargetsCache;
+    ///
+    /// let anchors = Arc::new((0..100).map(|i| format!("header-{}", i)).collect::<Vec<_>>());
+    /// let mut cache = TargetsCache::new();
+    ///
+    /// assert!(cache.contains_anchor(Path::new("/docs/hub.md"), &anchors, "header-42"));
+    /// assert!(!cache.contains_anchor(Path::new("/docs/hub.md"), &anchors, "header-100"));
+    ///
+    /// let anchors = Arc::new((0..101).map(|i| format!("header-{}", i)).collect::<Vec<_>>());
+    ///
+    /// assert!(cache.contains_anchor(Path::new("/docs/hub.md"), &anchors, "header-100"));
+    /// ```
+    pub fn contains_anchor(
+        &mut self,
+        canonical: &Path,
+        anchors: &Arc<Vec<String>>,
+        anchor: &str,
+    ) -> bool {
+        if anchors.len() <= SLUG_LOOKUP_THRESHOLD {
+            return anchors.iter().any(|candidate| candidate == anchor);
+        }
+
+        let lookup = self
+            .slug_lookups
+            .entry(canonical.to_owned())
+            .or_insert_with(|| SlugLookup::new(anchors));
+
+        if !Arc::ptr_eq(&lookup.anchors, anchors) {
+            *lookup = SlugLookup::new(anchors);
+        }
+
+        lookup.slugs.contains_key(anchor)
+    }
+
+    /// Get the target of a file if it's a redirect, i.e. if its front matter contains one of the provided markers
+    ///
+    /// The provided markers must always be the same, until the cache is cleared.
+    pub fn redirect_target(&mut self, canonical: &Path, markers: &[String]) -> Option<String> {
+        self.redirects
+            .entry(canonical.to_owned())
+            .or_insert_with(|| {
+                read_text_file(canonical)
+                    .ok()
+                    .and_then(|(content, _)| front_matter_value(&content, markers))
+            })
+            .clone()
+    }
+
+    /// Get the marker of a file if it's a draft which won't be published (see [`draft_marker`])
+    ///
+    /// The provided markers must always be the same, until the cache is cleared.
+    pub fn draft_marker(
+        &mut self,
+        canonical: &Path,
+        markers: &[(String, String)],
+    ) -> Option<String> {
+        self.drafts
+            .entry(canonical.to_owned())
+            .or_insert_with(|| {
+                read_text_file(canonical)
+                    .ok()
+                    .and_then(|(content, _)| draft_marker(&content, markers))
+            })
+            .clone()
+    }
+
+    /// Get the number of lines of a file, from the cache if it was already counted (e.g. for links like `#L42`)
+    ///
+    /// Files are only read once, until the cache is cleared.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::TargetsCache;
+    ///
+    /// let mut cache = TargetsCache::new();
+    /// let file = Path::new("doc.md").canonicalize().unwrap();
+    ///
+    /// // The file is only read once, until the cache is cleared
+    /// println!("{} line(s)", cache.line_count(&file).unwrap());
+    /// ```
+    pub fn line_count(&mut self, canonical: &Path) -> std::io::Result<usize> {
+        if let Some(count) = self.line_counts.get(canonical) {
+            return Ok(*count);
+        }
+
+        let (content, _) = read_text_file(canonical)?;
+        let count = content.lines().count();
+
+        self.line_counts.insert(canonical.to_owned(), count);
+        Ok(count)
+    }
+
+    /// Check an HTTP or HTTPS link, from the cache if it was already checked (see [`validate_http_link`](crate::validate::validate_http_link))
+    ///
+    /// All checks share the same HTTP agent, so connections to the same hosts are reused.
+    /// The provided redirects handling and timeout must always be the same, until the cache is cleared.
+    #[cfg(feature = "check-http")]
+    pub fn check_url(
+        &mut self,
+        url: &str,
+        follow_redirects: bool,
+        timeout: std::time::Duration,
+    ) -> crate::validate::ValidationResult {
+        let agent = &mut self.http_agent;
+
+        self.urls
+            .entry(url.to_owned())
+            .or_insert_with(|| {
+                let agent = agent
+                    .get_or_insert_with(|| crate::validate::http_agent(follow_redirects, timeout));
+
+                crate::validate::validate_http_link_with(agent, url)
+            })
+            .clone()
+    }
+
+    /// Get the alias of an anchor declared in a file (see [`inline_anchor_aliases`])
+    pub fn inline_anchor_alias(&mut self, canonical: &Path, anchor: &str) -> Option<String> {
+        self.inline_anchor_aliases(canonical).get(anchor).cloned()
+    }
+
+    /// Get all the anchor aliases declared in a file (see [`inline_anchor_aliases`])
+    pub(crate) fn inline_anchor_aliases(&mut self, canonical: &Path) -> &HashMap<String, String> {
+        self.anchor_aliases
+            .entry(canonical.to_owned())
+            .or_insert_with(|| {
+                read_text_file(canonical)
+                    .map(|(content, _)| inline_anchor_aliases(&content))
+                    .unwrap_or_default()
+            })
+    }
+
+    /// Register a checked file in the links graph
+    pub(crate) fn record_file(&mut self, canonical: &Path) {
+        self.link_graph.entry(canonical.to_owned()).or_default();
+    }
+
+    /// Register a link between two Markdown files in the links graph
+    pub(crate) fn record_link(&mut self, source: &Path, target: &Path) {
+        self.link_graph
+            .entry(source.to_owned())
+            .or_default()
+            .insert(target.to_owned());
+    }
+
+    /// Get the graph of the links between the Markdown files checked with this cache, by canonical path
+    ///
+    /// Each checked file is part of the graph, even if it doesn't link to any Markdown file.
+    /// See [`analyze_link_graph`](crate::link_graph::analyze_link_graph) to analyze it.
+    pub fn link_graph(&self) -> &LinkGraph {
+        &self.link_graph
+    }
+
+    /// Count a checked file, with the number of links it contains
+    pub(crate) fn record_checked_file(&mut self, link_count: usize) {
+        self.check_stats.files += 1;
+        self.check_stats.links += link_count;
+    }
+
+    /// Get the number of files and links checked with this cache so far, and reset them
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{check_broken_links_with, CheckerOptions, FileLinksCache, TargetsCache};
+    ///
+    /// let mut targets_cache = TargetsCache::new();
+    /// check_broken_links_with(Path::new("docs"), true, &CheckerOptions::default(), &mut FileLinksCache::new(), &mut targets_cache).unwrap();
+    ///
+    /// let stats = targets_cache.take_check_stats();
+    /// println!("{} link(s) checked in {} file(s)", stats.links, stats.files);
+    /// ```
+    pub fn take_check_stats(&mut self) -> CheckStats {
+        std::mem::take(&mut self.check_stats)
+    }
+
+    /// Store the profile of a checked file
+    pub(crate) fn record_profile(&mut self, profile: FileProfile) {
+        self.profiles.push(profile);
+    }
+
+    /// Get the profiles of the files checked with this cache so far, sorted by path, and forget them
+    ///
+    /// Files are only profiled when the [`CheckerOptions::profile`](crate::CheckerOptions::profile) option is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use broken_md_links::{check_broken_links_with, CheckerOptions, FileLinksCache, TargetsCache};
+    ///
+    /// let options = CheckerOptions { profile: true, ..CheckerOptions::default() };
+    /// let mut targets_cache = TargetsCache::new();
+    ///
+    /// check_broken_links_with(Path::new("docs"), true, &options, &mut FileLinksCache::new(), &mut targets_cache).unwrap();
+    ///
+    /// for profile in targets_cache.take_file_profiles() {
+    ///     println!("{}: {:.2} ms", profile.file.display(), profile.read_ms + profile.link_check_ms);
+    /// }
+    /// ```
+    pub fn take_file_profiles(&mut self) -> Vec<FileProfile> {
+        let mut profiles = std::mem::take(&mut self.profiles);
+        profiles.sort_by(|a, b| a.file.cmp(&b.file));
+        profiles
+    }
+
+    /// Add the content and statistics of another cache to this one (entries already present in this one are kept)
+    pub fn merge(&mut self, other: TargetsCache) {
+        for (target, resolved) in other.targets {
+            self.targets.entry(target).or_insert(resolved);
+        }
+
+        for (target, lookup) in other.slug_lookups {
+            self.slug_lookups.entry(target).or_insert(lookup);
+        }
+
+        for (target, redirect) in other.redirects {
+            self.redirects.entry(target).or_insert(redirect);
+        }
+
+        for (target, draft) in other.drafts {
+            self.drafts.entry(target).or_insert(draft);
+        }
+
+        for (target, count) in other.line_counts {
+            self.line_counts.entry(target).or_insert(count);
+        }
+
+        #[cfg(feature = "check-http")]
+        {
+            for (url, result) in other.urls {
+                self.urls.entry(url).or_insert(result);
+            }
+
+            if self.http_agent.is_none() {
+                self.http_agent = other.http_agent;
+            }
+        }
+
+        for (target, aliases) in other.anchor_aliases {
+            self.anchor_aliases.entry(target).or_insert(aliases);
+        }
+
+        for (source, targets) in other.link_graph {
+            self.link_graph.entry(source).or_default().extend(targets);
+        }
+
+        self.profiles.extend(other.profiles);
+
+        self.check_stats.files += other.check_stats.files;
+        self.check_stats.links += other.check_stats.links;
+
+        self.hits += other.hits;
+        self.misses += other.misses;
+    }
+
+    /// Forget all resolved targets, anchor lookups, redirects, drafts, line counts, checked URLs, anchor aliases and the links graph (statistics are kept)
+    pub fn clear(&mut self) {
+        self.link_graph.clear();
+        self.targets.clear();
+        self.slug_lookups.clear();
+        self.redirects.clear();
+        self.drafts.clear();
+        self.line_counts.clear();
+        #[cfg(feature = "check-http")]
+        {
+            self.urls.clear();
+            self.http_agent = None;
+        }
+        self.anchor_aliases.clear();
+    }
+
+    /// Get the number of resolutions got from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Get the number of resolutions which required to access the filesystem
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Get the proportion of resolutions got from the cache, between 0 and 1
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
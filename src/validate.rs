@@ -0,0 +1,655 @@
+//! Validators for each type of link
+//!
+//! These functions are the building blocks used by the checker, and can be composed to build custom validators.
+//! They don't display anything: it's up to the caller to report the result of the validation.
+
+use crate::{
+    generate_shared_anchors, safe_canonicalize, FileLinksCache, Fragment, ResolvedTarget,
+    SlugOptions, TargetsCache,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Extensions of the files considered as images (lowercase)
+static IMAGE_EXTENSIONS: &[&str] = &[
+    "apng", "avif", "bmp", "gif", "ico", "jpeg", "jpg", "png", "svg", "tif", "tiff", "webp",
+];
+
+/// Default timeout of the requests sent to check HTTP and HTTPS links (see [`CheckerOptions::http_timeout`](crate::CheckerOptions::http_timeout))
+pub static DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of a link's validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationResult {
+    /// The link is valid
+    Valid,
+
+    /// The link is broken or invalid, with the reason why
+    Broken(String),
+
+    /// The link is valid but suspicious, with the reason why
+    Warning(String),
+}
+
+/// Describe why a target which can't be resolved is missing
+///
+/// Symbolic links pointing to a nonexistent path (dead symlinks) exist themselves, so their target is mentioned instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use broken_md_links::validate::missing_target_reason;
+///
+/// assert_eq!(missing_target_reason(Path::new("docs/../missing.md")), "path 'missing.md' does not exist");
+/// ```
+pub fn missing_target_reason(target: &Path) -> String {
+    let target_canon = safe_canonicalize(target);
+
+    match std::fs::read_link(&target_canon) {
+        Ok(link_target) => format!(
+            "path '{}' is a dead symlink to '{}'",
+            target_canon,
+            safe_canonicalize(&link_target)
+        ),
+        Err(_) => format!("path '{}' does not exist", target_canon),
+    }
+}
+
+/// Validate a link pointing to a file or directory
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use broken_md_links::validate::{validate_file_link, ValidationResult};
+///
+/// assert_eq!(validate_file_link(Path::new("src")), ValidationResult::Valid);
+/// assert_eq!(
+///     validate_file_link(Path::new("src/../missing.md")),
+///     ValidationResult::Broken("broken link found: path 'missing.md' does not exist".to_owned())
+/// );
+/// ```
+pub fn validate_file_link(target: &Path) -> ValidationResult {
+    let target_canon = safe_canonicalize(target);
+
+    match std::fs::canonicalize(&target_canon) {
+        Ok(_) => ValidationResult::Valid,
+        Err(_) => ValidationResult::Broken(format!(
+            "broken link found: {}",
+            missing_target_reason(target)
+        )),
+    }
+}
+
+/// Validate a link pointing to a specific header of a Markdown file (e.g. `file.md#some-header`)
+///
+/// The target's headers are got from the provided cache, which is filled if the target is not already in it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::FileLinksCache;
+/// use broken_md_links::validate::{validate_header_link, ValidationResult};
+///
+/// match validate_header_link(Path::new("doc.md"), "installation", &mut FileLinksCache::new()) {
+///     ValidationResult::Valid => {}
+///     ValidationResult::Broken(error) => eprintln!("error: {}", error),
+///     ValidationResult::Warning(warning) => eprintln!("warning: {}", warning),
+/// }
+/// ```
+pub fn validate_header_link(
+    target: &Path,
+    fragment: &str,
+    cache: &mut FileLinksCache,
+) -> ValidationResult {
+    validate_header_link_with(target, fragment, &SlugOptions::default(), cache)
+}
+
+/// Validate a link pointing to a specific header of a Markdown file, with custom slug options
+///
+/// The identifiers of the target's HTML elements (e.g. `<a id="some-header"></a>`) are accepted as well.
+pub fn validate_header_link_with(
+    target: &Path,
+    fragment: &str,
+    slug_options: &SlugOptions,
+    cache: &mut FileLinksCache,
+) -> ValidationResult {
+    match target_anchors(target, slug_options, cache) {
+        Err(err) => ValidationResult::Broken(err),
+        Ok(anchors) if anchors.iter().any(|anchor| anchor == fragment) => ValidationResult::Valid,
+        Ok(_) => ValidationResult::Broken(format!(
+            "broken link found: header '{}' not found in '{}'",
+            fragment,
+            safe_canonicalize(target)
+        )),
+    }
+}
+
+/// Get the anchors of a link's target from the provided cache, which is filled if the target is not already in it
+/// Returns an error message if the target is not a file or if its anchors can't be generated
+fn target_anchors(
+    target: &Path,
+    slug_options: &SlugOptions,
+    cache: &mut FileLinksCache,
+) -> Result<Arc<Vec<String>>, String> {
+    let target_canon = safe_canonicalize(target);
+
+    // The target must be a file
+    if !target.is_file() {
+        return Err(format!(
+            "invalid header link found: path '{}' exists but is not a file",
+            target_canon
+        ));
+    }
+
+    // Canonicalize properly the target path to avoid irregularities in cache's keys
+    //  like 'dir/../file.md' and 'file.md' which are identical but do not have the same Path representation
+    let resolved = ResolvedTarget {
+        canonical: target
+            .canonicalize()
+            .map_err(|err| format!("failed to canonicalize path '{}': {}", target_canon, err))?,
+        is_file: true,
+    };
+
+    resolved_target_anchors(target, &resolved, slug_options, cache)
+}
+
+/// Get the anchors of an already resolved link's target from the provided cache, which is filled if the target is not already in it
+/// Returns an error message if the target is not a file or if its anchors can't be generated
+fn resolved_target_anchors(
+    target: &Path,
+    resolved: &ResolvedTarget,
+    slug_options: &SlugOptions,
+    cache: &mut FileLinksCache,
+) -> Result<Arc<Vec<String>>, String> {
+    let target_canon = safe_canonicalize(target);
+
+    // The target must be a file
+    if !resolved.is_file {
+        return Err(format!(
+            "invalid header link found: path '{}' exists but is not a file",
+            target_canon
+        ));
+    }
+
+    // If the target file is not already in cache, get all its anchors
+    // We do not use the fully canonicalized path to not force displaying an absolute path
+    cache
+        .get_or_try_insert_with(&resolved.canonical, || {
+            generate_shared_anchors(target, slug_options, cache)
+        })
+        .map_err(|err| {
+            format!(
+                "failed to generate slugs for file '{}': {}",
+                target_canon, err
+            )
+        })
+}
+
+/// Validate a link pointing to a specific location of a file, depending on the fragment's type
+///
+/// * Slugs must be the slug of a header of the target file, or the identifier of one of its HTML elements
+/// * HTML identifiers must be the `id` or `name` attribute of one of the target file's HTML elements
+///
+/// Slugs and HTML identifiers are got from the provided cache, which is filled if the target is not already in it.
+/// * Line numbers must not exceed the number of lines of the target file
+/// * Unknown fragments can't be validated and result in a warning
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::{FileLinksCache, Fragment, SlugOptions};
+/// use broken_md_links::validate::{validate_fragment_link, ValidationResult};
+///
+/// let fragment = Fragment::parse("L12");
+///
+/// match validate_fragment_link(Path::new("doc.md"), &fragment, &SlugOptions::default(), &mut FileLinksCache::new()) {
+///     ValidationResult::Valid => {}
+///     ValidationResult::Broken(error) => eprintln!("error: {}", error),
+///     ValidationResult::Warning(warning) => eprintln!("warning: {}", warning),
+/// }
+/// ```
+pub fn validate_fragment_link(
+    target: &Path,
+    fragment: &Fragment,
+    slug_options: &SlugOptions,
+    cache: &mut FileLinksCache,
+) -> ValidationResult {
+    match ResolvedTarget::resolve(target) {
+        Some(resolved) => validate_resolved_fragment_link(
+            target,
+            &resolved,
+            fragment,
+            slug_options,
+            cache,
+            &mut TargetsCache::new(),
+        ),
+        None => ValidationResult::Broken(format!(
+            "broken link found: {}",
+            missing_target_reason(target)
+        )),
+    }
+}
+
+/// Validate a link pointing to a specific location of a file which has already been resolved (e.g. with a [`TargetsCache`])
+///
+/// The provided targets cache is used to look up the anchors of targets which have many of them.
+pub fn validate_resolved_fragment_link(
+    target: &Path,
+    resolved: &ResolvedTarget,
+    fragment: &Fragment,
+    slug_options: &SlugOptions,
+    cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+) -> ValidationResult {
+    let target_canon = safe_canonicalize(target);
+
+    match fragment {
+        Fragment::Slug(slug) => {
+            match resolved_target_anchors(target, resolved, slug_options, cache) {
+                Err(err) => ValidationResult::Broken(err),
+                Ok(anchors)
+                    if targets_cache.contains_anchor(&resolved.canonical, &anchors, slug) =>
+                {
+                    ValidationResult::Valid
+                }
+                Ok(_) => ValidationResult::Broken(format!(
+                    "broken link found: header '{}' not found in '{}'",
+                    slug, target_canon
+                )),
+            }
+        }
+
+        Fragment::HtmlId(id) => {
+            match resolved_target_anchors(target, resolved, slug_options, cache) {
+                Err(err) => ValidationResult::Broken(err),
+                Ok(anchors) if targets_cache.contains_anchor(&resolved.canonical, &anchors, id) => {
+                    ValidationResult::Valid
+                }
+                Ok(_) => ValidationResult::Broken(format!(
+                    "broken link found: HTML anchor '{}' not found in '{}'",
+                    id, target_canon
+                )),
+            }
+        }
+
+        Fragment::LineNumber(line) => match targets_cache.line_count(&resolved.canonical) {
+            Ok(line_count) if *line <= line_count => ValidationResult::Valid,
+            Ok(line_count) => ValidationResult::Broken(format!(
+                "broken link found: line {} not found in '{}', which has {} lines",
+                line, target_canon, line_count
+            )),
+            Err(err) => ValidationResult::Broken(format!(
+                "invalid line link found: failed to read file '{}': {}",
+                target_canon, err
+            )),
+        },
+
+        Fragment::Unknown(fragment) => ValidationResult::Warning(format!(
+            "fragment '{}' of a link to '{}' has no recognized form and can't be checked",
+            fragment, target_canon
+        )),
+    }
+}
+
+/// How an anchor which doesn't exist differs from an existing one (see [`anchor_near_miss`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum AnchorNearMiss {
+    /// Only the casing differs (e.g. `#Getting-Started` for `#getting-started`)
+    Case,
+
+    /// Only repeated, leading or trailing dashes differ (e.g. `#setup--usage` for `#setup-usage`)
+    Punctuation,
+
+    /// One is the plural of the other (e.g. `#faqs` for `#faq`)
+    Plural,
+}
+
+/// Remove the differences of punctuation between two slugs: casing, repeated dashes, and leading or trailing dashes
+fn normalize_slug_punctuation(slug: &str) -> String {
+    slug.to_lowercase()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Find the existing anchor a link to an anchor which doesn't exist is very likely meant to point to
+///
+/// Near misses are looked for in this order: casing, then punctuation, then plural (an `s` or `es` suffix),
+///  the latter two ignoring the casing. The first matching anchor is returned, in the order of the provided anchors.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::validate::{anchor_near_miss, AnchorNearMiss};
+///
+/// let anchors = vec!["getting-started".to_owned(), "setup-usage".to_owned(), "faq".to_owned()];
+///
+/// assert_eq!(anchor_near_miss("Getting-Started", &anchors), Some((AnchorNearMiss::Case, "getting-started")));
+/// assert_eq!(anchor_near_miss("setup--usage-", &anchors), Some((AnchorNearMiss::Punctuation, "setup-usage")));
+/// assert_eq!(anchor_near_miss("FAQs", &anchors), Some((AnchorNearMiss::Plural, "faq")));
+/// assert_eq!(anchor_near_miss("installation", &anchors), None);
+/// ```
+pub fn anchor_near_miss<'a>(
+    anchor: &str,
+    anchors: &'a [String],
+) -> Option<(AnchorNearMiss, &'a str)> {
+    let lowercase = anchor.to_lowercase();
+
+    if let Some(candidate) = anchors
+        .iter()
+        .find(|candidate| *candidate != anchor && candidate.to_lowercase() == lowercase)
+    {
+        return Some((AnchorNearMiss::Case, candidate));
+    }
+
+    let normalized = normalize_slug_punctuation(anchor);
+
+    if normalized.is_empty() {
+        return None;
+    }
+
+    let normalized_anchors = anchors
+        .iter()
+        .map(|candidate| (candidate, normalize_slug_punctuation(candidate)))
+        .collect::<Vec<_>>();
+
+    if let Some((candidate, _)) =
+        normalized_anchors
+            .iter()
+            .find(|(candidate, normalized_candidate)| {
+                *candidate != anchor && *normalized_candidate == normalized
+            })
+    {
+        return Some((AnchorNearMiss::Punctuation, candidate));
+    }
+
+    let is_plural_of = |plural: &str, singular: &str| {
+        [plural.strip_suffix('s'), plural.strip_suffix("es")].contains(&Some(singular))
+    };
+
+    normalized_anchors
+        .iter()
+        .find(|(_, normalized_candidate)| {
+            !normalized_candidate.is_empty()
+                && (is_plural_of(&normalized, normalized_candidate)
+                    || is_plural_of(normalized_candidate, &normalized))
+        })
+        .map(|(candidate, _)| (AnchorNearMiss::Plural, candidate.as_str()))
+}
+
+/// Validate a link pointing to a specific location of the file it's located in (e.g. `#installation`)
+///
+/// The file's anchors and number of lines are provided, as they are already known when the file is being checked.
+///
+/// # Examples
+///
+/// ```
+/// use broken_md_links::Fragment;
+/// use broken_md_links::validate::{validate_same_file_fragment, ValidationResult};
+///
+/// let anchors = vec!["installation".to_owned(), "Section_2".to_owned()];
+///
+/// assert_eq!(validate_same_file_fragment(&Fragment::parse("installation"), &anchors, 10), ValidationResult::Valid);
+/// assert_eq!(validate_same_file_fragment(&Fragment::parse("Section_2"), &anchors, 10), ValidationResult::Valid);
+/// assert_eq!(validate_same_file_fragment(&Fragment::parse("L10"), &anchors, 10), ValidationResult::Valid);
+///
+/// assert_eq!(
+///     validate_same_file_fragment(&Fragment::parse("usage"), &anchors, 10),
+///     ValidationResult::Broken("broken link found: header 'usage' not found in this file".to_owned())
+/// );
+///
+/// assert_eq!(
+///     validate_same_file_fragment(&Fragment::parse("L11"), &anchors, 10),
+///     ValidationResult::Broken("broken link found: line 11 not found in this file, which has 10 lines".to_owned())
+/// );
+/// ```
+pub fn validate_same_file_fragment(
+    fragment: &Fragment,
+    anchors: &[String],
+    line_count: usize,
+) -> ValidationResult {
+    match fragment {
+        Fragment::Slug(anchor) | Fragment::HtmlId(anchor)
+            if anchors.iter().any(|candidate| candidate == anchor) =>
+        {
+            ValidationResult::Valid
+        }
+
+        Fragment::Slug(slug) => ValidationResult::Broken(format!(
+            "broken link found: header '{}' not found in this file",
+            slug
+        )),
+
+        Fragment::HtmlId(id) => ValidationResult::Broken(format!(
+            "broken link found: HTML anchor '{}' not found in this file",
+            id
+        )),
+
+        Fragment::LineNumber(line) if *line <= line_count => ValidationResult::Valid,
+
+        Fragment::LineNumber(line) => ValidationResult::Broken(format!(
+            "broken link found: line {} not found in this file, which has {} lines",
+            line, line_count
+        )),
+
+        Fragment::Unknown(fragment) => ValidationResult::Warning(format!(
+            "fragment '{}' of a link to this file has no recognized form and can't be checked",
+            fragment
+        )),
+    }
+}
+
+/// Validate a link pointing to an image
+///
+/// The target must be an existing file. A warning is returned if its extension is not a known image extension.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use broken_md_links::validate::{validate_image_link, ValidationResult};
+///
+/// assert!(matches!(validate_image_link(Path::new("src")), ValidationResult::Broken(_)));
+/// assert!(matches!(validate_image_link(Path::new("missing.png")), ValidationResult::Broken(_)));
+/// assert!(matches!(validate_image_link(Path::new("Cargo.toml")), ValidationResult::Warning(_)));
+/// ```
+pub fn validate_image_link(target: &Path) -> ValidationResult {
+    let target_canon = safe_canonicalize(target);
+
+    match std::fs::canonicalize(&target_canon) {
+        Err(_) => ValidationResult::Broken(format!(
+            "broken image found: {}",
+            missing_target_reason(target)
+        )),
+
+        Ok(path) if !path.is_file() => ValidationResult::Broken(format!(
+            "invalid image found: path '{}' is not a file",
+            target_canon
+        )),
+
+        Ok(_) => {
+            let is_image = target
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+                .unwrap_or(false);
+
+            if is_image {
+                ValidationResult::Valid
+            } else {
+                ValidationResult::Warning(format!(
+                    "image path '{}' does not have a known image extension",
+                    target_canon
+                ))
+            }
+        }
+    }
+}
+
+/// Validate the content of an image file, which must be decodable in the format its extension stands for
+///
+/// Images whose format is not known or not supported (e.g. SVG) are considered valid,
+///  and images which can't be decoded are reported with a warning (as the link itself is valid).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use broken_md_links::validate::{validate_image_content, ValidationResult};
+///
+/// if let ValidationResult::Warning(warning) = validate_image_content(Path::new("images/logo.png")) {
+///     eprintln!("warning: {}", warning);
+/// }
+/// ```
+#[cfg(feature = "image-check")]
+pub fn validate_image_content(target: &Path) -> ValidationResult {
+    use image::{ImageFormat, ImageReader};
+
+    let target_canon = safe_canonicalize(target);
+
+    let expected = match ImageFormat::from_path(target) {
+        Ok(format) if format.reading_enabled() => format,
+        _ => return ValidationResult::Valid,
+    };
+
+    let format_name = |format: ImageFormat| format.extensions_str()[0].to_uppercase();
+
+    let reader = match ImageReader::open(target).and_then(ImageReader::with_guessed_format) {
+        Ok(reader) => reader,
+        Err(err) => {
+            return ValidationResult::Warning(format!(
+                "failed to read image '{}': {}",
+                target_canon, err
+            ))
+        }
+    };
+
+    // The format is guessed from the image's content, or from its extension if the content is not recognized
+    match reader.format() {
+        Some(detected) if detected != expected => ValidationResult::Warning(format!(
+            "image '{}' is a {} image but its extension is for {} images",
+            target_canon,
+            format_name(detected),
+            format_name(expected)
+        )),
+
+        _ => match reader.decode() {
+            Ok(_) => ValidationResult::Valid,
+            Err(err) => ValidationResult::Warning(format!(
+                "image '{}' is not a valid {} image: {}",
+                target_canon,
+                format_name(expected),
+                err
+            )),
+        },
+    }
+}
+
+/// Validate a link pointing to an HTTP or HTTPS URL, by sending it a `HEAD` request
+///
+/// Servers which don't support `HEAD` requests (status 405 or 501) are sent a `GET` request instead.
+/// URLs answering with a 4xx or 5xx status are broken. Redirects are followed if asked to, else they are returned as a warning.
+/// A warning is returned as well if the URL can't be reached (e.g. its host doesn't exist or the request timed out).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "check-http")] {
+/// # use std::io::{BufRead, BufReader, Write};
+/// # use std::net::TcpListener;
+/// use std::time::Duration;
+/// use broken_md_links::validate::{validate_http_link, ValidationResult};
+///
+/// # let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+/// # let host = format!("http://{}", listener.local_addr().unwrap());
+/// # std::thread::spawn(move || {
+/// #     for stream in listener.incoming() {
+/// #         let mut stream = stream.unwrap();
+/// #         let mut request = String::new();
+/// #         BufReader::new(&stream).read_line(&mut request).unwrap();
+/// #         let response = match request.split(' ').nth(1).unwrap() {
+/// #             "/docs" => "200 OK\r\n",
+/// #             "/old-docs" => "301 Moved Permanently\r\nLocation: /docs\r\n",
+/// #             _ => "404 Not Found\r\n",
+/// #         };
+/// #         write!(stream, "HTTP/1.1 {}Content-Length: 0\r\nConnection: close\r\n\r\n", response).unwrap();
+/// #     }
+/// # });
+/// // A local server answering '/docs', redirecting '/old-docs' to it, and answering 404 otherwise
+/// let timeout = Duration::from_secs(10);
+///
+/// assert_eq!(validate_http_link(&format!("{}/docs", host), false, timeout), ValidationResult::Valid);
+/// assert_eq!(validate_http_link(&format!("{}/old-docs", host), true, timeout), ValidationResult::Valid);
+///
+/// assert_eq!(
+///     validate_http_link(&format!("{}/old-docs", host), false, timeout),
+///     ValidationResult::Warning(format!("URL '{}/old-docs' redirects to '/docs'", host))
+/// );
+///
+/// assert_eq!(
+///     validate_http_link(&format!("{}/missing", host), false, timeout),
+///     ValidationResult::Broken(format!("broken URL found: '{}/missing' answered with status 404", host))
+/// );
+/// # }
+/// ```
+#[cfg(feature = "check-http")]
+pub fn validate_http_link(
+    url: &str,
+    follow_redirects: bool,
+    timeout: Duration,
+) -> ValidationResult {
+    validate_http_link_with(&http_agent(follow_redirects, timeout), url)
+}
+
+/// Build the HTTP agent used to check HTTP and HTTPS links (see [`validate_http_link_with`])
+#[cfg(feature = "check-http")]
+pub fn http_agent(follow_redirects: bool, timeout: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .redirects(if follow_redirects { 5 } else { 0 })
+        .build()
+}
+
+/// Validate a link pointing to an HTTP or HTTPS URL like [`validate_http_link`] does, with an existing HTTP agent (see [`http_agent`])
+///
+/// Reusing the agent for many URLs allows to reuse its connections to the same hosts.
+#[cfg(feature = "check-http")]
+pub fn validate_http_link_with(agent: &ureq::Agent, url: &str) -> ValidationResult {
+    let response = match agent.head(url).call() {
+        Err(ureq::Error::Status(405 | 501, _)) => agent.get(url).call(),
+        response => response,
+    };
+
+    match response {
+        Ok(response) if (300..400).contains(&response.status()) => {
+            ValidationResult::Warning(format!(
+                "URL '{}' redirects to '{}'",
+                url,
+                response.header("location").unwrap_or("an unknown location")
+            ))
+        }
+        Ok(_) => ValidationResult::Valid,
+        Err(ureq::Error::Status(status, _)) => ValidationResult::Broken(format!(
+            "broken URL found: '{}' answered with status {}",
+            url, status
+        )),
+        Err(ureq::Error::Transport(transport)) => ValidationResult::Warning(format!(
+            "URL '{}' could not be reached: {}",
+            url,
+            match std::error::Error::source(&transport) {
+                Some(source) => format!("{}: {}", transport.kind(), source),
+                None => transport.kind().to_string(),
+            }
+        )),
+    }
+}
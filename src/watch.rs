@@ -0,0 +1,215 @@
+//! Watch mode of the command-line tool, checking the Markdown files again when they change on disk (see `--watch`)
+
+use crate::output::write_line;
+use crate::{
+    check_broken_links_with, safe_canonicalize, walk_markdown_files, CheckerOptions,
+    DetectedBrokenLink, FileLinksCache, Severity, TargetsCache,
+};
+use log::{debug, error, info};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time to wait for other changes after a file changed, so a file being written in several steps is only checked once
+static DEBOUNCE_WINDOW: Duration = Duration::from_millis(100);
+
+/// Message received by the watch loop
+enum WatchMessage {
+    /// Files were created or modified
+    Changed(Vec<PathBuf>),
+
+    /// The user asked to stop watching (Ctrl-C)
+    Stop,
+}
+
+/// Watch the input path and check again the Markdown files which are created or modified, until Ctrl-C is pressed
+///
+/// The anchors of the changed files are removed from the cache before they are checked again, and the resolved targets are forgotten.
+/// `initial` are the findings of the initial check, and `to_stderr` tells where the runs' summaries are written.
+pub(crate) fn watch(
+    input: &Path,
+    recursive: bool,
+    options: &CheckerOptions,
+    links_cache: &mut FileLinksCache,
+    targets_cache: &mut TargetsCache,
+    initial: &[DetectedBrokenLink],
+    to_stderr: bool,
+) -> Result<ExitCode, String> {
+    let (sender, receiver) = channel();
+
+    let stop = sender.clone();
+    ctrlc::set_handler(move || {
+        let _ = stop.send(WatchMessage::Stop);
+    })
+    .map_err(|err| format!("Failed to handle Ctrl-C: {}", err))?;
+
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                let _ = sender.send(WatchMessage::Changed(event.paths));
+            }
+            Ok(_) => {}
+            Err(err) => error!("Failed to watch for changes: {}", err),
+        })
+        .map_err(|err| format!("Failed to start watching for changes: {}", err))?;
+
+    watcher
+        .watch(
+            input,
+            match recursive {
+                true => RecursiveMode::Recursive,
+                false => RecursiveMode::NonRecursive,
+            },
+        )
+        .map_err(|err| {
+            format!(
+                "Failed to watch path '{}': {}",
+                safe_canonicalize(input),
+                err
+            )
+        })?;
+
+    info!(
+        "Watching '{}' for changes, press Ctrl-C to stop.",
+        safe_canonicalize(input)
+    );
+
+    let cwd = std::env::current_dir()
+        .and_then(|cwd| cwd.canonicalize())
+        .unwrap_or_default();
+
+    let mut runs = 1;
+    let mut total_broken = count_broken(initial);
+
+    while let Ok(WatchMessage::Changed(paths)) = receiver.recv() {
+        let mut changed = paths.into_iter().collect::<BTreeSet<_>>();
+
+        // Wait for the changes to settle
+        let stopped = loop {
+            match receiver.recv_timeout(DEBOUNCE_WINDOW) {
+                Ok(WatchMessage::Changed(paths)) => changed.extend(paths),
+                Ok(WatchMessage::Stop) | Err(RecvTimeoutError::Disconnected) => break true,
+                Err(RecvTimeoutError::Timeout) => break false,
+            }
+        };
+
+        if stopped {
+            break;
+        }
+
+        // Only the files a check of the input would find are checked again (e.g. not the ignored ones)
+        let checked = checked_files(input, recursive, options);
+
+        // Changed paths are absolute, they are displayed relatively to the current directory like the initial check's ones
+        let files = changed
+            .into_iter()
+            .filter_map(|path| path.canonicalize().ok())
+            .filter(|path| checked.contains(path))
+            .map(|path| match path.strip_prefix(&cwd) {
+                Ok(relative) => relative.to_owned(),
+                Err(_) => path,
+            })
+            .collect::<Vec<_>>();
+
+        if files.is_empty() {
+            continue;
+        }
+
+        runs += 1;
+
+        write_line(
+            format!(
+                "[{}] Checking {} changed file{} again...",
+                utc_time(SystemTime::now()),
+                files.len(),
+                if files.len() > 1 { "s" } else { "" }
+            ),
+            to_stderr,
+        );
+
+        // Targets may have been created or removed, and the anchors of the changed files may have changed
+        targets_cache.clear();
+
+        let mut broken = 0;
+
+        for file in &files {
+            if let Ok(canonical) = file.canonicalize() {
+                links_cache.remove(&canonical);
+            }
+
+            match check_broken_links_with(file, false, options, links_cache, targets_cache) {
+                Ok(errors) => broken += count_broken(&errors),
+                Err(err) => error!("{}", err),
+            }
+        }
+
+        total_broken += broken;
+
+        write_line(
+            match broken {
+                0 => "No broken link found in the changed files.".to_owned(),
+                broken => format!(
+                    "Found {} broken link{} in the changed files.",
+                    broken,
+                    if broken > 1 { "s" } else { "" }
+                ),
+            },
+            to_stderr,
+        );
+    }
+
+    debug!("Stopped watching for changes.");
+
+    write_line(
+        format!(
+            "Stopped after {} run{}, which found {} broken link{} in total.",
+            runs,
+            if runs > 1 { "s" } else { "" },
+            total_broken,
+            if total_broken > 1 { "s" } else { "" }
+        ),
+        to_stderr,
+    );
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Get the canonical paths of the files which are checked when checking the input
+///
+/// Directories are walked again, so files created since the previous check are found.
+fn checked_files(input: &Path, recursive: bool, options: &CheckerOptions) -> HashSet<PathBuf> {
+    let files = match recursive {
+        true => walk_markdown_files(input, options).0,
+        false => vec![input.to_owned()],
+    };
+
+    files
+        .iter()
+        .filter_map(|file| file.canonicalize().ok())
+        .collect()
+}
+
+/// Count the findings which are broken links (warnings and informational findings excluded)
+fn count_broken(errors: &[DetectedBrokenLink]) -> usize {
+    errors
+        .iter()
+        .filter(|error| error.severity == Severity::Error)
+        .count()
+}
+
+/// Format the time of day of a point in time, in UTC (e.g. `14:03:27`)
+fn utc_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    format!(
+        "{:02}:{:02}:{:02} UTC",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
@@ -0,0 +1,169 @@
+//! Integration tests of the generation and caching of anchors
+
+mod common;
+
+use broken_md_links::{
+    check_broken_links_simple, check_broken_links_with, generate_anchors_with, generate_slugs,
+    generate_slugs_with, load_anchor_aliases, load_links_cache, save_links_cache, CheckerOptions,
+    EmojiHandling, FileLinksCache, LinksCache, MathSlugHandling, SlugOptions, TargetsCache,
+};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn math_in_headers_is_handled_as_configured() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("physics.md"),
+        "## The $E = mc^2$ formula\n\n## Vector $\\mathbf{v}$\n",
+    )
+    .unwrap();
+
+    let slugs = |math_in_slug| {
+        let options = SlugOptions {
+            math_in_slug,
+            ..SlugOptions::default()
+        };
+        generate_slugs_with(&dir.join("physics.md"), &options).unwrap()
+    };
+
+    assert_eq!(
+        slugs(MathSlugHandling::Strip),
+        vec!["the--formula", "vector-"]
+    );
+    assert_eq!(
+        slugs(MathSlugHandling::IncludeRaw),
+        vec!["the-e--mc2-formula", "vector-mathbfv"]
+    );
+    assert_eq!(
+        slugs(MathSlugHandling::IncludePlain),
+        vec!["the-e--mc2-formula", "vector-v"]
+    );
+}
+
+#[test]
+fn slugs_are_generated_from_headers() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "# Title\n\n## See also: [the old guide](legacy/guide.md)\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        generate_slugs(&dir.join("doc.md")),
+        Ok(vec![
+            "title".to_owned(),
+            "see-also-the-old-guide".to_owned()
+        ])
+    );
+}
+
+#[test]
+fn anchors_include_html_ids() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "# Title\n\n<a id=\"Legacy_Section\"></a>\n\n## Usage\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        generate_anchors_with(&dir.join("doc.md"), &SlugOptions::default()),
+        Ok(vec![
+            "title".to_owned(),
+            "usage".to_owned(),
+            "Legacy_Section".to_owned()
+        ])
+    );
+}
+
+#[test]
+fn links_cache_files_are_loaded() {
+    let dir = TempDir::new();
+    fs::write(dir.join("README.md"), "# Docs\n\n## Usage\n").unwrap();
+    fs::write(dir.join("draft.md"), "# Draft\n").unwrap();
+
+    let options = CheckerOptions::default();
+    let mut cache = FileLinksCache::new();
+
+    cache.warm(&dir, &options);
+    save_links_cache(&cache, &options.slugs, &dir.join("headers.cache")).unwrap();
+
+    let loaded = load_links_cache(&dir.join("headers.cache"), &options.slugs).unwrap();
+    assert_eq!(loaded.entries(), cache.entries());
+
+    // Entries of files which changed since the cache was written are dropped
+    fs::remove_file(dir.join("draft.md")).unwrap();
+
+    let loaded = load_links_cache(&dir.join("headers.cache"), &options.slugs).unwrap();
+    assert_eq!(loaded.entries(), &cache.entries()[..1]);
+    assert_eq!(loaded.stats().evictions, 1);
+
+    // Caches written with other slug options are not used
+    let slugs = SlugOptions {
+        emoji_handling: EmojiHandling::Transliterate,
+        ..Default::default()
+    };
+    assert!(load_links_cache(&dir.join("headers.cache"), &slugs)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn anchor_aliases_are_loaded() {
+    let dir = TempDir::new();
+    fs::write(dir.join("guide.md"), "# Installation\n").unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "[Setup](guide.md#setup)\n\n[Usage](guide.md#usage)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("aliases.toml"), "\"guide.md#setup\" = \"guide.md#installation\"\n\"guide.md#usage\" = \"guide.md#how-to\"\n").unwrap();
+
+    let options = CheckerOptions {
+        anchor_aliases: load_anchor_aliases(&dir.join("aliases.toml")).unwrap(),
+        ..CheckerOptions::default()
+    };
+
+    // Aliases whose new anchor doesn't exist either are still reported
+    let errors = check_broken_links_simple(&dir.join("README.md"), &options).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+}
+
+#[test]
+fn links_cache_can_be_warmed() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("guide")).unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "# Docs\n\n[Setup](guide/setup.md#requirements)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("guide/setup.md"), "# Setup\n\n## Requirements\n").unwrap();
+
+    let options = CheckerOptions::default();
+    let mut cache = FileLinksCache::new();
+
+    assert_eq!(cache.warm(&dir, &options), 2);
+    assert_eq!(cache.warm(&dir, &options), 0);
+
+    let before = cache.stats();
+    check_broken_links_with(
+        &dir.join("README.md"),
+        false,
+        &options,
+        &mut cache,
+        &mut TargetsCache::new(),
+    )
+    .unwrap();
+    let after = cache.stats();
+
+    assert_eq!((after.entries, after.slugs), (2, 3));
+    assert!(after.hits > before.hits);
+
+    // Statistics are kept separately for each cache
+    assert_eq!(FileLinksCache::new().stats().hits, 0);
+}
@@ -0,0 +1,1351 @@
+//! Integration tests of the checks of files and directories, run against temporary directories
+
+mod common;
+
+use broken_md_links::{
+    check_broken_links_report, check_broken_links_simple, check_broken_links_with,
+    check_broken_links_with_callback, check_links_between_files, generate_anchors_with,
+    generate_slugs, BrokenLinkKind, CheckStats, CheckerOptions, DetectedBrokenLink, EmojiHandling,
+    FileLinksCache, InvalidUtf8Handling, Severity, SlugOptions, TargetsCache,
+};
+use common::TempDir;
+use std::collections::HashMap;
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::time::Duration;
+
+#[test]
+fn links_in_footnote_definitions_are_checked() {
+    let dir = TempDir::new();
+    fs::write(dir.join("rfc.md"), "# Motivation\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+Some text[^1] and some more[^2].
+
+[^1]: See [the RFC](rfc.md#motivation)
+
+[^2]: First paragraph, with [a broken link](missing.md).
+
+    Second paragraph, with [a broken header link](rfc.md#drawbacks).
+",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        errors.iter().map(|err| err.line).collect::<Vec<_>>(),
+        vec![5, 7]
+    );
+}
+
+#[test]
+fn all_kinds_of_links_are_checked_in_footnote_definitions() {
+    let dir = TempDir::new();
+    fs::write(dir.join("rfc.md"), "# Motivation\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+Some text[^refs] and some more[^media].
+
+[^refs]: A [full reference][full], a [collapsed][] one and a [shortcut].
+
+    Second paragraph, with [the RFC][rfc].
+
+[^media]: An ![image](missing.png).
+
+    <details>[Hidden](missing-hidden.md)</details>
+
+[full]: missing-full.md
+[collapsed]: missing-collapsed.md
+[shortcut]: rfc.md#drawbacks
+[rfc]: rfc.md#motivation
+",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|err| (err.line, err.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (3, BrokenLinkKind::MissingTarget),
+            (3, BrokenLinkKind::MissingTarget),
+            (3, BrokenLinkKind::MissingHeader),
+            (7, BrokenLinkKind::MissingImage),
+            (9, BrokenLinkKind::MissingTarget),
+        ]
+    );
+}
+
+#[test]
+fn path_style_reports_targets_not_in_their_shortest_form() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    fs::write(dir.join("docs/a.md"), "# Title\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "[a](docs/a.md) [b](./docs/a.md) [c](./docs/../docs/a.md#title)\n",
+    )
+    .unwrap();
+
+    let options = CheckerOptions {
+        check_path_style: true,
+        ..CheckerOptions::default()
+    };
+    let errors = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        (errors[0].kind, errors[0].severity),
+        (BrokenLinkKind::PathStyle, Severity::Warning)
+    );
+    assert_eq!(errors[0].suggestion.as_deref(), Some("docs/a.md#title"));
+}
+
+#[test]
+fn extension_case_reports_mismatched_extensions() {
+    let dir = TempDir::new();
+    fs::write(dir.join("setup.md"), "# Setup\n").unwrap();
+    fs::write(dir.join("doc.md"), "[setup](setup.MD#setup)\n").unwrap();
+
+    let options = CheckerOptions {
+        check_extension_case: true,
+        ..CheckerOptions::default()
+    };
+    let mut targets_cache = TargetsCache::new();
+    let errors = check_broken_links_with(
+        &dir.join("doc.md"),
+        false,
+        &options,
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, BrokenLinkKind::ExtensionCase);
+    assert_eq!(errors[0].suggestion.as_deref(), Some("setup.md#setup"));
+
+    // The target is only resolved once
+    assert_eq!((targets_cache.hits(), targets_cache.misses()), (0, 1));
+}
+
+#[test]
+fn headers_missing_from_the_table_of_contents_are_reported() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("guide.md"),
+        "\
+* [Installation](#installation)
+* [Usage](#usage)
+
+# Installation
+
+# Usage
+
+# Troubleshooting
+",
+    )
+    .unwrap();
+
+    let options = CheckerOptions {
+        check_toc: true,
+        ..CheckerOptions::default()
+    };
+    let errors = check_broken_links_simple(&dir.join("guide.md"), &options).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        (errors[0].line, errors[0].kind, errors[0].severity),
+        (8, BrokenLinkKind::Warning, Severity::Warning)
+    );
+    assert_eq!(
+        errors[0].error,
+        "header 'Troubleshooting' is missing from the table of contents"
+    );
+
+    // Without the option, the Table of Contents is not checked
+    assert!(
+        check_broken_links_simple(&dir.join("guide.md"), &CheckerOptions::default())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn absolute_windows_paths_are_reported() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    fs::write(dir.join("docs/page.md"), "# Page\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "[a](<C:\\Users\\alice\\docs\\page.md>) [b](<\\\\fileserver\\docs\\other.md>)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|err| err.kind == BrokenLinkKind::MachineSpecificPath));
+    assert_eq!(errors[0].suggestion.as_deref(), Some("docs/page.md"));
+    assert_eq!(errors[1].suggestion, None);
+}
+
+#[test]
+fn changelog_issue_links_must_reference_existing_issues() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("issues")).unwrap();
+    fs::write(dir.join("issues/123.md"), "# Issue 123\n").unwrap();
+    fs::write(
+        dir.join("CHANGELOG.md"),
+        "\
+* Fix [#123](https://github.com/org/repo/issues/123)
+* Fix [#124](https://github.com/org/repo/issues/124)
+",
+    )
+    .unwrap();
+
+    let options = CheckerOptions {
+        changelog_mode: true,
+        ..CheckerOptions::default()
+    };
+    let errors = check_broken_links_simple(&dir.join("CHANGELOG.md"), &options).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        (errors[0].line, errors[0].kind),
+        (2, BrokenLinkKind::MissingIssue)
+    );
+}
+
+#[test]
+fn links_in_html_blocks_are_checked() {
+    let dir = TempDir::new();
+    fs::write(dir.join("a.md"), "# A\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+<details>
+<summary>Links</summary>
+- [valid](a.md#a)
+- [broken](b.md)
+</details>
+",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+    assert_eq!(
+        errors.iter().map(|err| err.line).collect::<Vec<_>>(),
+        vec![4]
+    );
+
+    let options = CheckerOptions {
+        ignore_html_blocks: true,
+        ..CheckerOptions::default()
+    };
+    assert_eq!(
+        check_broken_links_simple(&dir.join("doc.md"), &options),
+        Ok(vec![])
+    );
+
+    // Links in HTML comments are ignored, and HTML blocks can be nested in other blocks
+    fs::write(
+        dir.join("doc.md"),
+        "\
+> <details>
+> <!-- [old](old.md)
+> [older](older.md) -->
+> [broken](b.md)
+> </details>
+",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+    assert_eq!(
+        errors
+            .iter()
+            .map(|err| (err.line, err.column))
+            .collect::<Vec<_>>(),
+        vec![(4, Some(3))]
+    );
+}
+
+#[test]
+fn destinations_with_spaces_are_reported() {
+    let dir = TempDir::new();
+    fs::write(dir.join("my setup.md"), "# Setup\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "[Setup](my setup.md#setup) [Usage](my setup.md#usage)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+    assert_eq!(
+        errors.iter().map(|err| err.kind).collect::<Vec<_>>(),
+        vec![BrokenLinkKind::Warning; 2]
+    );
+    assert!(errors[0].error.contains("is not a link"));
+
+    let options = CheckerOptions {
+        lenient_space_destinations: true,
+        ..CheckerOptions::default()
+    };
+    let errors = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0]
+        .error
+        .contains("write it '<my setup.md#setup>' or 'my%20setup.md#setup'"));
+    assert_eq!(errors[2].kind, BrokenLinkKind::MissingHeader);
+
+    // Titles are not part of the destination
+    fs::write(dir.join("doc.md"), "[Doc](doc.md \"The doc\")\n").unwrap();
+    assert_eq!(
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()),
+        Ok(vec![])
+    );
+}
+
+#[test]
+fn large_files_are_checked_in_linear_time() {
+    let dir = TempDir::new();
+    let padding = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(10);
+    let content = (0..5000)
+        .map(|i| format!("{}[link {}](#missing-{})\n", padding, i, i))
+        .collect::<String>();
+    fs::write(dir.join("doc.md"), &content).unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 5000);
+    assert_eq!(errors[4999].line, 5000);
+}
+
+#[test]
+fn directories_are_walked_in_order_and_follow_ignore_files() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("guide")).unwrap();
+    fs::create_dir_all(dir.join("drafts")).unwrap();
+    fs::write(dir.join(".ignore"), "drafts/\n").unwrap();
+    fs::write(dir.join("drafts/wip.md"), "[Broken](missing.md)\n").unwrap();
+    fs::write(dir.join("guide/b.md"), "[Broken](missing.md)\n").unwrap();
+    fs::write(dir.join("guide/a.md"), "[Broken](missing.md)\n").unwrap();
+    fs::write(dir.join("README.md"), "[Broken](missing.md)\n").unwrap();
+
+    let errors = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+    let files = errors
+        .iter()
+        .map(|error| error.file.strip_prefix(&dir).unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(files, vec!["README.md", "guide/a.md", "guide/b.md"]);
+
+    let options = CheckerOptions {
+        no_ignore: true,
+        ..Default::default()
+    };
+    assert_eq!(check_broken_links_simple(&dir, &options).unwrap().len(), 4);
+}
+
+#[test]
+fn results_do_not_depend_on_the_creation_order() {
+    let files = [
+        (
+            "guide/a.md",
+            "# A\n\n[B](./b.md#missing) [Up](../README.md#nope)\n",
+        ),
+        (
+            "guide/b.md",
+            "# B\n\n[A](../guide/./a.md#missing) [C](c.md)\n",
+        ),
+        (
+            "README.md",
+            "# Home\n\n[A](guide/a.md#missing) [B](guide/../guide/b.md#missing)\n",
+        ),
+    ];
+
+    let check = |reversed: bool| {
+        let dir = TempDir::new();
+        fs::create_dir_all(dir.join("guide")).unwrap();
+
+        let mut files = files.to_vec();
+
+        if reversed {
+            files.reverse();
+        }
+
+        for (file, content) in files {
+            fs::write(dir.join(file), content).unwrap();
+        }
+
+        let prefix = dir.to_str().unwrap().to_owned();
+
+        check_broken_links_simple(&dir, &CheckerOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|error| DetectedBrokenLink {
+                file: error.file.strip_prefix(&dir).unwrap().to_owned(),
+                error: error.error.replace(&prefix, "<dir>"),
+                ..error
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let results = check(false);
+
+    assert_eq!(results.len(), 6);
+    assert_eq!(results, check(true));
+}
+
+#[test]
+fn deeply_nested_trees_do_not_overflow_the_stack() {
+    let dir = TempDir::new();
+    let deepest = (0..1000).fold(dir.to_path_buf(), |path, _| path.join("d"));
+
+    fs::create_dir_all(&deepest).unwrap();
+    fs::write(deepest.join("deep.md"), "[Broken](missing.md)\n").unwrap();
+
+    let errors = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].file, deepest.join("deep.md"));
+}
+
+#[test]
+fn concurrent_checks_have_the_same_results() {
+    let dir = TempDir::new();
+    for i in 0..20 {
+        fs::create_dir_all(dir.join(format!("dir-{}", i))).unwrap();
+
+        for j in 0..5 {
+            let content = format!(
+                "# File {}\n\n[Next](../dir-{}/file-{}.md#file-{}) [Broken](missing.md)\n",
+                j,
+                (i + 1) % 20,
+                j,
+                j
+            );
+            fs::write(dir.join(format!("dir-{}/file-{}.md", i, j)), content).unwrap();
+        }
+    }
+
+    let sequential = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+
+    let options = CheckerOptions {
+        max_concurrent_dirs: 4,
+        ..Default::default()
+    };
+    let mut cache = FileLinksCache::new();
+
+    assert_eq!(sequential.len(), 100);
+    assert_eq!(
+        check_broken_links_with(&dir, true, &options, &mut cache, &mut TargetsCache::new()),
+        Ok(sequential.clone())
+    );
+    assert_eq!(cache.len(), 100);
+
+    // Files can also be checked on all available threads, whatever their directory
+    let options = CheckerOptions {
+        parallel: true,
+        ..Default::default()
+    };
+    assert_eq!(check_broken_links_simple(&dir, &options), Ok(sequential));
+}
+
+#[test]
+fn results_follow_the_walk_order() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("a/z")).unwrap();
+    for file in ["a/b.md", "a/z/c.md", "a/zz.md"] {
+        fs::write(dir.join(file), "[Broken](missing.md)\n").unwrap();
+    }
+
+    let options = CheckerOptions {
+        max_concurrent_dirs: 2,
+        ..Default::default()
+    };
+    let errors = check_broken_links_simple(&dir, &options).unwrap();
+
+    let files = errors
+        .iter()
+        .map(|error| error.file.strip_prefix(&dir).unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(files, ["a/b.md", "a/z/c.md", "a/zz.md"]);
+}
+
+#[test]
+#[cfg(unix)]
+fn symbolic_link_loops_are_skipped() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("a")).unwrap();
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    fs::write(dir.join("a/doc.md"), "[Broken](missing.md)\n").unwrap();
+    fs::write(dir.join("docs/guide.md"), "[Broken](missing.md)\n").unwrap();
+
+    std::os::unix::fs::symlink("..", dir.join("a/loop")).unwrap();
+    std::os::unix::fs::symlink("../docs", dir.join("a/docs")).unwrap();
+    std::os::unix::fs::symlink("docs", dir.join("mirror")).unwrap();
+
+    let options = CheckerOptions {
+        follow_symlinks: true,
+        ..Default::default()
+    };
+    let errors = check_broken_links_simple(&dir, &options).unwrap();
+
+    let (warnings, errors): (Vec<_>, Vec<_>) = errors
+        .into_iter()
+        .partition(|err| err.kind == BrokenLinkKind::Warning);
+
+    let files = errors.iter().map(|error| &error.file).collect::<Vec<_>>();
+    assert_eq!(
+        files,
+        vec![&dir.join("a/doc.md"), &dir.join("docs/guide.md")]
+    );
+
+    let mut warned = warnings
+        .iter()
+        .map(|warning| &warning.file)
+        .collect::<Vec<_>>();
+    warned.sort();
+
+    assert_eq!(
+        warned,
+        vec![
+            &dir.join("a/docs"),
+            &dir.join("a/loop"),
+            &dir.join("mirror")
+        ]
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn unreadable_paths_are_reported() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("locked")).unwrap();
+    fs::write(dir.join("locked/doc.md"), "[Broken](missing.md)\n").unwrap();
+    fs::write(dir.join("unreadable.md"), "[Broken](missing.md)\n").unwrap();
+    fs::write(dir.join("readable.md"), "[Broken](missing.md)\n").unwrap();
+
+    fs::set_permissions(dir.join("locked"), fs::Permissions::from_mode(0o000)).unwrap();
+    fs::set_permissions(dir.join("unreadable.md"), fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Permissions are not enforced for privileged users
+    if fs::read_dir(dir.join("locked")).is_err() {
+        let errors = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+        let kinds = errors
+            .iter()
+            .map(|error| {
+                (
+                    error.file.strip_prefix(&dir).unwrap().to_str().unwrap(),
+                    error.kind,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("locked", BrokenLinkKind::UnreadablePath),
+                ("readable.md", BrokenLinkKind::MissingTarget),
+                ("unreadable.md", BrokenLinkKind::UnreadablePath),
+            ]
+        );
+
+        let options = CheckerOptions {
+            halt_on_io_error: true,
+            ..Default::default()
+        };
+        assert!(check_broken_links_simple(&dir, &options).is_err());
+    }
+
+    fs::set_permissions(dir.join("locked"), fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn line_numbers_are_exact_after_multibyte_characters() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+# Présentation générale
+
+Écrit en français, 日本語のドキュメント, 🚀🚀🚀🚀🚀🚀🚀🚀
+
+Voir [la référence](#référence-absente).
+
+Et [l'index](index.md).
+
+
+
+",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        errors.iter().map(|error| error.line).collect::<Vec<_>>(),
+        vec![5, 7]
+    );
+}
+
+#[test]
+fn anchors_are_cached_during_the_check() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+# 🚀 Usage
+
+See [the options](#options), [the old ones](#Legacy_Options) and [the second example](#example-1).
+
+# Options
+
+# Example
+
+# Example
+
+# With `code` and [a link](doc.md)
+
+<a id=\"Legacy_Options\"></a>
+",
+    )
+    .unwrap();
+
+    for emoji_handling in [EmojiHandling::Strip, EmojiHandling::Transliterate] {
+        let options = CheckerOptions {
+            slugs: SlugOptions {
+                emoji_handling,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut cache = FileLinksCache::new();
+
+        assert_eq!(
+            check_broken_links_with(
+                &dir.join("doc.md"),
+                false,
+                &options,
+                &mut cache,
+                &mut TargetsCache::new()
+            ),
+            Ok(vec![])
+        );
+        assert_eq!(
+            cache
+                .get(&dir.join("doc.md").canonicalize().unwrap())
+                .as_deref(),
+            Some(&generate_anchors_with(&dir.join("doc.md"), &options.slugs).unwrap())
+        );
+    }
+}
+
+#[test]
+fn links_in_headers_are_checked() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "# Title\n\n## See also: [the old guide](legacy/guide.md)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+}
+
+#[test]
+fn files_larger_than_the_limit_are_skipped() {
+    let dir = TempDir::new();
+    fs::write(dir.join("small.md"), "[Missing](missing.md)\n").unwrap();
+    fs::write(dir.join("large.md"), "[Missing](missing.md)\n".repeat(1000)).unwrap();
+
+    let options = CheckerOptions {
+        max_file_size: Some(1024),
+        ..Default::default()
+    };
+    let errors = check_broken_links_simple(&dir, &options).unwrap();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        (errors[0].file.as_path(), errors[0].kind),
+        (dir.join("large.md").as_path(), BrokenLinkKind::Warning)
+    );
+    assert_eq!(errors[1].file, dir.join("small.md"));
+}
+
+#[test]
+fn files_left_when_the_timeout_expires_are_counted() {
+    let dir = TempDir::new();
+    fs::write(dir.join("a.md"), "[Missing](missing.md)\n").unwrap();
+    fs::write(dir.join("b.md"), "[Missing](missing.md)\n").unwrap();
+
+    let options = CheckerOptions {
+        timeout: Some(Duration::ZERO),
+        ..Default::default()
+    };
+    let errors = check_broken_links_simple(&dir, &options).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, BrokenLinkKind::Timeout);
+    assert!(errors[0].error.ends_with("2 files were left unchecked"));
+}
+
+#[test]
+fn invalid_utf8_files_are_handled_as_configured() {
+    let dir = TempDir::new();
+    // "Café" encoded in Latin-1
+    fs::write(
+        dir.join("latin1.md"),
+        b"# Caf\xe9\n\n[Missing](missing.md)\n",
+    )
+    .unwrap();
+
+    let check = |invalid_utf8| {
+        let options = CheckerOptions {
+            invalid_utf8,
+            ..Default::default()
+        };
+        check_broken_links_simple(&dir.join("latin1.md"), &options)
+    };
+
+    assert_eq!(check(InvalidUtf8Handling::Lossy).unwrap()[1].line, 3);
+    assert_eq!(check(InvalidUtf8Handling::Skip).unwrap().len(), 1);
+    assert!(check(InvalidUtf8Handling::Error)
+        .unwrap_err()
+        .contains("is not valid UTF-8"));
+}
+
+#[test]
+fn utf8_byte_order_marks_are_ignored() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("heading.md"),
+        "\u{feff}# Title\n\n[Title](#title)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("link.md"),
+        "\u{feff}[Missing](missing.md)\n\n[Title](heading.md#title)\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        generate_slugs(&dir.join("heading.md")).unwrap(),
+        vec!["title"]
+    );
+
+    let errors = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        (errors[0].file.file_name().unwrap().to_str(), errors[0].line),
+        (Some("link.md"), 1)
+    );
+}
+
+#[test]
+fn reference_links_and_definitions_are_checked() {
+    let dir = TempDir::new();
+    fs::write(dir.join("guide.md"), "# Guide\n\n## Setup\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+See [the guide][guide], [Setup][], [Usage][] and [missing].
+
+Also [the setup][setup] again, but not [this one][nowhere].
+
+[guide]: guide.md
+[setup]: guide.md#setup
+[usage]: guide.md#usage
+[missing]: missing.md
+[unused]: old.md
+",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|err| (err.line, err.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, BrokenLinkKind::MissingHeader),
+            (1, BrokenLinkKind::MissingTarget),
+            (3, BrokenLinkKind::UnresolvedReference),
+            // Definitions which no link uses are checked too
+            (9, BrokenLinkKind::DefinitionTargetMissing),
+        ]
+    );
+
+    // Unless they are ignored, links without a definition are reported too
+    let options = CheckerOptions {
+        ignore_unresolved_references: true,
+        ..CheckerOptions::default()
+    };
+    let errors = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+    assert_eq!(errors.len(), 3);
+
+    // Unused definitions are validated with the same rules as links
+    fs::write(dir.join("doc.md"), "[here]: ./\n").unwrap();
+    let options = CheckerOptions {
+        only_files: true,
+        ..CheckerOptions::default()
+    };
+    let errors = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+    assert_eq!(
+        errors.iter().map(|err| err.kind).collect::<Vec<_>>(),
+        vec![BrokenLinkKind::DirectoryLink]
+    );
+}
+
+#[test]
+fn image_targets_are_checked() {
+    let dir = TempDir::new();
+    fs::write(dir.join("diagram.png"), "").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "![Diagram](diagram.png) ![Architecture](arch.png) ![Logo](https://example.com/logo.png)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, BrokenLinkKind::MissingImage);
+    assert_eq!(
+        errors[0].error,
+        format!(
+            "broken image found: path '{}' does not exist",
+            dir.join("arch.png").display()
+        )
+    );
+
+    let options = CheckerOptions {
+        ignore_images: true,
+        ..CheckerOptions::default()
+    };
+    assert!(check_broken_links_simple(&dir.join("doc.md"), &options)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn links_to_the_same_file_are_checked() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "# Usage\n\nSee [usage](#usage), [line 3](#L3) and [installation](#installation)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+    assert_eq!(
+        errors[0].error,
+        "broken link found: header 'installation' not found in this file"
+    );
+
+    // Findings about a link are located at its first character, and contain its text
+    assert_eq!(errors[0].column, Some(40));
+    assert_eq!(errors[0].link_text.as_deref(), Some("installation"));
+}
+
+#[test]
+fn findings_are_located_at_the_link() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+| Name | Link |
+| ---- | ---- |
+| a \\| b | [One](one.md) |
+| `c` | **x [Two][missing]** ![Image](img.png) |
+
+- item
+  - nested [Three](three.md)
+    - deeper
+      [Four](four.md)
+
+> - quoted [Five](five.md)
+>   - nested [Six][missing]
+",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|err| (err.line, err.column.unwrap()))
+            .collect::<Vec<_>>(),
+        vec![
+            (3, 12),
+            (4, 13),
+            (4, 30),
+            (7, 12),
+            (9, 7),
+            (11, 12),
+            (12, 14)
+        ]
+    );
+}
+
+#[test]
+fn links_whose_text_is_their_destination_are_reported() {
+    let dir = TempDir::new();
+    fs::write(dir.join("guide.md"), "# Guide\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+See [./guide.md](./guide.md), [the guide](guide.md) and [`guide.md`](guide.md).
+
+Also [https://example.com/](https://example.com) and <https://example.com>.
+",
+    )
+    .unwrap();
+
+    assert!(
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default())
+            .unwrap()
+            .is_empty()
+    );
+
+    let options = CheckerOptions {
+        link_text_is_url: Some(Severity::Info),
+        ..CheckerOptions::default()
+    };
+    let findings = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+
+    assert_eq!(
+        findings
+            .iter()
+            .map(|finding| (finding.line, finding.column.unwrap()))
+            .collect::<Vec<_>>(),
+        vec![(1, 5), (1, 57), (3, 6)]
+    );
+    assert!(findings
+        .iter()
+        .all(|finding| finding.kind == BrokenLinkKind::LinkTextIsUrl
+            && finding.severity == Severity::Info));
+}
+
+#[test]
+fn fragments_can_be_html_ids() {
+    let dir = TempDir::new();
+    fs::write(dir.join("file.md"), "# Title\n\n<div id=\"custom\">\n\nContent\n\n</div>\n\nSome <span id='inline'>text</span>\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "[Custom](file.md#custom), [inline](file.md#inline) and [missing](file.md#other)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("doc.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].error.contains("'other'"));
+}
+
+#[test]
+fn rust_doc_comments_are_checked() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("guide.md"), "# Usage\n").unwrap();
+    fs::write(
+        dir.join("src/lib.rs"),
+        "\
+//! See [the guide](../guide.md#usage) and [the setup](../guide.md#setup).
+
+/// Uses a [`Vec`] and a [`HashMap`][std::collections::HashMap].
+///
+/// ```
+/// let code = \"[not a link](missing.md)\";
+#[derive(Debug)]
+/// ```
+pub struct Foo;
+
+/**
+ * Not [documented](missing.md) yet.
+ */
+pub fn foo() -> &'static str {
+    \"/// [Neither](missing.md)\"
+}
+
+/// Links to [private items](missing.md) are checked by other means.
+#[broken_md_links_derive::broken_links_ignore]
+pub fn bar() {}
+
+/// [Generated](missing.md) docs.
+///
+/// <!-- broken-md-links: ignore -->
+pub fn baz() {}
+",
+    )
+    .unwrap();
+
+    let options = CheckerOptions {
+        rust_doc_comments: true,
+        ..CheckerOptions::default()
+    };
+    let errors = check_broken_links_simple(&dir, &options).unwrap();
+
+    assert_eq!(
+        errors.iter().map(|err| err.line).collect::<Vec<_>>(),
+        vec![1, 12]
+    );
+    assert!(errors.iter().all(|err| err.file == dir.join("src/lib.rs")));
+
+    // Rust source files are not checked otherwise
+    assert_eq!(
+        check_broken_links_simple(&dir, &CheckerOptions::default()),
+        Ok(vec![])
+    );
+}
+
+#[test]
+fn warnings_can_be_errors() {
+    let dir = TempDir::new();
+    fs::write(dir.join("diagram.png"), "").unwrap();
+    fs::write(dir.join("doc.md"), "# Title\n\n#\n\n![](diagram.png)\n").unwrap();
+
+    let options = CheckerOptions {
+        require_alt_text: true,
+        ..CheckerOptions::default()
+    };
+    let warnings = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+
+    assert_eq!(
+        warnings
+            .iter()
+            .map(|err| (err.line, err.kind))
+            .collect::<Vec<_>>(),
+        vec![(3, BrokenLinkKind::Warning), (5, BrokenLinkKind::Warning)]
+    );
+    assert!(warnings.iter().all(|err| err.severity == Severity::Warning));
+    assert!(warnings[1].error.contains("no alternative text"));
+
+    let options = CheckerOptions {
+        error_on_warnings: true,
+        ..options
+    };
+    let errors = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|err| (err.line, err.kind))
+            .collect::<Vec<_>>(),
+        vec![(3, BrokenLinkKind::Warning), (5, BrokenLinkKind::Warning)]
+    );
+    assert!(errors.iter().all(|err| err.severity == Severity::Error));
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_check_matches_the_simple_one() {
+    let dir = TempDir::new();
+    fs::write(dir.join("doc.md"), "[Missing](missing.md)\n").unwrap();
+
+    let options = CheckerOptions::builder().only_files(true).build();
+
+    assert_eq!(
+        broken_md_links::check_broken_links(&dir, true, false, true, false, &mut HashMap::new()),
+        check_broken_links_simple(&dir, &options)
+    );
+}
+
+#[test]
+fn targets_cache_is_shared_between_checks() {
+    let dir = TempDir::new();
+    fs::write(dir.join("hub.md"), "# Hub\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "[Hub](hub.md), [again](./hub.md#hub), [missing](missing.md) and [again](missing.md)\n",
+    )
+    .unwrap();
+
+    let mut targets_cache = TargetsCache::new();
+    let errors = check_broken_links_with(
+        &dir.join("doc.md"),
+        false,
+        &CheckerOptions::default(),
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!((targets_cache.hits(), targets_cache.misses()), (1, 2));
+}
+
+#[test]
+fn repeated_destinations_are_resolved_once() {
+    let dir = TempDir::new();
+    fs::write(dir.join("types.md"), "# Types\n").unwrap();
+    fs::write(
+        dir.join("api.md"),
+        "* [Options](types.md#options)\n".repeat(500),
+    )
+    .unwrap();
+
+    let mut targets_cache = TargetsCache::new();
+    let errors = check_broken_links_with(
+        &dir.join("api.md"),
+        false,
+        &CheckerOptions::default(),
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+
+    assert_eq!(errors.len(), 500);
+    assert_eq!(errors[499].line, 500);
+    assert_eq!((targets_cache.hits(), targets_cache.misses()), (0, 1));
+}
+
+#[test]
+fn reports_count_the_checked_files_and_links() {
+    let dir = TempDir::new();
+    fs::write(dir.join("README.md"), "# Docs\n\n[Docs](#docs)\n").unwrap();
+
+    let options = CheckerOptions::default();
+    let result = check_broken_links_report(
+        &dir,
+        true,
+        &options,
+        &mut FileLinksCache::new(),
+        &mut TargetsCache::new(),
+    )
+    .unwrap();
+
+    assert_eq!(result.stats, CheckStats { files: 1, links: 1 });
+    assert_eq!(result.broken_count(), 0);
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        r#"{"findings":[],"stats":{"files":1,"links":1}}"#
+    );
+
+    fs::write(dir.join("README.md"), "# Docs\n\n[Setup](setup.md)\n").unwrap();
+
+    let result = check_broken_links_report(
+        &dir,
+        true,
+        &options,
+        &mut FileLinksCache::new(),
+        &mut TargetsCache::new(),
+    )
+    .unwrap();
+    assert_eq!(result.broken_count(), 1);
+}
+
+#[test]
+fn links_between_changed_files_are_checked() {
+    let dir = TempDir::new();
+    fs::write(dir.join("b.md"), "# Usage\n").unwrap();
+    fs::write(
+        dir.join("a.md"),
+        "[Usage](b.md#usage), [setup](b.md#setup), [removed](removed.md) and [other](other.md)\n",
+    )
+    .unwrap();
+
+    let changed = [dir.join("b.md"), dir.join("removed.md")];
+    let changed = changed
+        .iter()
+        .map(|target| target.as_path())
+        .collect::<Vec<_>>();
+
+    let mut cache = FileLinksCache::new();
+    let errors = check_links_between_files(
+        &dir.join("a.md"),
+        &changed,
+        &CheckerOptions::default(),
+        &mut cache,
+    )
+    .unwrap();
+
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].error.contains("'setup'"));
+    assert!(errors[1].error.contains("removed.md"));
+    assert!(errors[2].error.contains("other.md"));
+
+    // The anchors of changed files are not taken from the cache
+    fs::write(dir.join("b.md"), "# Usage\n\n## Setup\n").unwrap();
+
+    let errors = check_links_between_files(
+        &dir.join("a.md"),
+        &changed,
+        &CheckerOptions::default(),
+        &mut cache,
+    )
+    .unwrap();
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn findings_are_provided_to_the_callback_in_order() {
+    let dir = TempDir::new();
+    fs::write(dir.join("a.md"), "[Missing](missing.md)\n").unwrap();
+    fs::write(
+        dir.join("b.md"),
+        "[Missing](missing.md) [Again](missing.md)\n",
+    )
+    .unwrap();
+
+    let mut files = vec![];
+
+    let count = check_broken_links_with_callback(
+        &dir,
+        true,
+        &CheckerOptions::default(),
+        &mut FileLinksCache::new(),
+        |error| files.push(error.file.file_name().unwrap().to_owned()),
+    );
+
+    assert_eq!(count, Ok(3));
+    assert_eq!(files, vec!["a.md", "b.md", "b.md"]);
+}
+
+#[test]
+fn line_counts_are_cached() {
+    let dir = TempDir::new();
+    let file = dir.join("doc.md");
+    fs::write(&file, "# Title\n\nText\n").unwrap();
+
+    let mut cache = TargetsCache::new();
+    assert_eq!(cache.line_count(&file).unwrap(), 3);
+
+    fs::write(&file, "# Title\n").unwrap();
+    assert_eq!(cache.line_count(&file).unwrap(), 3);
+
+    cache.clear();
+    assert_eq!(cache.line_count(&file).unwrap(), 1);
+}
+
+#[test]
+fn check_stats_are_taken_once() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("a.md"),
+        "# A\n\n[B](b.md) [Missing](missing.md) [A](#a)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("b.md"), "# B\n").unwrap();
+
+    let mut targets_cache = TargetsCache::new();
+    check_broken_links_with(
+        &dir,
+        true,
+        &CheckerOptions::default(),
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+
+    assert_eq!(
+        targets_cache.take_check_stats(),
+        CheckStats { files: 2, links: 3 }
+    );
+    assert_eq!(targets_cache.take_check_stats(), CheckStats::default());
+
+    // Checks in parallel directories are counted as a single one
+    let options = CheckerOptions {
+        max_concurrent_dirs: 4,
+        ..CheckerOptions::default()
+    };
+    check_broken_links_with(
+        &dir,
+        true,
+        &options,
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+
+    assert_eq!(
+        targets_cache.take_check_stats(),
+        CheckStats { files: 2, links: 3 }
+    );
+}
+
+#[test]
+fn file_profiles_are_recorded() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("a.md"),
+        "# A\n\n[B](b.md#b) [Missing](missing.md) [Usage](b.md#usage)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("b.md"), "# B\n").unwrap();
+
+    let options = CheckerOptions {
+        profile: true,
+        ..CheckerOptions::default()
+    };
+    let mut targets_cache = TargetsCache::new();
+
+    check_broken_links_with(
+        &dir,
+        true,
+        &options,
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+
+    let profiles = targets_cache.take_file_profiles();
+
+    assert_eq!(
+        profiles
+            .iter()
+            .map(|profile| (profile.link_count, profile.broken_count))
+            .collect::<Vec<_>>(),
+        vec![(3, 2), (0, 0)]
+    );
+    assert!(profiles
+        .iter()
+        .all(|profile| profile.read_ms >= 0.0 && profile.link_check_ms >= 0.0));
+    assert!(targets_cache.take_file_profiles().is_empty());
+
+    // Profiles are only recorded in the cache of the check
+    check_broken_links_with(
+        &dir,
+        true,
+        &options,
+        &mut FileLinksCache::new(),
+        &mut TargetsCache::new(),
+    )
+    .unwrap();
+    assert!(targets_cache.take_file_profiles().is_empty());
+}
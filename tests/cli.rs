@@ -0,0 +1,535 @@
+//! Integration tests of the command-line tool, run against temporary directories
+
+mod common;
+
+use common::TempDir;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Run the checker from a directory
+fn broken_md_links(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_broken-md-links"))
+        .current_dir(dir)
+        .args(args)
+        .env_remove("GITHUB_ACTIONS")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn targets_only_patterns_are_relative_to_the_input() {
+    let dir = TempDir::new();
+    let elsewhere = TempDir::new();
+
+    fs::create_dir_all(dir.join("gen")).unwrap();
+    fs::write(dir.join("README.md"), "[API](gen/api.md#api)\n").unwrap();
+    fs::write(dir.join("gen/api.md"), "# API\n\n[Missing](missing.md)\n").unwrap();
+
+    let output = broken_md_links(
+        &elsewhere,
+        &[dir.to_str().unwrap(), "-r", "--targets-only", "gen/"],
+    );
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    // Without the pattern, the generated file is checked
+    let output = broken_md_links(&elsewhere, &[dir.to_str().unwrap(), "-r"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn incremental_checks_follow_scheme_patterns() {
+    let dir = TempDir::new();
+    let state = dir.join("state.json");
+
+    fs::write(dir.join("README.md"), "[Ticket](jira://PROJ-123)\n").unwrap();
+
+    let run = |pattern: &str| {
+        broken_md_links(
+            &dir,
+            &[
+                "README.md",
+                "--scheme-regex",
+                pattern,
+                "--incremental",
+                state.to_str().unwrap(),
+            ],
+        )
+        .status
+        .code()
+    };
+
+    assert_eq!(run("jira=^jira://PROJ-"), Some(0));
+    assert_eq!(run("jira=^jira://OTHER-"), Some(1));
+    assert_eq!(run("jira=^jira://PROJ-"), Some(0));
+}
+
+#[test]
+fn incremental_checks_follow_redirects_of_targets() {
+    let dir = TempDir::new();
+    let state = dir.join("state.json");
+
+    fs::write(dir.join("README.md"), "[Old](old.md)\n").unwrap();
+    fs::write(dir.join("old.md"), "# Old\n").unwrap();
+
+    let run = |incremental: bool| {
+        let mut args = vec!["README.md", "--check-redirects"];
+
+        if incremental {
+            args.extend(["--incremental", state.to_str().unwrap()]);
+        }
+
+        String::from_utf8_lossy(&broken_md_links(&dir, &args).stdout)
+            .contains("leads to a redirect")
+    };
+
+    assert!(!run(true));
+
+    fs::write(
+        dir.join("old.md"),
+        "---\nredirect_to: new.md\n---\n\n# Old\n",
+    )
+    .unwrap();
+
+    assert!(run(false));
+    assert!(run(true));
+}
+
+#[test]
+fn incremental_checks_follow_drafts() {
+    let dir = TempDir::new();
+    let state = dir.join("state.json");
+
+    fs::write(dir.join("README.md"), "[Next](next.md)\n").unwrap();
+    fs::write(dir.join("next.md"), "# Next\n").unwrap();
+
+    let run = |incremental: bool| {
+        let mut args = vec!["README.md", "--respect-drafts"];
+
+        if incremental {
+            args.extend(["--incremental", state.to_str().unwrap()]);
+        }
+
+        String::from_utf8_lossy(&broken_md_links(&dir, &args).stdout).contains("leads to a draft")
+    };
+
+    assert!(!run(true));
+
+    fs::write(dir.join("next.md"), "---\ndraft: true\n---\n\n# Next\n").unwrap();
+
+    assert!(run(false));
+    assert!(run(true));
+}
+
+#[test]
+fn incremental_checks_follow_anchor_aliases() {
+    let dir = TempDir::new();
+    let state = dir.join("state.json");
+
+    fs::write(dir.join("README.md"), "[Setup](guide.md#old)\n").unwrap();
+    fs::write(dir.join("guide.md"), "# New\n").unwrap();
+
+    let run = |incremental: bool| {
+        let mut args = vec!["README.md"];
+
+        if incremental {
+            args.extend(["--incremental", state.to_str().unwrap()]);
+        }
+
+        broken_md_links(&dir, &args).status.code()
+    };
+
+    assert_eq!(run(true), Some(1));
+
+    fs::write(
+        dir.join("guide.md"),
+        "<!-- broken-md-links:anchor-alias old=new -->\n\n# New\n",
+    )
+    .unwrap();
+
+    assert_eq!(run(false), Some(0));
+    assert_eq!(run(true), Some(0));
+}
+
+#[test]
+fn incremental_checks_follow_near_misses() {
+    let dir = TempDir::new();
+    let state = dir.join("state.json");
+
+    fs::write(dir.join("README.md"), "[FAQ](guide.md#faqs)\n").unwrap();
+    fs::write(dir.join("guide.md"), "# FAQ\n").unwrap();
+
+    let run = |incremental: bool| {
+        let mut args = vec!["README.md", "--anchor-plural-severity", "warn"];
+
+        if incremental {
+            args.extend(["--incremental", state.to_str().unwrap()]);
+        }
+
+        broken_md_links(&dir, &args).status.code()
+    };
+
+    assert_eq!(run(true), Some(0));
+
+    fs::write(dir.join("guide.md"), "# Other\n").unwrap();
+
+    assert_eq!(run(false), Some(1));
+    assert_eq!(run(true), Some(1));
+}
+
+#[test]
+fn incremental_checks_follow_definition_targets() {
+    let dir = TempDir::new();
+    let state = dir.join("state.json");
+
+    fs::write(dir.join("README.md"), "[guide]: guide.md\n").unwrap();
+    fs::write(dir.join("guide.md"), "# Guide\n").unwrap();
+
+    let run = |incremental: bool| {
+        let mut args = vec!["README.md"];
+
+        if incremental {
+            args.extend(["--incremental", state.to_str().unwrap()]);
+        }
+
+        broken_md_links(&dir, &args).status.code()
+    };
+
+    assert_eq!(run(true), Some(0));
+
+    fs::remove_file(dir.join("guide.md")).unwrap();
+
+    assert_eq!(run(false), Some(1));
+    assert_eq!(run(true), Some(1));
+}
+
+#[test]
+fn incremental_checks_refuse_http_checks() {
+    let dir = TempDir::new();
+    fs::write(dir.join("README.md"), "[Docs](https://example.com)\n").unwrap();
+
+    let output = broken_md_links(
+        &dir,
+        &["README.md", "--check-http", "--incremental", "state.json"],
+    );
+
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+    assert!(!dir.join("state.json").exists());
+}
+
+#[test]
+fn write_cache_only_fills_the_cache_without_checking() {
+    let dir = TempDir::new();
+
+    fs::create_dir_all(dir.join("drafts")).unwrap();
+    fs::write(dir.join(".ignore"), "drafts/\n").unwrap();
+    fs::write(dir.join("README.md"), "[Setup](guide.md#setup)\n").unwrap();
+    fs::write(dir.join("guide.md"), "# Guide\n\n## Usage\n").unwrap();
+    fs::write(dir.join("drafts/notes.md"), "# Notes\n").unwrap();
+
+    let output = broken_md_links(
+        &dir,
+        &[
+            ".",
+            "-r",
+            "--cache-file",
+            "headers.cache",
+            "--write-cache-only",
+        ],
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+
+    // Files are found the same way as when checking the directory
+    let cache = fs::read_to_string(dir.join("headers.cache")).unwrap();
+    assert!(cache.contains("guide.md\t"));
+    assert!(cache.contains("\tguide\n\tusage\n"));
+    assert!(!cache.contains("notes.md"));
+
+    // The cache is then used by regular checks
+    let output = broken_md_links(&dir, &[".", "-r", "--cache-file", "headers.cache"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        fs::read_to_string(dir.join("headers.cache")).unwrap(),
+        cache
+    );
+}
+
+#[test]
+fn silent_json_reports_only_write_the_report() {
+    let dir = TempDir::new();
+
+    fs::write(dir.join("README.md"), "# Docs\n\n[Docs](#docs)\n").unwrap();
+
+    let output = broken_md_links(
+        &dir,
+        &[".", "-r", "--output-format", "json", "-v", "silent"],
+    );
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+
+    // Reports without findings are still valid JSON
+    let report = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    assert_eq!(report["findings"], serde_json::json!([]));
+    assert_eq!(report["summary"]["files_checked"], 1);
+    assert_eq!(report["summary"]["links_checked"], 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn skipped_symbolic_links_are_reported_with_their_target() {
+    let dir = TempDir::new();
+
+    fs::write(dir.join("README.md"), "# Docs\n").unwrap();
+    std::os::unix::fs::symlink("nowhere.md", dir.join("dangling.md")).unwrap();
+    std::os::unix::fs::symlink("README.md", dir.join("alias.md")).unwrap();
+
+    let output = broken_md_links(
+        &dir,
+        &[".", "-r", "--output-format", "json", "-v", "silent"],
+    );
+
+    assert!(output.status.success());
+
+    let report = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    let warnings = report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|finding| {
+            assert_eq!(finding["kind"], "warning");
+            (
+                finding["file"].as_str().unwrap(),
+                finding["error"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        warnings,
+        [
+            (
+                "./alias.md",
+                "item is a symbolic link, which is not followed, ignoring it"
+            ),
+            (
+                "./dangling.md",
+                "item is a broken symbolic link to 'nowhere.md', ignoring it"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn output_does_not_depend_on_the_creation_order() {
+    let files = [
+        (
+            "guide/a.md",
+            "# A\n\n[B](./b.md#missing) [Up](../README.md#nope)\n",
+        ),
+        (
+            "guide/b.md",
+            "# B\n\n[A](../guide/./a.md#missing) [C](c.md)\n",
+        ),
+        (
+            "README.md",
+            "# Home\n\n[A](guide/a.md#missing) [B](guide/../guide/b.md#missing)\n",
+        ),
+    ];
+
+    // Elapsed time at the beginning of each log line
+    let elapsed = regex::Regex::new(r"\d+\.\d+s\]").unwrap();
+
+    let run = |reversed: bool| {
+        let dir = TempDir::new();
+        fs::create_dir_all(dir.join("guide")).unwrap();
+
+        let mut files = files.to_vec();
+
+        if reversed {
+            files.reverse();
+        }
+
+        for (file, content) in files {
+            fs::write(dir.join(file), content).unwrap();
+        }
+
+        let output = broken_md_links(&dir, &[".", "-r", "-v", "debug"]);
+        assert_eq!(output.status.code(), Some(1));
+
+        elapsed
+            .replace_all(&String::from_utf8_lossy(&output.stdout), "s]")
+            .replace(dir.to_str().unwrap(), "<dir>")
+    };
+
+    let output = run(false);
+
+    assert_eq!(output.matches("broken link found").count(), 6);
+    assert_eq!(output, run(true));
+}
+
+#[test]
+fn malformed_mailto_addresses_are_reported_when_asked_to() {
+    let dir = TempDir::new();
+
+    fs::write(
+        dir.join("README.md"),
+        "[Valid](mailto:user@example.com?subject=Hello)\n\
+         [Invalid](mailto:user@example.com,not-an-address)\n\
+         [Scheme](x:not-a-file)\n",
+    )
+    .unwrap();
+
+    let output = broken_md_links(
+        &dir,
+        &[
+            "README.md",
+            "--check-mailto",
+            "--output-format",
+            "json",
+            "-v",
+            "silent",
+        ],
+    );
+
+    // Malformed addresses are only warnings
+    assert!(output.status.success());
+
+    let report = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+
+    assert_eq!(
+        report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|finding| (
+                finding["line"].as_u64().unwrap(),
+                finding["kind"].as_str().unwrap(),
+                finding["error"].as_str().unwrap()
+            ))
+            .collect::<Vec<_>>(),
+        [(
+            2,
+            "warning",
+            "malformed e-mail address 'not-an-address' in link 'mailto:user@example.com,not-an-address'"
+        )]
+    );
+
+    // Addresses are not validated by default
+    let output = broken_md_links(
+        &dir,
+        &["README.md", "--output-format", "json", "-v", "silent"],
+    );
+
+    let report = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    assert_eq!(report["findings"], serde_json::json!([]));
+}
+
+#[test]
+fn mis_cased_header_links_are_reported_as_headers() {
+    let dir = TempDir::new();
+
+    fs::write(dir.join("doc.md"), "# Getting Started\n").unwrap();
+    fs::write(dir.join("README.md"), "[x](doc.md#Getting-Started)\n").unwrap();
+
+    let output = broken_md_links(
+        &dir,
+        &["README.md", "--output-format", "json", "-v", "silent"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let report = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+    let finding = &report["findings"][0];
+
+    assert_eq!(finding["kind"], "anchor-case");
+    assert_eq!(
+        finding["error"],
+        "broken link found: header 'Getting-Started' not found in 'doc.md', but anchor 'getting-started' only differs by its casing"
+    );
+    assert_eq!(finding["suggestion"], "doc.md#getting-started");
+}
+
+#[test]
+fn placeholders_are_substituted_in_image_destinations() {
+    let dir = TempDir::new();
+
+    fs::create_dir_all(dir.join("assets")).unwrap();
+    fs::write(dir.join("assets/logo.png"), "").unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "![Logo](${ASSETS}/logo.png)\n\n![Banner](${ASSETS}/banner.png)\n\n![Icon](${ICONS}/icon.png)\n",
+    )
+    .unwrap();
+
+    let output = broken_md_links(
+        &dir,
+        &[
+            "README.md",
+            "--define",
+            "ASSETS=assets",
+            "--output-format",
+            "json",
+            "-v",
+            "silent",
+        ],
+    );
+
+    let report = serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+
+    assert_eq!(
+        report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|finding| (
+                finding["line"].as_u64().unwrap(),
+                finding["kind"].as_str().unwrap(),
+                finding["link_target"].as_str().unwrap()
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (3, "missing-image", "assets/banner.png"),
+            (5, "unresolved-placeholder", "${ICONS}/icon.png")
+        ]
+    );
+}
+
+#[test]
+fn github_annotations_are_written_once_per_finding() {
+    let dir = TempDir::new();
+
+    fs::write(dir.join("README.md"), "[A](a.md)\n").unwrap();
+    fs::write(dir.join("a.md"), "# A\n\n[Missing](missing.md)\n").unwrap();
+    fs::write(dir.join("orphan.md"), "[Gone](gone.md)\n").unwrap();
+
+    // Annotations of the checked files are written as they are found, the ones of the graph analysis once it's done
+    let output = broken_md_links(
+        &dir,
+        &[
+            ".",
+            "-r",
+            "--format",
+            "github",
+            "--graph-analysis",
+            "-v",
+            "silent",
+        ],
+    );
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let annotations = stdout.lines().collect::<Vec<_>>();
+
+    assert_eq!(annotations.len(), 3, "{}", stdout);
+    assert!(annotations[0].starts_with("::error file=a.md,line=3,"));
+    assert!(annotations[1].starts_with("::error file=orphan.md,line=1,"));
+    assert!(annotations[2].starts_with("::notice file="));
+    assert!(annotations[2].contains("title=unreachable-cluster::"));
+}
@@ -0,0 +1,24 @@
+//! Integration tests of the headers cache shared between files with the same content
+#![cfg(feature = "content-hash")]
+
+mod common;
+
+use broken_md_links::{CheckerOptions, FileLinksCache, LinksCache};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn identical_files_are_parsed_once() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("vendor")).unwrap();
+    fs::write(dir.join("guide.md"), "# Guide\n\n## Usage\n").unwrap();
+    fs::write(dir.join("vendor/guide.md"), "# Guide\n\n## Usage\n").unwrap();
+
+    let mut cache = FileLinksCache::new();
+
+    assert_eq!(cache.warm(&dir, &CheckerOptions::default()), 2);
+    assert_eq!((cache.dedupe_hits(), cache.stored_contents()), (1, 1));
+
+    // Each cache has its own contents
+    assert_eq!(FileLinksCache::new().dedupe_hits(), 0);
+}
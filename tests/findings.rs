@@ -0,0 +1,192 @@
+//! Integration tests of the processing and reporting of findings
+
+mod common;
+
+use broken_md_links::report_diff::{github_annotations, sarif_report, JsonReport, ReportSummary};
+use broken_md_links::{
+    broken_targets, check_broken_links_simple, check_broken_links_with, dedupe_findings,
+    group_by_target, CheckerOptions, FileLinksCache, TargetsCache,
+};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn identical_findings_are_deduplicated() {
+    let dir = TempDir::new();
+    fs::write(dir.join("types.md"), "# Types\n").unwrap();
+    fs::write(
+        dir.join("api.md"),
+        "[Options](types.md#options)\n\n[Missing](missing.md)\n\n[Options](types.md#options)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("api.md"), &CheckerOptions::default()).unwrap();
+    let deduped = dedupe_findings(&errors);
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(deduped.len(), 2);
+    assert_eq!((deduped[0].count(), &deduped[0].lines), (2, &vec![1, 5]));
+    assert_eq!((deduped[1].count(), &deduped[1].lines), (1, &vec![3]));
+    assert_eq!(deduped[0].target.as_deref(), Some("types.md#options"));
+
+    // Destinations written differently are not collapsed, even if they lead to the same problem
+    fs::write(
+        dir.join("api.md"),
+        "[Options](types.md#options)\n\n[Options](./types.md#options)\n",
+    )
+    .unwrap();
+
+    let errors =
+        check_broken_links_simple(&dir.join("api.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(errors[0].error, errors[1].error);
+    assert_eq!(dedupe_findings(&errors).len(), 2);
+}
+
+#[test]
+fn findings_are_grouped_by_target() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("guide")).unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "[Setup](guide/setup.md)\n\n[Old](old.md)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("guide/usage.md"),
+        "[Setup](setup.md#requirements)\n",
+    )
+    .unwrap();
+
+    let errors = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+    let groups = group_by_target(&errors);
+
+    assert_eq!(groups.len(), 2);
+    assert!(groups[0].target.ends_with("guide/setup.md"));
+    assert_eq!(
+        groups[0]
+            .locations
+            .iter()
+            .map(|location| location.line)
+            .collect::<Vec<_>>(),
+        vec![1, 1]
+    );
+    assert_eq!(groups[1].locations.len(), 1);
+}
+
+#[test]
+fn broken_targets_are_sorted_by_link_count() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("guide")).unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "[Setup](guide/setup.md) [Old](old.md)\n\n[Setup again](guide/setup.md)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("guide/usage.md"),
+        "[Setup](setup.md#requirements)\n",
+    )
+    .unwrap();
+
+    let errors = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+    let targets = broken_targets(&errors);
+
+    assert_eq!(
+        targets
+            .iter()
+            .map(|target| target.count)
+            .collect::<Vec<_>>(),
+        vec![3, 1]
+    );
+    assert!(targets[0].target.ends_with("guide/setup.md"));
+    assert_eq!(
+        targets[0].files,
+        vec![dir.join("README.md"), dir.join("guide/usage.md")]
+    );
+}
+
+#[test]
+fn json_reports_summarize_the_findings() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("README.md"),
+        "# Docs\n\n[Setup](setup.md) [Usage](#usage) [Docs](#docs)\n",
+    )
+    .unwrap();
+
+    let mut targets_cache = TargetsCache::new();
+    let findings = check_broken_links_with(
+        &dir,
+        true,
+        &CheckerOptions::default(),
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+    let report = JsonReport::new(findings, None, targets_cache.take_check_stats());
+
+    assert_eq!(
+        report.summary,
+        ReportSummary {
+            files_checked: 1,
+            links_checked: 3,
+            broken_count: 2
+        }
+    );
+}
+
+#[test]
+fn sarif_reports_have_a_result_per_finding() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("README.md"),
+        "# Docs\n\nSee [Setup](setup.md) and [Usage](#usage)\n",
+    )
+    .unwrap();
+
+    let findings =
+        check_broken_links_simple(&dir.join("README.md"), &CheckerOptions::default()).unwrap();
+    let sarif = sarif_report(&findings);
+
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(
+        sarif["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let result = &sarif["runs"][0]["results"][1];
+
+    assert_eq!(result["ruleId"], "missing-header");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        format!("file://{}", dir.join("README.md").display())
+    );
+    assert_eq!(result["level"], "error");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["region"],
+        serde_json::json!({ "startLine": 3, "startColumn": 27 })
+    );
+}
+
+#[test]
+fn github_annotations_locate_the_findings() {
+    let dir = TempDir::new();
+    fs::write(dir.join("guide.md"), "# Guide\n\nSee [Setup](setup.md)\n").unwrap();
+
+    let findings =
+        check_broken_links_simple(&dir.join("guide.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        github_annotations(&findings),
+        format!(
+            "::error file={},line=3,col=5,title=missing-target::broken link found: path '{}' does not exist\n",
+            dir.join("guide.md").display(),
+            dir.join("setup.md").display()
+        )
+    );
+}
@@ -0,0 +1,169 @@
+//! Integration tests of the git hooks installed by `install-hook`, run against temporary git repositories
+
+mod common;
+
+use common::TempDir;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Create an empty git repository in the temporary directory
+fn repository() -> TempDir {
+    let dir = TempDir::new();
+
+    assert!(git(&dir, &["init", "--quiet"]).status.success());
+    dir
+}
+
+/// Run a git command in a repository
+fn git(dir: &Path, args: &[&str]) -> Output {
+    Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Run the checker in a repository
+fn broken_md_links(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_broken-md-links"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn pre_commit_hook_blocks_broken_links() {
+    let dir = repository();
+
+    assert!(broken_md_links(&dir, &["install-hook"]).status.success());
+
+    fs::write(dir.join("guide.md"), "# Guide\n").unwrap();
+    fs::write(dir.join("README.md"), "[Guide](guide.md#guide)\n").unwrap();
+    assert!(git(&dir, &["add", "."]).status.success());
+    assert!(git(&dir, &["commit", "--quiet", "-m", "Valid links"])
+        .status
+        .success());
+
+    fs::write(dir.join("README.md"), "[Guide](guide.md#setup)\n").unwrap();
+    assert!(git(&dir, &["add", "."]).status.success());
+    assert!(!git(&dir, &["commit", "--quiet", "-m", "Broken link"])
+        .status
+        .success());
+
+    // The staged content is checked, not the working tree's
+    fs::write(dir.join("README.md"), "[Guide](guide.md)\n").unwrap();
+    assert!(!git(&dir, &["commit", "--quiet", "-m", "Broken link"])
+        .status
+        .success());
+
+    // Unstaged files are not checked
+    fs::write(dir.join("draft.md"), "[Missing](missing.md)\n").unwrap();
+    assert!(git(&dir, &["add", "README.md"]).status.success());
+    assert!(git(&dir, &["commit", "--quiet", "-m", "Fixed link"])
+        .status
+        .success());
+
+    // Staged files starting with a dash are not taken for options
+    fs::write(dir.join("-v.md"), "[Missing](missing.md)\n").unwrap();
+    assert!(git(&dir, &["add", "--", "-v.md"]).status.success());
+    assert!(!git(&dir, &["commit", "--quiet", "-m", "Broken link"])
+        .status
+        .success());
+}
+
+#[test]
+fn pre_commit_hook_uses_the_repository_configuration() {
+    let dir = repository();
+
+    assert!(broken_md_links(&dir, &["install-hook"]).status.success());
+
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    fs::write(
+        dir.join("broken-md-links.toml"),
+        "ignore-header-links = true\n",
+    )
+    .unwrap();
+    fs::write(dir.join("guide.md"), "# Guide\n").unwrap();
+    fs::write(dir.join("docs/setup.md"), "[Guide](../guide.md#setup)\n").unwrap();
+    assert!(git(&dir, &["add", "."]).status.success());
+    assert!(git(&dir, &["commit", "--quiet", "-m", "Headers ignored"])
+        .status
+        .success());
+
+    fs::write(
+        dir.join("broken-md-links.toml"),
+        "ignore-header-links = false\n",
+    )
+    .unwrap();
+    fs::write(dir.join("docs/setup.md"), "[Guide](../guide.md#setup) \n").unwrap();
+    assert!(git(&dir, &["add", "."]).status.success());
+    assert!(!git(&dir, &["commit", "--quiet", "-m", "Headers"])
+        .status
+        .success());
+}
+
+#[test]
+fn existing_hooks_are_preserved() {
+    let dir = repository();
+
+    // Hooks are located in the directory configured with `core.hooksPath`
+    assert!(git(&dir, &["config", "core.hooksPath", ".githooks"])
+        .status
+        .success());
+    fs::create_dir_all(dir.join(".githooks")).unwrap();
+
+    let hook = dir.join(".githooks/pre-push");
+    let script = "#!/bin/sh\n\necho 'Pushing'\n";
+    fs::write(&hook, script).unwrap();
+
+    assert!(
+        !broken_md_links(&dir, &["install-hook", "--hook", "pre-push"])
+            .status
+            .success()
+    );
+    assert_eq!(fs::read_to_string(&hook).unwrap(), script);
+
+    assert!(
+        broken_md_links(&dir, &["install-hook", "--hook", "pre-push", "--append"])
+            .status
+            .success()
+    );
+    let installed = fs::read_to_string(&hook).unwrap();
+    assert!(installed.starts_with(script) && installed.contains("broken-md-links"));
+
+    // Installing again updates the check instead of adding it twice
+    assert!(
+        broken_md_links(&dir, &["install-hook", "--hook", "pre-push"])
+            .status
+            .success()
+    );
+    assert_eq!(fs::read_to_string(&hook).unwrap(), installed);
+
+    assert!(
+        broken_md_links(&dir, &["uninstall-hook", "--hook", "pre-push"])
+            .status
+            .success()
+    );
+    assert_eq!(fs::read_to_string(&hook).unwrap(), script);
+    assert!(
+        !broken_md_links(&dir, &["uninstall-hook", "--hook", "pre-push"])
+            .status
+            .success()
+    );
+
+    // Hooks which only run the checker are removed
+    assert!(
+        broken_md_links(&dir, &["install-hook", "--hook", "pre-push", "--force"])
+            .status
+            .success()
+    );
+    assert!(
+        broken_md_links(&dir, &["uninstall-hook", "--hook", "pre-push"])
+            .status
+            .success()
+    );
+    assert!(!hook.exists());
+}
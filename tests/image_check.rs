@@ -0,0 +1,57 @@
+//! Integration tests of the decoding of images
+#![cfg(feature = "image-check")]
+
+mod common;
+
+use broken_md_links::validate::{validate_image_content, ValidationResult};
+use broken_md_links::{check_broken_links_simple, CheckerOptions};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn undecodable_images_are_reported() {
+    let dir = TempDir::new();
+    fs::write(dir.join("diagram.png"), "Not an image").unwrap();
+    fs::write(dir.join("doc.md"), "![Diagram](diagram.png)\n").unwrap();
+
+    let options = CheckerOptions {
+        check_image_dimensions: true,
+        ..CheckerOptions::default()
+    };
+    let warnings = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].error.contains("is not a valid PNG image"));
+}
+
+#[test]
+fn image_content_is_validated() {
+    let dir = TempDir::new();
+    // 1x1 transparent GIF image
+    fs::write(
+        dir.join("pixel.gif"),
+        b"GIF89a\x01\x00\x01\x00\x80\x00\x00\x00\x00\x00\xff\xff\xff!\xf9\x04\x01\x00\x00\x00\x00,\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02D\x01\x00;",
+    ).unwrap();
+    fs::copy(dir.join("pixel.gif"), dir.join("pixel.png")).unwrap();
+    fs::write(dir.join("diagram.png"), "Not an image").unwrap();
+    fs::write(dir.join("diagram.svg"), "<svg></svg>").unwrap();
+
+    assert_eq!(
+        validate_image_content(&dir.join("pixel.gif")),
+        ValidationResult::Valid
+    );
+    assert_eq!(
+        validate_image_content(&dir.join("diagram.svg")),
+        ValidationResult::Valid
+    );
+
+    assert!(matches!(
+        validate_image_content(&dir.join("pixel.png")),
+        ValidationResult::Warning(warning) if warning.ends_with("is a GIF image but its extension is for PNG images")
+    ));
+
+    assert!(matches!(
+        validate_image_content(&dir.join("diagram.png")),
+        ValidationResult::Warning(warning) if warning.contains("is not a valid PNG image")
+    ));
+}
@@ -0,0 +1,51 @@
+//! Integration tests of incremental checks
+
+mod common;
+
+use broken_md_links::incremental::{check_broken_links_incremental, IncrementalState};
+use broken_md_links::{check_broken_links_simple, CheckerOptions, FileLinksCache, TargetsCache};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn unchanged_files_are_not_checked_again() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "[Usage](docs/guide.md#usage) and [Broken](missing.md)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("docs/guide.md"), "# Guide\n\n## Usage\n").unwrap();
+    fs::write(dir.join("docs/other.md"), "[Guide](guide.md)\n").unwrap();
+
+    let options = CheckerOptions::default();
+    let state_dir = TempDir::new();
+    let state_file = state_dir.join("state.json");
+
+    let run = || {
+        let mut state = IncrementalState::load(&state_file, &options).unwrap_or_default();
+        let errors = check_broken_links_incremental(
+            &dir,
+            true,
+            &options,
+            &mut FileLinksCache::new(),
+            &mut TargetsCache::new(),
+            &mut state,
+        )
+        .unwrap();
+        state.save(&state_file).unwrap();
+        (errors, state.rechecked_files().len())
+    };
+
+    let full = || check_broken_links_simple(&dir, &options).unwrap();
+
+    assert_eq!(run(), (full(), 3));
+    assert_eq!(run(), (full(), 0));
+
+    // Renaming the header invalidates the file linking to it, but not the one linking to the file only
+    fs::write(dir.join("docs/guide.md"), "# Guide\n\n## How to use\n").unwrap();
+
+    assert_eq!(run(), (full(), 2));
+    assert_eq!(full().len(), 2);
+}
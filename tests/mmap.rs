@@ -0,0 +1,61 @@
+//! Integration tests of the checks of memory-mapped files
+#![cfg(feature = "mmap")]
+
+mod common;
+
+use broken_md_links::{check_broken_links_simple, BrokenLinkKind, CheckerOptions};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn mapped_files_are_checked_like_files_read_in_memory() {
+    let dir = TempDir::new();
+
+    // Byte order marks are skipped, empty files are not mapped, and invalid UTF-8 is decoded in memory
+    fs::write(dir.join("bom.md"), "\u{FEFF}# Title\n\n[Title](#title)\n").unwrap();
+    fs::write(dir.join("empty.md"), "").unwrap();
+    fs::write(
+        dir.join("latin1.md"),
+        b"# Caf\xE9\n\n[Missing](missing.md)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "[Bom](bom.md#title) [Empty](empty.md)\n",
+    )
+    .unwrap();
+
+    let options = CheckerOptions::default();
+
+    let check = || {
+        check_broken_links_simple(&dir, &options)
+            .unwrap()
+            .into_iter()
+            .map(|error| {
+                let file = error.file.file_name().unwrap().to_str().unwrap().to_owned();
+                (file, error.kind)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        check(),
+        [
+            ("latin1.md".to_owned(), BrokenLinkKind::Warning),
+            ("latin1.md".to_owned(), BrokenLinkKind::MissingTarget)
+        ]
+    );
+
+    // Files modified between two checks are mapped again
+    fs::write(dir.join("bom.md"), "\u{FEFF}# Other\n\n[Title](#title)\n").unwrap();
+
+    assert_eq!(
+        check(),
+        [
+            ("README.md".to_owned(), BrokenLinkKind::MissingHeader),
+            ("bom.md".to_owned(), BrokenLinkKind::MissingHeader),
+            ("latin1.md".to_owned(), BrokenLinkKind::Warning),
+            ("latin1.md".to_owned(), BrokenLinkKind::MissingTarget)
+        ]
+    );
+}
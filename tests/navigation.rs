@@ -0,0 +1,216 @@
+//! Integration tests of the analysis of the links between the files of a directory
+
+mod common;
+
+use broken_md_links::link_graph::analyze_link_graph;
+use broken_md_links::partials::check_partial_links;
+use broken_md_links::{
+    check_broken_links_with, check_navigation_order, count_links_in_file, find_unreachable_files,
+    generate_link_map, BrokenLinkKind, CheckerError, CheckerOptions, FileLinksCache, Severity,
+    TargetsCache,
+};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn links_are_counted() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "\
+[inline](a.md) [reference][ref] <https://example.com> ![image](b.png)
+
+[ref]: c.md
+",
+    )
+    .unwrap();
+
+    assert_eq!(count_links_in_file(&dir.join("doc.md")), Ok(4));
+}
+
+#[test]
+fn navigation_order_is_checked() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("SUMMARY.md"),
+        "\
+* [Introduction](README.md)
+* [Intro](01-intro.md)
+* [Usage](03-usage.md)
+* [Setup](02-setup.md)
+  * [Setup on Windows](02-windows.md#requirements)
+* [Guide](guide/01-guide.md)
+",
+    )
+    .unwrap();
+
+    let problems =
+        check_navigation_order(&dir.join("SUMMARY.md"), &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        problems,
+        vec![
+            (
+                4,
+                "entry '02-setup.md' is listed after '03-usage.md'".to_owned()
+            ),
+            (
+                5,
+                "entry '02-windows.md' is listed after '03-usage.md'".to_owned()
+            ),
+            (
+                5,
+                "numeric prefix of '02-windows.md' is also used by '02-setup.md'".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn link_map_lists_the_links_of_each_file() {
+    let dir = TempDir::new();
+    fs::write(dir.join("README.md"), "# Docs\n\n[Guide](guide.md#setup) and [missing][ref]\n\n![Logo](logo.png)\n\n[ref]: missing.md\n").unwrap();
+    fs::write(dir.join("guide.md"), "No link here.\n").unwrap();
+
+    let map = generate_link_map(&dir, &CheckerOptions::default()).unwrap();
+    let links = &map[&dir.join("README.md")];
+
+    assert_eq!(map[&dir.join("guide.md")], vec![]);
+    assert_eq!(
+        links
+            .iter()
+            .map(|link| (link.line, link.destination.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(3, "guide.md#setup"), (3, "missing.md"), (5, "logo.png"),]
+    );
+    assert_eq!(
+        (links[0].path(), links[0].fragment()),
+        ("guide.md", Some("setup"))
+    );
+    assert_eq!(links[1].reference.as_deref(), Some("ref"));
+    assert!(links[2].is_image);
+
+    // Notebooks which can't be parsed are reported instead of having no link
+    #[cfg(feature = "notebooks")]
+    {
+        fs::write(dir.join("broken.ipynb"), "not a notebook").unwrap();
+        assert!(generate_link_map(&dir.join("broken.ipynb"), &CheckerOptions::default()).is_err());
+    }
+}
+
+#[test]
+fn unreachable_files_are_found() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("guide")).unwrap();
+    fs::write(dir.join("README.md"), "[Guide](guide/index.md)\n").unwrap();
+    fs::write(
+        dir.join("guide/index.md"),
+        "[Setup](setup.md#requirements)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("guide/setup.md"), "# Requirements\n").unwrap();
+    fs::write(dir.join("guide/old.md"), "[Setup](setup.md)\n").unwrap();
+
+    let unreachable =
+        find_unreachable_files(&dir, &[dir.join("README.md")], &CheckerOptions::default()).unwrap();
+
+    assert_eq!(
+        unreachable,
+        vec![dir.join("guide/old.md").canonicalize().unwrap()]
+    );
+
+    // Entry points must exist
+    assert!(matches!(
+        find_unreachable_files(&dir, &[dir.join("index.md")], &CheckerOptions::default()),
+        Err(CheckerError::MissingEntryPoint { .. })
+    ));
+}
+
+#[test]
+fn link_graph_is_analyzed() {
+    let dir = TempDir::new();
+    fs::write(dir.join("README.md"), "[Guide](guide.md) [Intro](intro.md)").unwrap();
+    fs::write(dir.join("guide.md"), "[Home](README.md) [FAQ](faq.md)").unwrap();
+    fs::write(dir.join("faq.md"), "[Guide](guide.md)").unwrap();
+    fs::write(dir.join("intro.md"), "[Setup](setup.md)").unwrap();
+    fs::write(dir.join("setup.md"), "[Usage](usage.md)").unwrap();
+    fs::write(dir.join("usage.md"), "The end.").unwrap();
+    fs::write(dir.join("old.md"), "[Older](older.md)").unwrap();
+    fs::write(dir.join("older.md"), "[Old](old.md)").unwrap();
+
+    let mut targets_cache = TargetsCache::new();
+    check_broken_links_with(
+        &dir,
+        true,
+        &CheckerOptions::default(),
+        &mut FileLinksCache::new(),
+        &mut targets_cache,
+    )
+    .unwrap();
+
+    let root = dir.canonicalize().unwrap();
+    let findings = analyze_link_graph(
+        targets_cache.link_graph(),
+        &root,
+        &[root.join("README.md")],
+        Severity::Warning,
+    );
+
+    let messages = findings
+        .iter()
+        .map(|finding| (finding.kind, finding.error.as_str()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(messages, vec![
+        (BrokenLinkKind::UnreachableCluster, "cluster of 2 files unreachable from the entry points: old.md, older.md"),
+        (BrokenLinkKind::LinkCycle, "circular references: faq.md -> guide.md -> faq.md"),
+        (BrokenLinkKind::DeadEndChain, "dead-end chain: intro.md -> setup.md -> usage.md, which doesn't link to any other file"),
+    ]);
+}
+
+#[test]
+fn partial_links_are_checked_from_including_pages() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("_includes")).unwrap();
+    fs::create_dir_all(dir.join("guide")).unwrap();
+    fs::write(
+        dir.join("_includes/footer.md"),
+        "[License](../LICENSE.md) [Contributing](contributing.md)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("_includes/contributing.md"), "# Contributing\n").unwrap();
+    fs::write(dir.join("LICENSE.md"), "# License\n").unwrap();
+    fs::write(dir.join("README.md"), "# Docs\n\n{% include footer.md %}\n").unwrap();
+    fs::write(
+        dir.join("guide/setup.md"),
+        "# Setup\n\n{% include footer.md %}\n",
+    )
+    .unwrap();
+
+    let options = CheckerOptions {
+        partial_dirs: vec!["_includes".to_owned()],
+        ..CheckerOptions::default()
+    };
+    let findings = check_partial_links(&dir, &options).unwrap();
+
+    assert_eq!(findings.iter().map(|finding| (finding.kind, finding.error.as_str())).collect::<Vec<_>>(), vec![
+        (BrokenLinkKind::PartialContextLink, "link to '../LICENSE.md' only resolves from the partial's directory, not once included in 'README.md'"),
+        (BrokenLinkKind::PartialContextLink, "link to 'contributing.md' only resolves from the partial's directory, not once included in 'README.md'"),
+        (BrokenLinkKind::PartialContextLink, "link to 'contributing.md' only resolves from the partial's directory, not once included in 'guide/setup.md'"),
+    ]);
+    assert!(findings
+        .iter()
+        .all(|finding| finding.file == dir.join("_includes/footer.md")));
+
+    // Findings can be reported as warnings instead
+    let options = CheckerOptions {
+        partial_context_severity: Severity::Warning,
+        ..options
+    };
+    let findings = check_partial_links(&dir, &options).unwrap();
+
+    assert_eq!(findings.len(), 3);
+    assert!(findings
+        .iter()
+        .all(|finding| finding.severity == Severity::Warning));
+}
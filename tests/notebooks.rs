@@ -0,0 +1,43 @@
+//! Integration tests of the checks of Jupyter notebooks
+#![cfg(feature = "notebooks")]
+
+mod common;
+
+use broken_md_links::{check_broken_links_simple, CheckerOptions};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn notebook_cells_are_checked() {
+    let dir = TempDir::new();
+    fs::write(dir.join("guide.md"), "# Guide\n").unwrap();
+    fs::write(
+        dir.join("doc.md"),
+        "[Usage](tutorial.ipynb#usage) and [other](tutorial.ipynb#other)\n",
+    )
+    .unwrap();
+    fs::write(dir.join("tutorial.ipynb"), r###"{
+  "cells": [
+    { "cell_type": "code", "source": ["print('[Not a link](missing.md)')"] },
+    { "cell_type": "markdown", "source": ["# Setup\n", "\n", "See [the guide](guide.md) and [this page](missing.md)\n"] },
+    { "cell_type": "markdown", "source": "## Usage\n\n[Setup](#setup)\n\n[Missing](#missing)" }
+  ]
+}"###).unwrap();
+
+    let errors = check_broken_links_simple(&dir, &CheckerOptions::default()).unwrap();
+    let locations = errors.iter().map(|err| {
+        (
+            err.file.strip_prefix(&dir).unwrap().to_str().unwrap(),
+            err.location(),
+        )
+    });
+
+    assert_eq!(
+        locations.collect::<Vec<_>>(),
+        vec![
+            ("doc.md", ":1".to_owned()),
+            ("tutorial.ipynb", " (cell 2, line 3)".to_owned()),
+            ("tutorial.ipynb", " (cell 3, line 5)".to_owned())
+        ]
+    );
+}
@@ -0,0 +1,226 @@
+//! Integration tests of the options presets and of the files configuring the checker
+
+mod common;
+
+use broken_md_links::directory_config::{ConfigOverrides, DirectoryConfigs, GitignorePatterns};
+use broken_md_links::{
+    check_broken_links_simple, check_broken_links_with, load_placeholder_variables, slugify_with,
+    BrokenLinkKind, CheckerOptions, FileLinksCache, LinksCache, SchemeVerdict, SlugAlgorithm,
+    TargetsCache,
+};
+use common::TempDir;
+use std::fs;
+
+#[test]
+fn directory_configurations_are_loaded() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("handbook/drafts")).unwrap();
+    fs::write(dir.join("broken-md-links.toml"), "only-files = true\n").unwrap();
+    fs::write(dir.join("handbook/broken-md-links.toml"), "only-files = false\nslug-algorithm = \"gitlab\"\nexclude = [\"drafts/\"]\ntargets-only = [\"generated/\"]\n").unwrap();
+    fs::write(dir.join("README.md"), "[Handbook](handbook)\n").unwrap();
+    fs::write(
+        dir.join("handbook/index.md"),
+        "# Café & Co\n\n[Handbook](.)\n[Header](#café-co)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("handbook/drafts/wip.md"),
+        "[Missing](missing.md)\n",
+    )
+    .unwrap();
+
+    let options = CheckerOptions {
+        directory_configs: DirectoryConfigs::load(&dir, false).unwrap(),
+        ..CheckerOptions::default()
+    };
+
+    assert!(options.resolve(&dir.join("README.md")).only_files);
+    assert!(!options.resolve(&dir.join("handbook/index.md")).only_files);
+    assert_eq!(
+        options.slugs_for(&dir.join("handbook/index.md")).algorithm,
+        SlugAlgorithm::GitLab
+    );
+    assert_eq!(
+        options.slugs_for(&dir.join("README.md")).algorithm,
+        SlugAlgorithm::Default
+    );
+    assert!(options
+        .directory_configs
+        .is_excluded(&dir.join("handbook/drafts/wip.md")));
+    assert!(options
+        .directory_configs
+        .is_target_only(&dir.join("handbook/generated/api.md")));
+
+    let errors = check_broken_links_simple(&dir, &options).unwrap();
+    assert_eq!(
+        errors
+            .iter()
+            .map(|err| err.file.clone())
+            .collect::<Vec<_>>(),
+        vec![dir.join("README.md")]
+    );
+
+    // Options provided on the command line take precedence
+    let mut options = options;
+    options.directory_configs.cli_overrides = ConfigOverrides {
+        only_files: Some(true),
+        ..ConfigOverrides::default()
+    };
+    assert!(options.resolve(&dir.join("handbook/index.md")).only_files);
+}
+
+#[test]
+fn gitignore_patterns_are_matched() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("assets")).unwrap();
+    let patterns = GitignorePatterns::new(&dir, &["assets/".to_owned()]).unwrap();
+
+    assert!(patterns.matches(&dir.join("assets/logo.md")));
+    assert!(!patterns.matches(&dir.join("README.md")));
+
+    // Files outside of the patterns' directory never match
+    assert!(!patterns.matches(&std::env::temp_dir().join("assets/logo.md")));
+
+    assert!(GitignorePatterns::new(&dir, &["{assets".to_owned()]).is_err());
+}
+
+#[test]
+fn targets_only_patterns_match_files() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.join("reference/generated")).unwrap();
+    fs::write(
+        dir.join("README.md"),
+        "[API](reference/generated/api.md#functions) [Types](reference/generated/api.md#types)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("reference/generated/api.md"),
+        "# API\n\n## Functions\n\n[Missing](missing.md)\n",
+    )
+    .unwrap();
+
+    let options = CheckerOptions {
+        targets_only: GitignorePatterns::new(&dir, &["reference/generated/".to_owned()]).unwrap(),
+        ..CheckerOptions::default()
+    };
+
+    assert!(options.is_target_only(&dir.join("reference/generated/api.md")));
+    assert!(!options.is_target_only(&dir.join("README.md")));
+
+    let mut cache = FileLinksCache::new();
+    let before = cache.stats();
+    let errors =
+        check_broken_links_with(&dir, true, &options, &mut cache, &mut TargetsCache::new())
+            .unwrap();
+
+    // The generated file is not checked, but links to its headers are
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].error.contains("'types'"));
+    assert_eq!(cache.stats().targets_only - before.targets_only, 1);
+}
+
+#[test]
+fn scheme_handlers_validate_links() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "[Ticket](jira://PROJ-123)\n[Typo](jira://proj123)\n[Page](confluence://SPACE/Page)\n",
+    )
+    .unwrap();
+
+    let mut options = CheckerOptions::default();
+
+    options.scheme_handler("jira", |dest| {
+        match dest.trim_start_matches("jira://").split_once('-') {
+            Some((project, _)) if project.chars().all(|c| c.is_ascii_uppercase()) => {
+                SchemeVerdict::Valid
+            }
+            _ => SchemeVerdict::Invalid("not an issue key".to_owned()),
+        }
+    });
+
+    let errors = check_broken_links_simple(&dir.join("doc.md"), &options).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        (errors[0].line, errors[0].kind),
+        (2, BrokenLinkKind::InvalidSchemeLink)
+    );
+}
+
+#[test]
+fn placeholder_variables_are_loaded() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("config.toml"),
+        "[variables]\nversion = 1.4\nlang = \"en\"\n",
+    )
+    .unwrap();
+
+    let variables = load_placeholder_variables(&dir.join("config.toml")).unwrap();
+
+    assert_eq!(variables.get("version").map(String::as_str), Some("1.4"));
+    assert_eq!(variables.get("lang").map(String::as_str), Some("en"));
+}
+
+#[test]
+fn mdbook_preset_checks_includes() {
+    let dir = TempDir::new();
+
+    let options = CheckerOptions::for_mdbook().build();
+
+    assert!(options.only_files && options.check_mdbook_includes);
+    assert_eq!(slugify_with("Café\t& Co", &options.slugs), "café--co");
+
+    fs::write(
+        dir.join("listing.rs"),
+        "// ANCHOR: main\nfn main() {}\n// ANCHOR_END: main\n",
+    )
+    .unwrap();
+    fs::write(dir.join("chapter.md"), "{{#include listing.rs:main}}\n\n{{#include listing.rs:setup}}\n\n{{#include missing.rs}}\n").unwrap();
+
+    // Includes are only checked with this preset
+    assert_eq!(
+        check_broken_links_simple(&dir, &CheckerOptions::for_github().build())
+            .unwrap()
+            .len(),
+        0
+    );
+
+    let errors = check_broken_links_simple(&dir, &options).unwrap();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].error,
+        "anchor 'setup' does not exist in included file 'listing.rs'"
+    );
+    assert_eq!(errors[1].error, "included file 'missing.rs' does not exist");
+
+    // Escaped directives are not processed by mdBook
+    fs::write(dir.join("chapter.md"), "\\{{#include missing.rs}}\n").unwrap();
+    assert_eq!(check_broken_links_simple(&dir, &options).unwrap().len(), 0);
+}
+
+#[test]
+fn mkdocs_docs_dir_is_read_from_the_configuration() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("mkdocs.yml"),
+        "site_name: Project\nnav:\n  - Home: index.md\n",
+    )
+    .unwrap();
+    assert_eq!(CheckerOptions::mkdocs_docs_dir(&dir), Ok(dir.join("docs")));
+
+    fs::write(
+        dir.join("mkdocs.yml"),
+        "site_name: Project\ndocs_dir: 'documentation'\n",
+    )
+    .unwrap();
+    assert_eq!(
+        CheckerOptions::mkdocs_docs_dir(&dir),
+        Ok(dir.join("documentation"))
+    );
+
+    fs::remove_file(dir.join("mkdocs.yml")).unwrap();
+    assert!(CheckerOptions::mkdocs_docs_dir(&dir).is_err());
+}
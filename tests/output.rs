@@ -0,0 +1,58 @@
+//! Integration tests of the messages written while several directories are checked at the same time
+
+mod common;
+
+use common::TempDir;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn parallel_checks_do_not_interleave_messages() {
+    let dir = TempDir::new();
+
+    for d in 0..16 {
+        fs::create_dir_all(dir.join(format!("dir-{}", d))).unwrap();
+
+        for f in 0..8 {
+            let links = (0..6)
+                .map(|l| format!("[Missing](missing-{}.md) [Header](#missing-{})\n", l, l))
+                .collect::<String>();
+
+            fs::write(dir.join(format!("dir-{}/file-{}.md", d, f)), links).unwrap();
+        }
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_broken-md-links"))
+        .current_dir(&dir)
+        .args([".", "-r", "--max-concurrent-dirs", "16", "-v", "info"])
+        .env_remove("GITHUB_ACTIONS")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut current_file = None;
+    let mut checked_files = 0;
+
+    for line in stdout.lines() {
+        // Every line is a complete message
+        assert!(line.starts_with('\u{1b}'), "corrupted line: {:?}", line);
+
+        if let Some((_, file)) = line.split_once("Analyzing: ") {
+            current_file = Some(file.trim_start_matches("./").to_owned());
+            checked_files += 1;
+        } else if line.contains("broken link found") {
+            let file = current_file.as_ref().expect("finding before any file");
+
+            assert!(
+                line.contains(file.as_str()),
+                "finding of another file than '{}': {:?}",
+                file,
+                line
+            );
+        }
+    }
+
+    assert_eq!(checked_files, 16 * 8);
+}
@@ -0,0 +1,53 @@
+//! Integration tests of the JSON Schema of the reports
+#![cfg(feature = "schema")]
+
+mod common;
+
+use common::TempDir;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn reports_match_the_schema() {
+    let dir = TempDir::new();
+
+    fs::write(
+        dir.join("README.md"),
+        "# Docs\n\n[Setup](setup.md) [Usage](#usage)\n\n## Title\n\n## Title\n",
+    )
+    .unwrap();
+    fs::write(dir.join("guide.md"), "[Setup](setup.md)\n").unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_broken-md-links"))
+            .current_dir(&dir)
+            .args(args)
+            .output()
+            .unwrap();
+
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    let schema = run(&["schema"]);
+    let validator = jsonschema::validator_for(&schema).unwrap();
+
+    for args in [
+        &[".", "-r", "--format", "json"][..],
+        &[".", "-r", "--format", "json", "--group-by-target"][..],
+    ] {
+        let report = run(args);
+
+        assert_eq!(report["format_version"], 1);
+        assert!(report["findings"].as_array().unwrap().len() >= 3);
+        assert_eq!(report["broken_targets"][0]["count"], 2);
+        assert_eq!(report["findings"][1]["column"], 19);
+        assert_eq!(report["findings"][1]["link_text"], "Usage");
+
+        let errors = validator
+            .iter_errors(&report)
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>();
+
+        assert!(errors.is_empty(), "invalid report {}: {:?}", report, errors);
+    }
+}
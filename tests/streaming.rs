@@ -0,0 +1,58 @@
+//! Integration tests of the asynchronous streaming API
+#![cfg(feature = "async")]
+
+mod common;
+
+use broken_md_links::{check_broken_links_streaming, CheckerOptions};
+use common::TempDir;
+use std::fs;
+use tokio_stream::StreamExt;
+
+#[test]
+fn findings_are_streamed() {
+    let dir = TempDir::new();
+    fs::write(dir.join("a.md"), "[Missing](missing.md)\n").unwrap();
+    fs::write(
+        dir.join("b.md"),
+        "[Missing](missing.md)\n\n[A](a.md#nope)\n",
+    )
+    .unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let lines = runtime.block_on(async {
+        check_broken_links_streaming(&dir, CheckerOptions::default())
+            .map(|result| {
+                result.map(|error| (error.file.file_name().unwrap().to_owned(), error.line))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .await
+    });
+
+    assert_eq!(
+        lines,
+        Ok(vec![
+            ("a.md".into(), 1),
+            ("b.md".into(), 1),
+            ("b.md".into(), 3)
+        ])
+    );
+
+    // Files can be checked in parallel, the broken links being yielded in the same order
+    let parallel = runtime.block_on(async {
+        check_broken_links_streaming(
+            &dir,
+            CheckerOptions {
+                parallel: true,
+                ..CheckerOptions::default()
+            },
+        )
+        .map(|result| result.map(|error| (error.file.file_name().unwrap().to_owned(), error.line)))
+        .collect::<Result<Vec<_>, _>>()
+        .await
+    });
+
+    assert_eq!(parallel, lines);
+}
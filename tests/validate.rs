@@ -0,0 +1,125 @@
+//! Integration tests of the validation of individual links
+
+mod common;
+
+use broken_md_links::validate::{
+    missing_target_reason, validate_fragment_link, validate_header_link, ValidationResult,
+};
+use broken_md_links::{check_link_fragment, FileLinksCache, Fragment, SlugOptions};
+use common::TempDir;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn link_fragments_are_checked() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("doc.md"),
+        "# Installation\n\n<a id=\"Step_1\"></a>\n",
+    )
+    .unwrap();
+
+    let mut cache = FileLinksCache::new();
+
+    assert_eq!(
+        check_link_fragment(
+            &dir.join("doc.md"),
+            &Fragment::parse("installation"),
+            &mut cache
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        check_link_fragment(&dir.join("doc.md"), &Fragment::parse("Step_1"), &mut cache),
+        Ok(())
+    );
+    assert_eq!(
+        check_link_fragment(&dir.join("doc.md"), &Fragment::LineNumber(3), &mut cache),
+        Ok(())
+    );
+    assert!(
+        check_link_fragment(&dir.join("doc.md"), &Fragment::parse("usage"), &mut cache).is_err()
+    );
+    assert!(check_link_fragment(
+        &dir.join("missing.md"),
+        &Fragment::parse("usage"),
+        &mut cache
+    )
+    .is_err());
+}
+
+#[test]
+fn dead_symbolic_links_are_described() {
+    assert_eq!(
+        missing_target_reason(Path::new("docs/../missing.md")),
+        "path 'missing.md' does not exist"
+    );
+
+    let dir = TempDir::new();
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink("missing-file.md", dir.join("link.md")).unwrap();
+
+        assert_eq!(
+            missing_target_reason(&dir.join("link.md")),
+            format!(
+                "path '{}' is a dead symlink to 'missing-file.md'",
+                dir.join("link.md").display()
+            )
+        );
+    }
+}
+
+#[test]
+fn header_links_are_validated() {
+    let dir = TempDir::new();
+    let target = dir.join("doc.md");
+    fs::write(&target, "# Installation\n").unwrap();
+
+    let mut cache = FileLinksCache::new();
+
+    assert_eq!(
+        validate_header_link(&target, "installation", &mut cache),
+        ValidationResult::Valid
+    );
+    assert!(matches!(
+        validate_header_link(&target, "usage", &mut cache),
+        ValidationResult::Broken(_)
+    ));
+    assert!(matches!(
+        validate_header_link(&dir, "usage", &mut cache),
+        ValidationResult::Broken(_)
+    ));
+}
+
+#[test]
+fn fragment_links_are_validated() {
+    let dir = TempDir::new();
+    let target = dir.join("doc.md");
+    fs::write(
+        &target,
+        "# Installation\n\n<a id=\"Step_1\"></a>\n\n<a name=\"legacy\"></a>\n",
+    )
+    .unwrap();
+
+    let validate = |fragment| {
+        validate_fragment_link(
+            &target,
+            &Fragment::parse(fragment),
+            &SlugOptions::default(),
+            &mut FileLinksCache::new(),
+        )
+    };
+
+    assert_eq!(validate("installation"), ValidationResult::Valid);
+    assert_eq!(validate("legacy"), ValidationResult::Valid);
+    assert_eq!(validate("Step_1"), ValidationResult::Valid);
+    assert_eq!(validate("L5"), ValidationResult::Valid);
+    assert!(matches!(validate("usage"), ValidationResult::Broken(_)));
+    assert!(matches!(validate("Step_2"), ValidationResult::Broken(_)));
+    assert!(matches!(validate("L6"), ValidationResult::Broken(_)));
+    assert!(matches!(
+        validate("some header"),
+        ValidationResult::Warning(_)
+    ));
+}
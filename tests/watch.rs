@@ -0,0 +1,174 @@
+//! Integration tests of the watch mode, run against temporary directories
+//!
+//! The checker is stopped like with Ctrl-C, so these tests only run on Unix.
+
+#![cfg(all(unix, feature = "watch"))]
+
+mod common;
+
+use common::TempDir;
+use regex::Regex;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::channel;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Time to wait for the checker to notice a change and check the changed files again
+const SETTLE_TIME: Duration = Duration::from_millis(1500);
+
+/// Create an empty git repository in the temporary directory, so `.gitignore` files apply
+fn repository() -> TempDir {
+    let dir = TempDir::new();
+
+    assert!(Command::new("git")
+        .current_dir(&dir)
+        .args(["init", "--quiet"])
+        .status()
+        .unwrap()
+        .success());
+
+    dir
+}
+
+/// Watch a directory, apply each change then wait for it to be checked, and get the lines written by the checker
+fn watch(dir: &Path, args: &[&str], changes: &[&dyn Fn()]) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_broken-md-links"))
+        .current_dir(dir)
+        .args([".", "-r", "--watch", "-v", "info"])
+        .args(args)
+        .env_remove("GITHUB_ACTIONS")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let (sender, receiver) = channel();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+
+    let reader = std::thread::spawn(move || {
+        for line in stdout.lines() {
+            let _ = sender.send(line.unwrap());
+        }
+    });
+
+    let mut lines = vec![];
+
+    loop {
+        let line = match receiver.recv_timeout(Duration::from_secs(30)) {
+            Ok(line) => line,
+            Err(_) => {
+                let _ = child.kill();
+                panic!("The checker didn't start watching: {:#?}", lines);
+            }
+        };
+
+        let watching = line.contains("Watching");
+        lines.push(line);
+
+        if watching {
+            break;
+        }
+    }
+
+    for change in changes {
+        change();
+        sleep(SETTLE_TIME);
+    }
+
+    assert!(Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap()
+        .success());
+
+    child.wait().unwrap();
+    reader.join().unwrap();
+
+    lines.extend(receiver.try_iter());
+    lines
+}
+
+/// Get the runs of the checker after a change, with the number of files they checked again
+fn runs(lines: &[String]) -> Vec<String> {
+    let run = Regex::new(r"^\[\d{2}:\d{2}:\d{2} UTC\] (Checking \d+ changed files? again\.\.\.)$")
+        .unwrap();
+
+    lines
+        .iter()
+        .filter_map(|line| run.captures(line).map(|captures| captures[1].to_owned()))
+        .collect()
+}
+
+#[test]
+fn only_checked_files_are_checked_again() {
+    let dir = repository();
+
+    fs::create_dir_all(dir.join("gen")).unwrap();
+    fs::write(dir.join(".gitignore"), "ignored.md\n").unwrap();
+    fs::write(dir.join("README.md"), "# Title\n").unwrap();
+
+    let lines = watch(
+        &dir,
+        &["--targets-only", "gen/"],
+        &[
+            // Ignored and target-only files are not checked again
+            &|| {
+                fs::write(dir.join("ignored.md"), "[Missing](missing.md)\n").unwrap();
+                fs::write(dir.join("gen/api.md"), "[Missing](missing.md)\n").unwrap();
+                fs::write(dir.join("notes.txt"), "[Missing](missing.md)\n").unwrap();
+            },
+            &|| fs::write(dir.join("README.md"), "[Missing](missing.md)\n").unwrap(),
+        ],
+    );
+
+    assert_eq!(
+        runs(&lines),
+        ["Checking 1 changed file again..."],
+        "{:#?}",
+        lines
+    );
+    assert!(lines.contains(&"Found 1 broken link in the changed files.".to_owned()));
+    assert!(lines.contains(&"Stopped after 2 runs, which found 1 broken link in total.".to_owned()));
+}
+
+#[test]
+fn changes_in_quick_succession_are_checked_once() {
+    let dir = repository();
+
+    fs::write(dir.join("README.md"), "# Title\n").unwrap();
+    fs::write(dir.join("lib.rs"), "//! Library\n").unwrap();
+
+    let lines = watch(
+        &dir,
+        &["--rust-doc-comments"],
+        &[
+            &|| {
+                for i in 0..5 {
+                    fs::write(
+                        dir.join("README.md"),
+                        format!("[Missing](missing-{}.md)\n", i),
+                    )
+                    .unwrap();
+                }
+            },
+            // Files other than Markdown ones are checked again if they are checked
+            &|| fs::write(dir.join("lib.rs"), "//! [Missing](missing.md)\n").unwrap(),
+        ],
+    );
+
+    assert_eq!(
+        runs(&lines),
+        [
+            "Checking 1 changed file again...",
+            "Checking 1 changed file again..."
+        ],
+        "{:#?}",
+        lines
+    );
+
+    assert!(
+        lines.contains(&"Stopped after 3 runs, which found 2 broken links in total.".to_owned())
+    );
+}